@@ -19,6 +19,18 @@ error! {
         #[formatted_message("I was not able to read the command-line propery.\n{0}")]
         #[help = "See the command-line usage with `weld --help`."]
         CommandLine(String),
+
+        #[code = E016]
+        #[message = "The target triple is not recognized."]
+        #[formatted_message("`{0}` is not a recognized target triple.")]
+        #[help = "See the supported targets with `weld --print-targets`."]
+        InvalidTarget(String),
+
+        #[code = E025]
+        #[message = "A response file includes itself, directly or through other response files."]
+        #[formatted_message("Response file inclusion cycle detected: {0}.")]
+        #[help = "Remove one of the `@<file>` arguments of the cycle."]
+        ResponseFileCycle(String),
     }
 }
 
@@ -47,4 +59,8 @@ impl Error {
     pub(crate) fn explain(error_code: &str) -> Result<&'static str, WeldError> {
         WeldError::explain(error_code)
     }
+
+    pub(crate) fn list() -> impl Iterator<Item = (&'static str, &'static str)> {
+        WeldError::list()
+    }
 }
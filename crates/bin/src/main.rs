@@ -8,6 +8,7 @@ mod error;
 use std::{
     env,
     ffi::OsString,
+    fs,
     path::{Path, PathBuf},
     process,
 };
@@ -15,25 +16,146 @@ use std::{
 use argh::FromArgs;
 use error::Error;
 use weld_errors::Result;
-use weld_linker::{target::Triple, Configuration};
+use weld_linker::{target::Triple, Configuration, Linker, SortSection, SymbolValue};
 
 fn default_output_file() -> PathBuf {
     PathBuf::from("a.out")
 }
 
+/// Parse an address, either in hexadecimal if prefixed by `0x`, or in decimal.
+fn parse_address(value: &str) -> Result<u64, String> {
+    match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hexadecimal) => u64::from_str_radix(hexadecimal, 16),
+        None => value.parse(),
+    }
+    .map_err(|_| format!("`{value}` is not a valid address"))
+}
+
+/// Parse a section start, of the form `name=address`.
+fn parse_section_start(value: &str) -> Result<(String, u64), String> {
+    let (name, address) = value
+        .split_once('=')
+        .ok_or_else(|| format!("`{value}` is not of the form `name=address`"))?;
+
+    Ok((name.to_owned(), parse_address(address)?))
+}
+
+/// Parse a symbol definition, of the form `name=value`, where `value` is an
+/// address or another symbol name.
+fn parse_defsym(value: &str) -> Result<(String, SymbolValue), String> {
+    let (name, symbol_value) = value
+        .split_once('=')
+        .ok_or_else(|| format!("`{value}` is not of the form `name=value`"))?;
+
+    Ok((name.to_owned(), symbol_value.parse()?))
+}
+
+/// Expand the response files, i.e. the arguments of the form `@<file>`, into
+/// the arguments written in `<file>`, recursively. An argument whose file
+/// cannot be read is kept as is.
+///
+/// `including` holds the response files being expanded, in order of
+/// inclusion: a response file including one of them is a cycle.
+fn expand_response_files(
+    arguments: &[String],
+    including: &mut Vec<(PathBuf, String)>,
+) -> Result<Vec<String>, Error> {
+    let mut expanded = Vec::with_capacity(arguments.len());
+
+    for argument in arguments {
+        let Some(path) = argument.strip_prefix('@') else {
+            expanded.push(argument.clone());
+
+            continue;
+        };
+
+        let Ok(content) = fs::read_to_string(path) else {
+            expanded.push(argument.clone());
+
+            continue;
+        };
+
+        // The same file can be named by different paths.
+        let identity = fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+
+        if let Some(start) = including.iter().position(|(included, _)| *included == identity) {
+            let cycle = including[start..]
+                .iter()
+                .map(|(_, name)| name.as_str())
+                .chain([path])
+                .collect::<Vec<_>>()
+                .join(" → ");
+
+            return Err(Error::ResponseFileCycle(cycle));
+        }
+
+        including.push((identity, path.to_owned()));
+        expanded.extend(expand_response_files(&split_response_file(&content), including)?);
+        including.pop();
+    }
+
+    Ok(expanded)
+}
+
+/// Split the content of a response file into arguments. Arguments are
+/// separated by whitespaces, unless they are quoted with `'` or `"`, and `\`
+/// escapes the next character.
+fn split_response_file(content: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut argument = None::<String>;
+    let mut quote = None;
+    let mut characters = content.chars();
+
+    while let Some(character) = characters.next() {
+        match (quote, character) {
+            (_, '\\') => {
+                if let Some(escaped) = characters.next() {
+                    argument.get_or_insert_with(String::new).push(escaped);
+                }
+            }
+            (Some(opening_quote), _) if character == opening_quote => quote = None,
+            (Some(_), _) => argument.get_or_insert_with(String::new).push(character),
+            (None, '\'' | '"') => {
+                quote = Some(character);
+                argument.get_or_insert_with(String::new);
+            }
+            (None, _) if character.is_whitespace() => arguments.extend(argument.take()),
+            (None, _) => argument.get_or_insert_with(String::new).push(character),
+        }
+    }
+
+    arguments.extend(argument);
+
+    arguments
+}
+
 /// The `weld` command is an experimental linker: it combines several object
 /// files and libraries, resolves symbols, and produces an output file.
+/// Arguments can be read from a response file with `@<file>`.
 #[derive(Debug, FromArgs)]
 struct Weld {
-    /// explain a particular error based on its code (of kind `E...`).
+    /// explain a particular error based on its code (of kind `E...`), or
+    /// list all errors with `all`.
     #[argh(option)]
     explain: Option<String>,
 
-    /// target triple.
-    #[argh(option, short = 't', default = "Triple::host()")]
-    target: Triple,
+    /// list all errors, same as `--explain all`.
+    #[argh(switch)]
+    list_errors: bool,
 
-    /// input files.
+    /// print the version and the enabled features.
+    #[argh(switch)]
+    version: bool,
+
+    /// print the list of supported targets.
+    #[argh(switch)]
+    print_targets: bool,
+
+    /// target triple. If not specified, the host is the target.
+    #[argh(option, short = 't')]
+    target: Option<String>,
+
+    /// input files: object files, or archives whose needed members are linked.
     #[argh(positional)]
     input_files: Vec<PathBuf>,
 
@@ -41,6 +163,126 @@ struct Weld {
     /// `a.out` is used.
     #[argh(option, short = 'o', default = "default_output_file()")]
     output_file: PathBuf,
+
+    /// keep the relocation sections in the output.
+    #[argh(switch)]
+    emit_relocs: bool,
+
+    /// produce a relocatable object file, which can be linked again, instead
+    /// of an executable.
+    #[argh(switch, short = 'r')]
+    relocatable: bool,
+
+    /// virtual address of the first loadable segment, e.g. `0x400000`.
+    #[argh(option, from_str_fn(parse_address))]
+    image_base: Option<u64>,
+
+    /// pad the output file to a multiple of this power of two, e.g. `4096`.
+    #[argh(option, from_str_fn(parse_address))]
+    file_align: Option<u64>,
+
+    /// align the loadable segments to this page size, e.g. `0x10000`. If not
+    /// specified, the page size of the target is used.
+    #[argh(option, from_str_fn(parse_address))]
+    max_page_size: Option<u64>,
+
+    /// place a section at a fixed virtual address, e.g. `.text=0x8000000`.
+    /// Can be repeated.
+    #[argh(option, from_str_fn(parse_section_start))]
+    section_start: Vec<(String, u64)>,
+
+    /// define a global symbol, e.g. `origin=0x8000000` for an absolute
+    /// symbol, or `alias=symbol` for an alias. Can be repeated.
+    /// `--defsym=<name>=<value>` is accepted too.
+    #[argh(option, from_str_fn(parse_defsym))]
+    defsym: Vec<(String, SymbolValue)>,
+
+    /// order the input sections inside each output section, by `name` or by
+    /// `alignment`.
+    #[argh(option)]
+    sort_section: Option<SortSection>,
+
+    /// remove the debug sections from the output.
+    #[argh(switch)]
+    strip_debug: bool,
+
+    /// remove the unused input sections from the output.
+    #[argh(switch)]
+    gc_sections: bool,
+
+    /// report the input sections removed by `--gc-sections`.
+    #[argh(switch)]
+    print_gc_sections: bool,
+
+    /// redirect the undefined references to `<name>` to `__wrap_<name>`, and
+    /// the ones to `__real_<name>` to `<name>`. Can be repeated.
+    /// `--wrap=<name>` is accepted too.
+    #[argh(option)]
+    wrap: Vec<String>,
+
+    /// report every input file defining or referencing the symbol `<name>`,
+    /// and the definition it resolves to. Can be repeated. `-y <name>` and
+    /// `--trace-symbol=<name>` are accepted too.
+    #[argh(option, short = 'y')]
+    trace_symbol: Vec<String>,
+
+    /// keep only the global symbols listed in this file, one per line, in
+    /// the output symbol table.
+    #[argh(option)]
+    retain_symbols_file: Option<PathBuf>,
+
+    /// produce a dynamically-linked executable.
+    #[argh(switch)]
+    dynamic: bool,
+
+    /// path of the dynamic linker, e.g. `/lib64/ld-linux-x86-64.so.2`. If not
+    /// specified, the dynamic linker of the target is used.
+    #[argh(option)]
+    dynamic_linker: Option<PathBuf>,
+
+    /// produce a fully-static executable: no dynamic linker, all used symbols
+    /// must be defined, and `-l` links archives. `-static` is an alias.
+    #[argh(switch, long = "static")]
+    static_link: bool,
+
+    /// produce a shared object. `-shared` is an alias.
+    #[argh(switch)]
+    shared: bool,
+
+    /// name of the shared object, written as `DT_SONAME`, e.g.
+    /// `libfoo.so.1`. `-soname` is an alias.
+    #[argh(option)]
+    soname: Option<String>,
+
+    /// link against the shared library `lib<name>.so`, or the archive
+    /// `lib<name>.a` with `--static`, e.g. `-l c` or `-lc`. Can be repeated.
+    #[argh(option, short = 'l')]
+    library: Vec<String>,
+
+    /// add a directory where the libraries are searched, e.g. `-L /usr/lib`
+    /// or `-L/usr/lib`. Can be repeated.
+    #[argh(option, short = 'L')]
+    library_path: Vec<PathBuf>,
+
+    /// add directories where the shared libraries are searched at runtime,
+    /// separated by `:`. Can be repeated. `-rpath` is an alias.
+    #[argh(option)]
+    rpath: Vec<String>,
+
+    /// write the runtime search paths as the legacy `DT_RPATH` instead of
+    /// `DT_RUNPATH`. `--enable-new-dtags=no` is an alias.
+    #[argh(switch)]
+    disable_new_dtags: bool,
+
+    /// ignore all warnings. The reports of `--print-gc-sections` and
+    /// `--trace-symbol` are kept.
+    #[argh(switch)]
+    no_warnings: bool,
+
+    /// treat warnings as errors, but not the reports of `--print-gc-sections`
+    /// and `--trace-symbol`. `-Werror` is an alias.
+    #[argh(switch)]
+    fatal_warnings: bool,
 }
 
 impl Weld {
@@ -63,9 +305,39 @@ impl Weld {
             .and_then(|file_name| file_name.to_str())
             .unwrap_or(&arguments[0]);
 
-        // Extract all arguments.
-        let arguments =
-            arguments.iter().skip(1).map(|argument| argument.as_str()).collect::<Vec<_>>();
+        // Expand the response files.
+        let arguments = expand_response_files(&arguments[1..], &mut Vec::new())?;
+
+        // Extract all arguments, map the aliases to their long form, and split
+        // the short options glued to their value, like `-lc`.
+        let arguments = arguments
+            .iter()
+            .flat_map(|argument| match argument.as_str() {
+                "-Werror" => vec!["--fatal-warnings"],
+                "-static" => vec!["--static"],
+                "-shared" => vec!["--shared"],
+                "-soname" => vec!["--soname"],
+                "-rpath" => vec!["--rpath"],
+                "--enable-new-dtags=no" => vec!["--disable-new-dtags"],
+                "--enable-new-dtags" | "--enable-new-dtags=yes" => vec![],
+                argument if argument.starts_with("--defsym=") => {
+                    vec!["--defsym", &argument["--defsym=".len()..]]
+                }
+                argument if argument.starts_with("--wrap=") => {
+                    vec!["--wrap", &argument["--wrap=".len()..]]
+                }
+                argument if argument.starts_with("--trace-symbol=") => {
+                    vec!["--trace-symbol", &argument["--trace-symbol=".len()..]]
+                }
+                argument if argument.len() > 2 && argument.starts_with("-l") => {
+                    vec!["--library", &argument[2..]]
+                }
+                argument if argument.len() > 2 && argument.starts_with("-L") => {
+                    vec!["--library-path", &argument[2..]]
+                }
+                argument => vec![argument],
+            })
+            .collect::<Vec<_>>();
 
         // Parse and build `Self`.
         match Weld::from_args(&[command], &arguments) {
@@ -93,6 +365,15 @@ fn main() -> Result<()> {
     // Build the command-line arguments.
     let weld = Weld::new()?;
 
+    // Handle the `--list-errors` and `--explain all` options.
+    if weld.list_errors || weld.explain.as_deref() == Some("all") {
+        for (error_code, title) in Error::list() {
+            println!("{error_code}: {title}");
+        }
+
+        return Ok(());
+    }
+
     // Handle the `--explain` option.
     if let Some(error_code) = weld.explain {
         println!("{}", Error::explain(&error_code)?);
@@ -100,11 +381,107 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Handle the `--version` option.
+    if weld.version {
+        println!("weld {}", env!("CARGO_PKG_VERSION"));
+        println!("features: {}", weld_linker::features().join(", "));
+
+        return Ok(());
+    }
+
+    // Handle the `--print-targets` option.
+    if weld.print_targets {
+        for target in Linker::supported_targets() {
+            println!("{target}");
+        }
+
+        return Ok(());
+    }
+
+    // Parse the target triple.
+    let target = match weld.target {
+        Some(target) => target.parse::<Triple>().map_err(|_| Error::InvalidTarget(target))?,
+        None => Triple::host(),
+    };
+
     // Configure and create the linker.
-    let linker = Configuration::new(weld.target, weld.input_files, weld.output_file).linker();
+    let mut configuration = Configuration::new(target, weld.input_files, weld.output_file)
+        .emit_relocations(weld.emit_relocs)
+        .relocatable(weld.relocatable)
+        .strip_debug(weld.strip_debug)
+        .gc_sections(weld.gc_sections)
+        .print_gc_sections(weld.print_gc_sections)
+        .dynamic(weld.dynamic)
+        .static_link(weld.static_link)
+        .shared(weld.shared)
+        .new_dtags(!weld.disable_new_dtags)
+        .no_warnings(weld.no_warnings)
+        .fatal_warnings(weld.fatal_warnings);
+
+    for library in weld.library {
+        configuration = configuration.library(library);
+    }
+
+    for library_path in weld.library_path {
+        configuration = configuration.library_path(library_path);
+    }
+
+    for rpath in weld.rpath {
+        configuration = configuration.rpath(rpath);
+    }
+
+    for wrap in weld.wrap {
+        configuration = configuration.wrap(wrap);
+    }
+
+    for trace_symbol in weld.trace_symbol {
+        configuration = configuration.trace_symbol(trace_symbol);
+    }
+
+    if let Some(retain_symbols_file) = weld.retain_symbols_file {
+        configuration = configuration.retain_symbols_file(retain_symbols_file);
+    }
+
+    if let Some(soname) = weld.soname {
+        configuration = configuration.soname(soname);
+    }
+
+    if let Some(dynamic_linker) = weld.dynamic_linker {
+        configuration = configuration.dynamic_linker(dynamic_linker);
+    }
+
+    if let Some(image_base) = weld.image_base {
+        configuration = configuration.image_base(image_base);
+    }
+
+    if let Some(file_align) = weld.file_align {
+        configuration = configuration.file_align(file_align);
+    }
+
+    if let Some(max_page_size) = weld.max_page_size {
+        configuration = configuration.max_page_size(max_page_size);
+    }
+
+    for (name, address) in weld.section_start {
+        configuration = configuration.section_start(name, address);
+    }
+
+    for (name, value) in weld.defsym {
+        configuration = configuration.defsym(name, value);
+    }
+
+    if let Some(sort_section) = weld.sort_section {
+        configuration = configuration.sort_section(sort_section);
+    }
+
+    let linker = configuration.linker();
 
     // Take a deep breath, and here we are!
-    linker.link()?;
+    let warnings = linker.link()?;
+
+    for warning in warnings {
+        eprintln!("{:?}", miette::Report::new(warning));
+    }
 
     Ok(())
 }
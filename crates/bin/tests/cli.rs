@@ -0,0 +1,242 @@
+//! Command-line tests: run the `weld` executable, and check its output.
+
+use std::process::{Command, Output};
+
+/// Run `weld` with `arguments`.
+fn weld(arguments: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_weld")).args(arguments).output().unwrap()
+}
+
+#[test]
+fn test_print_targets() {
+    let output = weld(&["--print-targets"]);
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.lines().any(|line| line == "x86_64-unknown-linux-gnu"));
+}
+
+#[test]
+fn test_version() {
+    // The input file doesn't exist: no link must be attempted.
+    let output = weld(&["--version", "missing.o"]);
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.starts_with(concat!("weld ", env!("CARGO_PKG_VERSION"), "\n")));
+    assert!(stdout.contains("elf64"));
+}
+
+#[test]
+fn test_invalid_target() {
+    let output = weld(&["--target", "nonsense", "main.o"]);
+
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(stderr.contains("`nonsense` is not a recognized target triple."));
+    assert!(stderr.contains("weld --print-targets"));
+}
+
+#[test]
+fn test_list_errors() {
+    let (last_error_code, _) = weld_errors::DIAGNOSTICS.last().unwrap();
+
+    for arguments in [&["--list-errors"][..], &["--explain", "all"]] {
+        let output = weld(arguments);
+
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+
+        assert!(stdout.starts_with("E000: "));
+        assert!(stdout.contains(&format!("{last_error_code}: ")));
+        assert_eq!(stdout.lines().count(), weld_errors::DIAGNOSTICS.len());
+    }
+}
+
+#[test]
+fn test_glued_library_options() {
+    let output = weld(&[
+        "../linker/tests/fixtures/start_elf_amd64.o",
+        "../linker/tests/fixtures/exit_elf_amd64.o",
+        "-lmissing",
+        "-L../linker/tests/fixtures",
+    ]);
+
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(stderr.contains("`libmissing.so`"));
+}
+
+#[test]
+fn test_print_gc_sections() {
+    let output_file = std::env::temp_dir().join("weld_test_print_gc_sections");
+    let output = weld(&[
+        "../linker/tests/fixtures/gc_elf_amd64.o",
+        "--gc-sections",
+        "--print-gc-sections",
+        "-o",
+        output_file.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(stderr.lines().any(|line| line.contains("`.text.unused`")));
+    assert!(!stderr.contains("`.text.used`"));
+
+    std::fs::remove_file(output_file).unwrap();
+}
+
+#[test]
+fn test_wrap() {
+    let output_file = std::env::temp_dir().join("weld_test_wrap_cli");
+    let link = |wrap: &[&str]| {
+        let mut arguments = vec![
+            "../linker/tests/fixtures/call_foo_elf_amd64.o",
+            "../linker/tests/fixtures/foo_elf_amd64.o",
+            "../linker/tests/fixtures/wrap_foo_elf_amd64.o",
+            "-o",
+            output_file.to_str().unwrap(),
+        ];
+        arguments.extend(wrap);
+
+        weld(&arguments)
+    };
+
+    assert!(link(&["--wrap", "foo"]).status.success());
+    assert!(link(&["--wrap=foo"]).status.success());
+
+    // Without wrapping, `__real_foo` is undefined.
+    let output = link(&["--static"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("`__real_foo`"));
+
+    std::fs::remove_file(output_file).unwrap();
+}
+
+#[test]
+fn test_trace_symbol() {
+    let output_file = std::env::temp_dir().join("weld_test_trace_symbol_cli");
+
+    // The trace is a report: it's neither ignored, nor treated as an error.
+    for trace_symbol in [
+        &["--trace-symbol", "foo"][..],
+        &["--trace-symbol=foo"],
+        &["-y", "foo"],
+        &["--trace-symbol", "foo", "--fatal-warnings"],
+        &["--trace-symbol", "foo", "--no-warnings"],
+    ] {
+        let mut arguments = vec![
+            "../linker/tests/fixtures/call_foo_elf_amd64.o",
+            "../linker/tests/fixtures/foo_elf_amd64.o",
+            "-o",
+            output_file.to_str().unwrap(),
+        ];
+        arguments.extend(trace_symbol);
+
+        let output = weld(&arguments);
+
+        assert!(output.status.success());
+
+        let stderr = String::from_utf8(output.stderr).unwrap();
+
+        assert!(stderr.contains("is referenced by the input file"));
+        assert!(stderr.contains("is defined by the input file"));
+        assert!(stderr.contains("resolves to the definition of the input file"));
+    }
+
+    std::fs::remove_file(output_file).unwrap();
+}
+
+#[test]
+fn test_defsym() {
+    let output_file = std::env::temp_dir().join("weld_test_defsym_cli");
+    let link = |defsym: &[&str]| {
+        let mut arguments = vec![
+            "../linker/tests/fixtures/defsym_elf_amd64.o",
+            "-o",
+            output_file.to_str().unwrap(),
+        ];
+        arguments.extend(defsym);
+
+        weld(&arguments)
+    };
+
+    assert!(link(&["--defsym", "origin=0x1000"]).status.success());
+    assert!(link(&["--defsym=origin=4096"]).status.success());
+
+    let output = link(&["--defsym", "origin=0xzz"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("`0xzz` is not a valid"));
+
+    std::fs::remove_file(output_file).unwrap();
+}
+
+#[test]
+fn test_response_files() {
+    let directory = std::env::temp_dir();
+    let output_file = directory.join("weld_test_response_files_cli");
+    let response_file = directory.join("weld_test_response_files.txt");
+    let nested_response_file = directory.join("weld_test_response_files_nested.txt");
+
+    std::fs::write(
+        &response_file,
+        format!(
+            "../linker/tests/fixtures/start_elf_amd64.o\n@{} -o '{}'\n",
+            nested_response_file.display(),
+            output_file.display(),
+        ),
+    )
+    .unwrap();
+    std::fs::write(&nested_response_file, "\"../linker/tests/fixtures/exit_elf_amd64.o\"").unwrap();
+
+    let output = weld(&[&format!("@{}", response_file.display())]);
+
+    assert!(output.status.success());
+    assert!(output_file.exists());
+
+    std::fs::remove_file(output_file).unwrap();
+    std::fs::remove_file(response_file).unwrap();
+    std::fs::remove_file(nested_response_file).unwrap();
+}
+
+#[test]
+fn test_response_file_cycle() {
+    let directory = std::env::temp_dir();
+    let a = directory.join("weld_test_response_file_cycle_a.txt");
+    let b = directory.join("weld_test_response_file_cycle_b.txt");
+
+    std::fs::write(&a, format!("main.o @{}", b.display())).unwrap();
+    std::fs::write(&b, format!("@{}", a.display())).unwrap();
+
+    let output = weld(&[&format!("@{}", a.display())]);
+
+    assert!(!output.status.success());
+
+    // The message may be wrapped, even inside a path: compare without the
+    // whitespaces.
+    let without_whitespaces =
+        |text: &str| text.chars().filter(|c| !c.is_whitespace() && *c != '│').collect::<String>();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(without_whitespaces(&stderr).contains(&without_whitespaces(&format!(
+        "Response file inclusion cycle detected: {a} → {b} → {a}.",
+        a = a.display(),
+        b = b.display(),
+    ))));
+
+    std::fs::remove_file(a).unwrap();
+    std::fs::remove_file(b).unwrap();
+}
@@ -0,0 +1,105 @@
+//! `weld-counting-allocator` provides a global allocator which counts the
+//! allocations, to test the memory usage of the other crates. It's a
+//! development dependency only.
+//!
+//! A test binary installs it with:
+//!
+//! ```rust
+//! use weld_counting_allocator::{count_allocations, CountingAllocator};
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: CountingAllocator = CountingAllocator;
+//!
+//! fn main() {
+//!     let (_, allocations) = count_allocations(|| vec![1, 2, 3]);
+//!
+//!     assert_eq!(allocations, 1);
+//! }
+//! ```
+//!
+//! Then, [`count_allocations`] counts the allocations performed by a closure
+//! on the current thread, and [`peak_allocated`] measures the peak of the
+//! memory allocated by a closure, on all threads.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// An allocator counting the allocations per thread, and the allocated bytes
+/// along with their peak for all threads.
+pub struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Count an allocation of `size` bytes. The allocation may happen while the
+/// thread is being destroyed, in which case it is not counted for the thread.
+fn count_allocation(size: usize) {
+    let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+
+    let allocated = ALLOCATED.fetch_add(size, Ordering::SeqCst) + size;
+    PEAK.fetch_max(allocated, Ordering::SeqCst);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_allocation(layout.size());
+
+        // SAFETY: Same contract as `GlobalAlloc::alloc`.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+
+        // SAFETY: Same contract as `GlobalAlloc::dealloc`.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+        count_allocation(new_size);
+
+        // SAFETY: Same contract as `GlobalAlloc::realloc`.
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+/// Run `f`, and count the allocations it performs on the current thread. A
+/// reallocation counts as an allocation.
+///
+/// Tests run in parallel, so the allocations of the other threads are not
+/// counted.
+pub fn count_allocations<F, R>(f: F) -> (R, usize)
+where
+    F: FnOnce() -> R,
+{
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let after = ALLOCATIONS.with(Cell::get);
+
+    (result, after - before)
+}
+
+/// Run `f`, and measure the peak of the memory it allocates, in bytes.
+///
+/// The memory allocated by all threads is measured, e.g. by a thread pool
+/// used by `f`, so the tests using it must not run in parallel with other
+/// tests.
+pub fn peak_allocated<F, R>(f: F) -> (R, usize)
+where
+    F: FnOnce() -> R,
+{
+    let before = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(before, Ordering::SeqCst);
+
+    let result = f();
+
+    (result, PEAK.load(Ordering::SeqCst) - before)
+}
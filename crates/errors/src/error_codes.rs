@@ -45,4 +45,7 @@ macro_rules! register_diagnostics {
     };
 }
 
-register_diagnostics!(E000, E001, E002, E003);
+register_diagnostics!(
+    E000, E001, E002, E003, E004, E005, E006, E007, E008, E009, E010, E011, E012, E013, E014, E015,
+    E016, E017, E018, E019, E020, E021, E022, E023, E024, E025, E026, E027, E028, E029
+);
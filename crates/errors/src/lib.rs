@@ -101,6 +101,8 @@ macro_rules! link_error_code {
 ///
 /// * `#[cfg(…)]` (optional),
 /// * `#[code = E...]` to define the error code (optional),
+/// * `#[severity = Warning]` to define the severity of the diagnostic, see
+///   [`miette::Severity`] (optional, `Error` by default),
 /// * `#[message = "…"]` to define a literal string message; it will be used as
 ///   documentation.
 /// * `#[formatted_message("format {0} {}", .0.accessor)]` to define a “dynamic”
@@ -134,6 +136,7 @@ macro_rules! error {
         [ $( $accumulator:tt )* ]
         $( #[cfg( $cfg:meta )] )*
         $( #[code = $error_code:ident] )?
+        $( #[severity = $severity:ident] )?
         #[message = $error_message:expr]
         #[help = $error_help:literal]
         $( $tail:tt )*
@@ -152,6 +155,7 @@ macro_rules! error {
                 #[error($error_message)]
                 #[diagnostic(
                     $( code($error_code), )?
+                    $( severity($severity), )?
                     help($error_help),
                 )]
                 $( #[cfg( $cfg )] )*
@@ -167,6 +171,7 @@ macro_rules! error {
         [ $( $accumulator:tt )* ]
         $( #[cfg( $cfg:meta )] )*
         $( #[code = $error_code:ident] )?
+        $( #[severity = $severity:ident] )?
         #[message = $error_message:expr]
         #[formatted_message( $error_message_format:literal $( , . $error_message_arguments:expr )* $( , )* )]
         #[help = $error_help:literal]
@@ -183,9 +188,10 @@ macro_rules! error {
                     #[doc = "\n"]
                 )?
                 #[doc = $error_message]
-                #[error( $error_message_format $( , . $error_message_arguments ),* )]
+                #[error( $error_message_format $( , . $error_message_arguments )* )]
                 #[diagnostic(
                     $( code($error_code), )?
+                    $( severity($severity), )?
                     help($error_help),
                 )]
                 $( #[cfg( $cfg )] )*
@@ -319,6 +325,11 @@ error! {
         #[help = "Did you mistype the error code? The pattern is `E[0-9]{{3}}`, i.e. an `E` followed by 3 digits, such as `E000`."]
         InvalidCode(String),
 
+        #[code = E000]
+        #[message = "The given error code is invalid, but it is close to a valid one."]
+        #[formatted_message("`{0}` is not a valid error code; did you mean `{1}`?")]
+        #[help = "The pattern is `E[0-9]{{3}}`, i.e. an `E` followed by 3 digits, such as `E000`."]
+        InvalidCodeWithSuggestion(String, &'static str),
     }
 }
 
@@ -326,6 +337,9 @@ impl Error {
     /// Given a specific error code, this method returns the associated
     /// diagnostic, if the error exists.
     ///
+    /// If the error code doesn't exist but is close to an existing one, the
+    /// latter is suggested.
+    ///
     /// ```
     /// use weld_errors::Error;
     ///
@@ -335,6 +349,12 @@ impl Error {
     ///
     /// // Explain an invalid error.
     /// assert!(Error::explain("oops").is_err());
+    ///
+    /// // Explain an invalid error, close to a valid one.
+    /// assert_eq!(
+    ///     Error::explain("E04").unwrap_err().to_string(),
+    ///     "`E04` is not a valid error code; did you mean `E004`?",
+    /// );
     /// # }
     /// ```
     #[cfg(feature = "diagnostics")]
@@ -350,6 +370,82 @@ impl Error {
                     }
                 },
             )
-            .ok_or(Self::InvalidCode(error_code.to_owned()))
+            .ok_or_else(|| match Self::suggest(error_code) {
+                Some(suggestion) => {
+                    Self::InvalidCodeWithSuggestion(error_code.to_owned(), suggestion)
+                }
+                None => Self::InvalidCode(error_code.to_owned()),
+            })
+    }
+
+    /// List all the error codes, along with their title, i.e. the first line
+    /// of their diagnostic.
+    ///
+    /// ```
+    /// use weld_errors::Error;
+    ///
+    /// # fn main() {
+    /// let (error_code, title) = Error::list().next().unwrap();
+    ///
+    /// assert_eq!(error_code, "E000");
+    /// assert!(!title.is_empty());
+    /// # }
+    /// ```
+    #[cfg(feature = "diagnostics")]
+    pub fn list() -> impl Iterator<Item = (&'static str, &'static str)> {
+        DIAGNOSTICS.iter().map(|(error_code, diagnostic)| {
+            let title = diagnostic
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty() && !line.starts_with('#'))
+                .unwrap_or_default();
+
+            (*error_code, title)
+        })
+    }
+
+    /// Find the existing error code that is the closest to `error_code`, if
+    /// it is close enough.
+    #[cfg(feature = "diagnostics")]
+    fn suggest(error_code: &str) -> Option<&'static str> {
+        /// Maximum edit distance for an error code to be suggested.
+        const MAXIMUM_DISTANCE: usize = 2;
+
+        let error_code = error_code.to_uppercase();
+        let mut suggestion = None;
+
+        for (current_error_code, _) in DIAGNOSTICS {
+            let distance = edit_distance(&error_code, current_error_code);
+
+            if distance <= MAXIMUM_DISTANCE
+                && suggestion.is_none_or(|(_, best_distance)| distance < best_distance)
+            {
+                suggestion = Some((*current_error_code, distance));
+            }
+        }
+
+        suggestion.map(|(error_code, _)| error_code)
     }
 }
+
+/// Compute the Levenshtein distance between two strings.
+#[cfg(feature = "diagnostics")]
+fn edit_distance(left: &str, right: &str) -> usize {
+    let right = right.chars().collect::<Vec<_>>();
+    let mut previous_row = (0..=right.len()).collect::<Vec<_>>();
+    let mut current_row = vec![0; right.len() + 1];
+
+    for (i, left_char) in left.chars().enumerate() {
+        current_row[0] = i + 1;
+
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous_row[j] + usize::from(left_char != *right_char);
+
+            current_row[j + 1] = substitution.min(previous_row[j + 1] + 1).min(current_row[j] + 1);
+        }
+
+        previous_row.clone_from(&current_row);
+    }
+
+    previous_row[right.len()]
+}
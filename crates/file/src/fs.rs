@@ -21,7 +21,13 @@ impl FileReader for File {
     where
         P: AsRef<Path>,
     {
-        Ok(Self { inner: fs::File::open(path)? })
+        let inner = fs::File::open(path)?;
+
+        if inner.metadata()?.len() == 0 {
+            return Err(empty_file_error());
+        }
+
+        Ok(Self { inner })
     }
 
     fn read_as_bytes(mut self) -> Self::Reader {
@@ -52,4 +58,12 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_file_empty() {
+        let error = File::open("tests/empty.txt").err().unwrap();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), empty_file_error().to_string());
+    }
 }
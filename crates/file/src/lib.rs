@@ -1,6 +1,11 @@
 //! `weld_file` is a thin crate to manipulate files.
 
-use std::{future::Future, io::Result, ops::Deref, path::Path};
+use std::{
+    future::Future,
+    io::{Error, ErrorKind, Result},
+    ops::Deref,
+    path::Path,
+};
 
 #[cfg(all(not(feature = "auto"), not(feature = "fs"), not(feature = "mmap")))]
 compile_error!("No feature has been selected, please select at least `auto`");
@@ -11,6 +16,20 @@ pub mod mmap;
 #[cfg(feature = "fs")]
 pub mod fs;
 
+/// The file pickers `weld_file` has been compiled with.
+pub const FEATURES: &[&str] = &[
+    #[cfg(feature = "fs")]
+    "fs",
+    #[cfg(feature = "mmap")]
+    "mmap",
+];
+
+/// Error returned by all file readers when a file is empty, so that the
+/// behavior doesn't depend on the selected file reader.
+fn empty_file_error() -> Error {
+    Error::new(ErrorKind::InvalidInput, "The file is empty")
+}
+
 /// Define what a file reader should look like.
 pub trait FileReader: Sized {
     /// The reader should outputs bytes that implements `Deref<[u8]>`.
@@ -1,7 +1,7 @@
 //! Memmory map file reader.
 
 use rustix::{
-    mm::{mmap, munmap, MapFlags, ProtFlags},
+    mm::{madvise, mmap, munmap, Advice, MapFlags, ProtFlags},
     param::page_size,
 };
 use std::{
@@ -19,11 +19,29 @@ pub struct Mmap {
     content: MmapContent,
 }
 
-impl FileReader for Mmap {
-    type Bytes = MmapContent;
-    type Reader = Ready<Result<Self::Bytes>>;
+impl Mmap {
+    /// Open a file like [`FileReader::open`], but prefault the whole mapping
+    /// up front, with `MAP_POPULATE`, instead of faulting pages one by one
+    /// while they are read.
+    ///
+    /// It is faster when the whole file is read, but slower when only a part
+    /// of it is, like the header. On platforms without `MAP_POPULATE`, it
+    /// behaves like [`FileReader::open`].
+    pub fn open_prefaulted<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let flags = MapFlags::SHARED | MapFlags::POPULATE;
 
-    fn open<P>(path: P) -> Result<Self>
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        let flags = MapFlags::SHARED;
+
+        Self::open_with_flags(path, flags)
+    }
+
+    /// Open a file with specific `mmap(2)` flags.
+    fn open_with_flags<P>(path: P, flags: MapFlags) -> Result<Self>
     where
         P: AsRef<Path>,
     {
@@ -38,22 +56,35 @@ impl FileReader for Mmap {
             })?;
 
             if length == 0 {
-                return Err(Error::new(
-                    ErrorKind::InvalidInput,
-                    "Memory map's length must not be zero",
-                ));
+                return Err(empty_file_error());
             }
 
             length
         };
 
         let pointer = unsafe {
-            mmap(ptr::null_mut(), length, ProtFlags::READ, MapFlags::SHARED, &file, 0)
+            mmap(ptr::null_mut(), length, ProtFlags::READ, flags, &file, 0)
                 .map_err(|errno| Error::from_raw_os_error(errno.raw_os_error()))?
         };
 
+        // Object files are parsed front-to-back. The advice is only a hint:
+        // platforms that don't support it are fine ignoring it.
+        let _ = unsafe { madvise(pointer, length, Advice::Sequential) };
+
         Ok(Self { content: MmapContent { _file: file, pointer, length } })
     }
+}
+
+impl FileReader for Mmap {
+    type Bytes = MmapContent;
+    type Reader = Ready<Result<Self::Bytes>>;
+
+    fn open<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::open_with_flags(path, MapFlags::SHARED)
+    }
 
     fn read_as_bytes(self) -> Self::Reader {
         ready(Ok(self.content))
@@ -105,4 +136,37 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_mmap_object_file() -> Result<()> {
+        block_on(async {
+            let file = Mmap::open("../linker/tests/fixtures/exit_elf_amd64.o")?;
+            let content = file.read_as_bytes().await?;
+
+            assert_eq!(content[..4], b"\x7fELF"[..]);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_mmap_prefaulted() -> Result<()> {
+        block_on(async {
+            let path = "../linker/tests/fixtures/exit_elf_amd64.o";
+            let content = Mmap::open(path)?.read_as_bytes().await?;
+            let prefaulted_content = Mmap::open_prefaulted(path)?.read_as_bytes().await?;
+
+            assert_eq!(*prefaulted_content, *content);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_mmap_empty_file() {
+        let error = Mmap::open("tests/empty.txt").err().unwrap();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), empty_file_error().to_string());
+    }
 }
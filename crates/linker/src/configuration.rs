@@ -1,4 +1,9 @@
-use std::path::PathBuf;
+use std::{
+    collections::HashMap,
+    fmt, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use crate::{target::Triple, Linker};
 
@@ -10,17 +15,412 @@ pub struct Configuration {
     /// The target triple for which the linker has to link.
     pub(crate) target: Triple,
 
-    /// All the files the linker has to link together.
+    /// All the files the linker has to link together. Only the needed members
+    /// of the archives are linked.
     pub(crate) input_files: Vec<PathBuf>,
 
     /// The file that will contain the result of the linker.
-    pub(crate) _output_file: PathBuf,
+    pub(crate) output_file: PathBuf,
+
+    /// Whether the relocation sections must be kept in the output file.
+    pub(crate) emit_relocations: bool,
+
+    /// Whether the output is a relocatable object file, to be linked again.
+    pub(crate) relocatable: bool,
+
+    /// The virtual address of the first loadable segment, if not the default
+    /// one.
+    pub(crate) image_base: Option<u64>,
+
+    /// Fixed virtual addresses of output sections, indexed by section name.
+    pub(crate) section_starts: HashMap<String, u64>,
+
+    /// How to order the input sections inside each output section, if not in
+    /// the input order.
+    pub(crate) sort_section: Option<SortSection>,
+
+    /// Whether the debug sections must be removed from the output file.
+    pub(crate) strip_debug: bool,
+
+    /// Whether the unused input sections must be removed from the output file.
+    pub(crate) gc_sections: bool,
+
+    /// Whether the removed input sections must be reported.
+    pub(crate) print_gc_sections: bool,
+
+    /// Symbols defined by the configuration, in order, along with their
+    /// value.
+    pub(crate) defined_symbols: Vec<(String, SymbolValue)>,
+
+    /// Names of the symbols whose undefined references are redirected to
+    /// `__wrap_<name>`, while `__real_<name>` refers to the original symbol.
+    pub(crate) wrap: Vec<String>,
+
+    /// Names of the symbols whose definitions and references are reported.
+    pub(crate) trace_symbols: Vec<String>,
+
+    /// Path of the file listing the global symbols to keep in the output
+    /// symbol table, if any.
+    pub(crate) retain_symbols_file: Option<PathBuf>,
+
+    /// The size of the output file is padded to a multiple of this value,
+    /// if any.
+    pub(crate) file_align: Option<u64>,
+
+    /// The maximum page size, used to align the segments, if any.
+    pub(crate) max_page_size: Option<u64>,
+
+    /// Whether the output is a dynamically-linked executable.
+    pub(crate) dynamic: bool,
+
+    /// The path of the dynamic linker, if not the default one of the target.
+    pub(crate) dynamic_linker: Option<PathBuf>,
+
+    /// Whether the output is a fully-static executable.
+    pub(crate) static_link: bool,
+
+    /// Whether the output is a shared object.
+    pub(crate) shared: bool,
+
+    /// The name of the shared object, written as `DT_SONAME`, if any.
+    pub(crate) soname: Option<String>,
+
+    /// Names of the libraries to link against, e.g. `c` for `libc.so`.
+    pub(crate) libraries: Vec<String>,
+
+    /// Directories where the libraries are searched, in order.
+    pub(crate) library_paths: Vec<PathBuf>,
+
+    /// Directories where the shared libraries are searched at runtime.
+    pub(crate) rpath: Vec<String>,
+
+    /// Whether the runtime search paths are written as `DT_RUNPATH`, or as
+    /// the legacy `DT_RPATH`.
+    pub(crate) new_dtags: bool,
+
+    /// Whether the warnings must be ignored.
+    pub(crate) no_warnings: bool,
+
+    /// Whether the warnings must be treated as errors.
+    pub(crate) fatal_warnings: bool,
+
+    /// The callback to report the progress of the link, if any.
+    pub(crate) progress: Option<Progress>,
+
+    /// The hook to post-process the bytes of the output file, if any.
+    pub(crate) post_process: Option<PostProcess>,
 }
 
 impl Configuration {
     /// Create a new `Configuration`.
     pub fn new(target: Triple, input_files: Vec<PathBuf>, output_file: PathBuf) -> Self {
-        Self { target, input_files, _output_file: output_file }
+        Self {
+            target,
+            input_files,
+            output_file,
+            emit_relocations: false,
+            relocatable: false,
+            image_base: None,
+            section_starts: HashMap::new(),
+            sort_section: None,
+            strip_debug: false,
+            gc_sections: false,
+            print_gc_sections: false,
+            defined_symbols: Vec::new(),
+            wrap: Vec::new(),
+            trace_symbols: Vec::new(),
+            retain_symbols_file: None,
+            file_align: None,
+            max_page_size: None,
+            dynamic: false,
+            dynamic_linker: None,
+            static_link: false,
+            shared: false,
+            soname: None,
+            libraries: Vec::new(),
+            library_paths: Vec::new(),
+            rpath: Vec::new(),
+            new_dtags: true,
+            no_warnings: false,
+            fatal_warnings: false,
+            progress: None,
+            post_process: None,
+        }
+    }
+
+    /// Keep the relocation sections in the output file, after the relocations
+    /// have been applied. Their offsets are rewritten to be virtual addresses.
+    pub fn emit_relocations(mut self, value: bool) -> Self {
+        self.emit_relocations = value;
+        self
+    }
+
+    /// Produce a relocatable object file, like `ld -r`, instead of an
+    /// executable: the input sections are merged, and the symbols combined,
+    /// but no address is assigned. The relocations are not applied: they are
+    /// kept, against the merged sections, so that the output file can be
+    /// linked again. By default, an executable is produced.
+    pub fn relocatable(mut self, value: bool) -> Self {
+        self.relocatable = value;
+        self
+    }
+
+    /// Set the virtual address of the first loadable segment. It must be
+    /// aligned to the page size. By default, it is `0x400000` for executables.
+    pub fn image_base(mut self, value: u64) -> Self {
+        self.image_base = Some(value);
+        self
+    }
+
+    /// Place the output section `name` at a fixed virtual address. The
+    /// sections without a fixed address are laid out after it.
+    pub fn section_start<N>(mut self, name: N, address: u64) -> Self
+    where
+        N: Into<String>,
+    {
+        self.section_starts.insert(name.into(), address);
+        self
+    }
+
+    /// Order the input sections inside each output section. By default, the
+    /// input order is kept.
+    pub fn sort_section(mut self, value: SortSection) -> Self {
+        self.sort_section = Some(value);
+        self
+    }
+
+    /// Remove the debug sections, like `.debug_info`, from the output file.
+    /// By default, they are merged and kept.
+    pub fn strip_debug(mut self, value: bool) -> Self {
+        self.strip_debug = value;
+        self
+    }
+
+    /// Remove the unused input sections from the output file. A section is
+    /// used if it is reachable from the entry point, or from the exported
+    /// symbols of a shared object, by following the relocations. By default,
+    /// all the sections are kept.
+    pub fn gc_sections(mut self, value: bool) -> Self {
+        self.gc_sections = value;
+        self
+    }
+
+    /// Report each input section removed by [`Self::gc_sections`], with a
+    /// [`Warning::RemovedSection`][crate::Warning::RemovedSection]. It is a
+    /// report, not a warning, see [`Self::no_warnings`].
+    pub fn print_gc_sections(mut self, value: bool) -> Self {
+        self.print_gc_sections = value;
+        self
+    }
+
+    /// Define the global symbol `name`, as if an input file defined it. An
+    /// absolute value defines an absolute symbol, i.e. which doesn't belong
+    /// to any section. Another symbol name defines an alias to this symbol.
+    /// It is an error if an input file defines `name` too, unless it is
+    /// weak.
+    pub fn defsym<N>(mut self, name: N, value: SymbolValue) -> Self
+    where
+        N: Into<String>,
+    {
+        self.defined_symbols.push((name.into(), value));
+        self
+    }
+
+    /// Wrap the symbol `name`: the undefined references to `name` resolve to
+    /// `__wrap_<name>`, and the undefined references to `__real_<name>`
+    /// resolve to `name`. It can be called several times, to wrap several
+    /// symbols.
+    pub fn wrap<N>(mut self, name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        self.wrap.push(name.into());
+        self
+    }
+
+    /// Trace the symbol `name`: every input file defining or referencing it
+    /// is reported, along with the definition it resolves to, as
+    /// [`Warning::TracedSymbolDefinition`],
+    /// [`Warning::TracedSymbolReference`] and
+    /// [`Warning::TracedSymbolResolution`]. These are reports, not warnings,
+    /// see [`Self::no_warnings`]. It can be called several times, to trace
+    /// several symbols.
+    ///
+    /// [`Warning::TracedSymbolDefinition`]: crate::Warning::TracedSymbolDefinition
+    /// [`Warning::TracedSymbolReference`]: crate::Warning::TracedSymbolReference
+    /// [`Warning::TracedSymbolResolution`]: crate::Warning::TracedSymbolResolution
+    pub fn trace_symbol<N>(mut self, name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        self.trace_symbols.push(name.into());
+        self
+    }
+
+    /// Keep only the global symbols listed in the file at `path`, one name
+    /// per line, in the output symbol table. The local symbols are kept, and
+    /// the dynamic symbol table is left untouched. By default, all the
+    /// symbols are kept.
+    pub fn retain_symbols_file<P>(mut self, path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.retain_symbols_file = Some(path.into());
+        self
+    }
+
+    /// Pad the output file with zero bytes, so that its size is a multiple
+    /// of `value`, which must be a power of two. The headers are left
+    /// untouched: the padding is not part of any section. By default, the
+    /// output file is not padded.
+    pub fn file_align(mut self, value: u64) -> Self {
+        self.file_align = Some(value);
+        self
+    }
+
+    /// Set the maximum page size supported by the target systems, which must
+    /// be a power of two. The loadable segments are aligned to it, so that
+    /// they can be mapped on any of these systems. By default, it depends on
+    /// the target, e.g. `0x1000` for x86-64 and `0x10000` for AArch64.
+    pub fn max_page_size(mut self, value: u64) -> Self {
+        self.max_page_size = Some(value);
+        self
+    }
+
+    /// Produce a dynamically-linked executable. Such an executable is started
+    /// by its dynamic linker, see [`Self::dynamic_linker`]. By default, the
+    /// executable is statically linked.
+    pub fn dynamic(mut self, value: bool) -> Self {
+        self.dynamic = value;
+        self
+    }
+
+    /// Set the path of the dynamic linker, written in the `.interp` section of
+    /// dynamically-linked executables. By default, the dynamic linker of the
+    /// target is used, e.g. `/lib64/ld-linux-x86-64.so.2` on Linux x86-64.
+    pub fn dynamic_linker<P>(mut self, path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.dynamic_linker = Some(path.into());
+        self
+    }
+
+    /// Produce a fully-static executable: it has no dynamic linker, even if
+    /// [`Self::dynamic`] is set, and every global symbol used by an input
+    /// file must be defined. The libraries are linked from their archives,
+    /// see [`Self::library`].
+    pub fn static_link(mut self, value: bool) -> Self {
+        self.static_link = value;
+        self
+    }
+
+    /// Produce a shared object instead of an executable. It has no dynamic
+    /// linker, and its image base is `0` by default.
+    pub fn shared(mut self, value: bool) -> Self {
+        self.shared = value;
+        self
+    }
+
+    /// Set the name of the shared object, written as `DT_SONAME`. Other files
+    /// linked against this shared object will need it by this name. It has no
+    /// effect if the output is not a shared object, see [`Self::shared`].
+    pub fn soname<N>(mut self, name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        self.soname = Some(name.into());
+        self
+    }
+
+    /// Link against the shared library `name`, i.e. `lib<name>.so`, searched
+    /// in the directories added by [`Self::library_path`]. The output is then
+    /// a dynamically-linked executable which needs this library.
+    ///
+    /// For a fully-static executable, see [`Self::static_link`], the archive
+    /// `lib<name>.a` is searched instead: only its members defining a symbol
+    /// which is used, but not defined yet, are linked, like the members of an
+    /// archive given as an input file.
+    pub fn library<N>(mut self, name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        self.libraries.push(name.into());
+        self
+    }
+
+    /// Add a directory where the libraries are searched, see
+    /// [`Self::library`]. Directories are searched in the order they are
+    /// added.
+    pub fn library_path<P>(mut self, path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.library_paths.push(path.into());
+        self
+    }
+
+    /// Add directories where the shared libraries are searched at runtime by
+    /// the dynamic linker. `path` can hold several directories separated by
+    /// `:`. It has no effect on fully-static executables.
+    pub fn rpath<P>(mut self, path: P) -> Self
+    where
+        P: Into<String>,
+    {
+        self.rpath.push(path.into());
+        self
+    }
+
+    /// Write the runtime search paths, see [`Self::rpath`], as `DT_RUNPATH`
+    /// if `true`, or as the legacy `DT_RPATH` if `false`. By default,
+    /// `DT_RUNPATH` is used.
+    pub fn new_dtags(mut self, value: bool) -> Self {
+        self.new_dtags = value;
+        self
+    }
+
+    /// Ignore all the warnings. The reports requested explicitly, e.g. by
+    /// [`Self::print_gc_sections`] or [`Self::trace_symbol`], are kept.
+    pub fn no_warnings(mut self, value: bool) -> Self {
+        self.no_warnings = value;
+        self
+    }
+
+    /// Treat the warnings as errors: the link fails if there is at least one
+    /// warning, and no output file is written. The reports requested
+    /// explicitly, e.g. by [`Self::print_gc_sections`] or
+    /// [`Self::trace_symbol`], are not warnings.
+    pub fn fatal_warnings(mut self, value: bool) -> Self {
+        self.fatal_warnings = value;
+        self
+    }
+
+    /// Report the progress of the link: `callback` is called at the start of
+    /// each [`LinkPhase`], in order.
+    pub fn progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(LinkPhase<'_>) + Send + Sync + 'static,
+    {
+        self.progress = Some(Progress(Box::new(callback)));
+        self
+    }
+
+    /// Post-process the bytes of the output file, e.g. to sign or to pack
+    /// it: `hook` receives the final image, once the relocations are applied
+    /// and the headers are built, and returns the bytes to output instead.
+    pub fn post_process<F>(mut self, hook: F) -> Self
+    where
+        F: FnOnce(Vec<u8>) -> io::Result<Vec<u8>> + Send + Sync + 'static,
+    {
+        self.post_process = Some(PostProcess(Box::new(hook)));
+        self
+    }
+
+    /// Report that a link phase starts, if a progress callback is set.
+    pub(crate) fn report(&self, phase: LinkPhase<'_>) {
+        if let Some(Progress(callback)) = &self.progress {
+            callback(phase);
+        }
     }
 
     /// End the configuration step, and build a [`Linker`].
@@ -28,3 +428,88 @@ impl Configuration {
         Linker::with_configuration(self)
     }
 }
+
+/// A callback reporting the progress of the link, see
+/// [`Configuration::progress`].
+pub(crate) struct Progress(Box<dyn Fn(LinkPhase<'_>) + Send + Sync>);
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("Progress")
+    }
+}
+
+/// A hook post-processing the bytes of the output file, see
+/// [`Configuration::post_process`].
+pub(crate) struct PostProcess(
+    pub(crate) Box<dyn FnOnce(Vec<u8>) -> io::Result<Vec<u8>> + Send + Sync>,
+);
+
+impl fmt::Debug for PostProcess {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("PostProcess")
+    }
+}
+
+/// A phase of the link, reported by [`Configuration::progress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkPhase<'a> {
+    /// An input file is being parsed. It is reported once per input file.
+    ParsingInput(&'a Path),
+    /// The symbols of all the input files are being resolved.
+    ResolvingSymbols,
+    /// The relocations of all the input files are being applied.
+    ApplyingRelocations,
+    /// The output file is being built and written.
+    WritingOutput,
+}
+
+/// How to order the input sections inside an output section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortSection {
+    /// By name, in lexicographic order, like `SORT_BY_NAME` in `ld`.
+    Name,
+    /// By alignment, in descending order, like `SORT_BY_ALIGNMENT` in `ld`.
+    Alignment,
+}
+
+impl FromStr for SortSection {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "name" => Ok(Self::Name),
+            "alignment" => Ok(Self::Alignment),
+            _ => Err(format!(
+                "`{value}` is not a valid section sort, expected `name` or `alignment`"
+            )),
+        }
+    }
+}
+
+/// The value of a symbol defined by the configuration, see
+/// [`Configuration::defsym`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolValue {
+    /// An absolute value, e.g. `0x8000000`.
+    Absolute(u64),
+    /// The value of another symbol, i.e. an alias.
+    Symbol(String),
+}
+
+impl FromStr for SymbolValue {
+    type Err = String;
+
+    /// Parse a hexadecimal value prefixed by `0x`, a decimal value, or a
+    /// symbol name.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let absolute = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+            Some(hexadecimal) => u64::from_str_radix(hexadecimal, 16),
+            None if value.starts_with(|c: char| c.is_ascii_digit()) => value.parse(),
+            None if !value.is_empty() => return Ok(Self::Symbol(value.to_owned())),
+            None => return Err("the symbol value is empty".to_owned()),
+        };
+
+        absolute.map(Self::Absolute).map_err(|_| format!("`{value}` is not a valid symbol value"))
+    }
+}
@@ -0,0 +1,145 @@
+//! Static archives, i.e. `.a` files: their members are object files which are
+//! linked only if they define a symbol which is used, but not defined yet.
+
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use bstr::{BStr, ByteSlice};
+use weld_object::archive::Archive;
+
+use super::{dynamic::find_library, Error, InputFile};
+use crate::Configuration;
+
+/// An object file to parse: an input file, or a member of an archive.
+#[derive(Debug)]
+pub(super) struct InputObject<'a> {
+    /// The path of the object file, e.g. `libfoo.a(foo.o)` for a member of an
+    /// archive.
+    pub(super) path: PathBuf,
+    /// The bytes of the object file.
+    pub(super) bytes: &'a [u8],
+    /// Whether the object file is a member of an archive.
+    pub(super) is_member: bool,
+}
+
+/// Get the object files of an input file: the file itself, or the members of
+/// the archive, in order.
+pub(super) fn input_objects<'a>(
+    path: &Path,
+    bytes: &'a [u8],
+) -> Result<Vec<InputObject<'a>>, Error> {
+    if !Archive::is_archive(bytes) {
+        return Ok(vec![InputObject { path: path.to_path_buf(), bytes, is_member: false }]);
+    }
+
+    let (_, archive) =
+        Archive::read::<()>(bytes).map_err(|_| Error::ParsingArchive(path.to_path_buf()))?;
+
+    Ok(archive
+        .members
+        .into_iter()
+        .map(|member| InputObject {
+            path: PathBuf::from(format!("{}({})", path.display(), member.name.to_str_lossy())),
+            bytes: member.data,
+            is_member: true,
+        })
+        .collect())
+}
+
+/// Find the static libraries of a fully-static executable, i.e.
+/// `lib<name>.a`, see [`Configuration::library`]. They are linked after the
+/// input files.
+pub(super) fn static_libraries(configuration: &Configuration) -> Result<Vec<PathBuf>, Error> {
+    if !configuration.static_link {
+        return Ok(Vec::new());
+    }
+
+    configuration
+        .libraries
+        .iter()
+        .map(|name| {
+            find_library(name, "a", &configuration.library_paths).ok_or_else(|| match find_library(
+                name,
+                "so",
+                &configuration.library_paths,
+            ) {
+                Some(shared_library) => Error::SharedLibraryInStaticLink(shared_library),
+                None => Error::LibraryNotFound(name.clone()),
+            })
+        })
+        .collect()
+}
+
+/// Select the object files to link: all the input files, and the members of
+/// the archives which define a symbol used by a selected object file, but not
+/// defined by any of them yet. The result is indexed by input file index.
+///
+/// An undefined weak symbol doesn't select a member.
+pub(super) fn selected_files<'a>(files: &'a [InputFile<'a>], is_member: &[bool]) -> Vec<bool> {
+    let mut selected = is_member.iter().map(|is_member| !is_member).collect::<Vec<_>>();
+    let mut symbols = SelectedSymbols::default();
+
+    for input_file in
+        files.iter().zip(&selected).filter_map(|(file, selected)| selected.then_some(file))
+    {
+        symbols.add(input_file);
+    }
+
+    // Selecting a member may add undefined symbols, which are defined by a
+    // member that has been skipped: iterate until nothing changes.
+    loop {
+        let mut changed = false;
+
+        for (input_file, selected) in files.iter().zip(selected.iter_mut()) {
+            if !*selected && symbols.is_needed(input_file) {
+                symbols.add(input_file);
+                *selected = true;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            return selected;
+        }
+    }
+}
+
+/// The global symbols of the selected object files, see [`selected_files`].
+#[derive(Default)]
+struct SelectedSymbols<'a> {
+    /// The symbols which are defined.
+    defined: HashSet<Cow<'a, BStr>>,
+    /// The symbols which are used, but not defined yet.
+    undefined: HashSet<Cow<'a, BStr>>,
+}
+
+impl<'a> SelectedSymbols<'a> {
+    /// Add the global symbols of a selected object file.
+    fn add(&mut self, input_file: &'a InputFile<'a>) {
+        for (symbol, _) in input_file.file.symbols_with_sections() {
+            let Some(name) = symbol.name.as_ref().filter(|_| !symbol.is_local()) else {
+                continue;
+            };
+
+            if !symbol.is_undefined() {
+                self.undefined.remove(name);
+                self.defined.insert(name.clone());
+            } else if symbol.is_global() && !self.defined.contains(name) {
+                self.undefined.insert(name.clone());
+            }
+        }
+    }
+
+    /// Check whether an object file defines a symbol which is used, but not
+    /// defined yet.
+    fn is_needed(&self, input_file: &'a InputFile<'a>) -> bool {
+        input_file.file.symbols_with_sections().any(|(symbol, _)| {
+            !symbol.is_local()
+                && !symbol.is_undefined()
+                && symbol.name.is_some_and(|name| self.undefined.contains(&name))
+        })
+    }
+}
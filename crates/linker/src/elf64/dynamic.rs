@@ -0,0 +1,538 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use bstr::{BString, ByteSlice};
+use weld_object::{
+    elf64::{
+        elf_hash, Address, Dynamic, DynamicTag, Endianness, Machine, SectionIndex, SectionType,
+        SymbolBinding, SymbolType,
+    },
+    prelude::*,
+};
+
+use super::{
+    layout::Layout,
+    merge,
+    plt::{Plt, R_X86_64_PLT32},
+    read_input_files,
+    symbols::{SymbolReference, Symbols},
+    Error, InputFile,
+};
+use crate::Configuration;
+
+/// Name of the section holding the strings of the dynamic entries.
+pub(super) const DYNAMIC_STRINGS_SECTION_NAME: &str = ".dynstr";
+
+/// Name of the section holding the dynamic entries.
+pub(super) const DYNAMIC_SECTION_NAME: &str = ".dynamic";
+
+/// Name of the section holding the dynamic symbols.
+pub(super) const DYNAMIC_SYMBOLS_SECTION_NAME: &str = ".dynsym";
+
+/// Name of the section holding the hash table of the dynamic symbols.
+pub(super) const DYNAMIC_HASH_SECTION_NAME: &str = ".hash";
+
+/// The possible numbers of buckets of the hash table, like `ld` does.
+const HASH_BUCKET_COUNTS: &[usize] =
+    &[1, 3, 17, 37, 67, 97, 131, 197, 263, 521, 1031, 2053, 4099, 8209, 16411, 32771];
+
+/// A symbol of the dynamic symbol table.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct DynamicSymbol {
+    /// Offset, in the strings, of the name of the symbol.
+    name_offset: u64,
+    /// The definition of the symbol if it is exported, or one of its uses if
+    /// it is imported.
+    reference: SymbolReference,
+}
+
+/// What the dynamic linker needs to know to load the output file.
+#[derive(Debug)]
+pub(super) struct DynamicLinking {
+    /// The strings referenced by the dynamic entries, i.e. the content of
+    /// `.dynstr`.
+    pub(super) strings: Vec<u8>,
+    /// Offsets, in `strings`, of the names of the needed shared objects.
+    needed: Vec<u64>,
+    /// Tag and offset, in `strings`, of the runtime search paths, if any.
+    run_path: Option<(DynamicTag, u64)>,
+    /// Offset, in `strings`, of the name of the output shared object, if any.
+    shared_object_name: Option<u64>,
+    /// Whether the output is a shared object, which always has dynamic
+    /// entries.
+    shared: bool,
+    /// Whether the output is a dynamically-linked executable, which always
+    /// has dynamic entries.
+    dynamic: bool,
+    /// The dynamic symbols, i.e. the content of `.dynsym` without the null
+    /// symbol.
+    pub(super) symbols: Vec<DynamicSymbol>,
+    /// Map the name of an imported symbol to its index in the dynamic
+    /// symbols.
+    imports: HashMap<BString, u32>,
+    /// The PLT stubs of the imported functions.
+    pub(super) plt: Plt,
+    /// Whether the output has an array of constructors, i.e. `.init_array`.
+    init_array: bool,
+    /// Whether the output has an array of destructors, i.e. `.fini_array`.
+    fini_array: bool,
+}
+
+impl DynamicLinking {
+    /// Find the libraries of the configuration, and collect their names, the
+    /// runtime search paths, and the name of the output shared object.
+    ///
+    /// A library is needed by its `SONAME` if it has one, otherwise by its
+    /// file name. The libraries and the runtime search paths are ignored for
+    /// fully-static executables, which link the archives instead, see
+    /// [`super::archive::static_libraries`]. The name of the output is ignored
+    /// if it is not a shared object.
+    pub(super) fn new(configuration: &Configuration) -> Result<Self, Error> {
+        let mut strings = vec![0x00];
+        let libraries: &[String] =
+            if configuration.static_link { &[] } else { &configuration.libraries };
+        let mut needed = Vec::with_capacity(libraries.len());
+        let mut run_path = None;
+        let mut shared_object_name = None;
+        let shared = configuration.shared;
+        let dynamic = configuration.dynamic && !configuration.static_link;
+
+        // A relocatable output is not dynamically linked: it will be linked
+        // again.
+        if configuration.relocatable
+            || (libraries.is_empty()
+                && (configuration.rpath.is_empty() || configuration.static_link)
+                && !shared
+                && !dynamic)
+        {
+            return Ok(Self {
+                strings: Vec::new(),
+                needed,
+                run_path,
+                shared_object_name,
+                shared,
+                dynamic,
+                symbols: Vec::new(),
+                imports: HashMap::new(),
+                plt: Plt::default(),
+                init_array: false,
+                fini_array: false,
+            });
+        }
+
+        if let (true, Some(soname)) = (shared, &configuration.soname) {
+            shared_object_name = Some(strings.len() as u64);
+            strings.extend_from_slice(soname.as_bytes());
+            strings.push(0x00);
+        }
+
+        let library_files = libraries
+            .iter()
+            .map(|name| {
+                find_library(name, "so", &configuration.library_paths)
+                    .ok_or_else(|| Error::LibraryNotFound(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (path, bytes) in library_files.iter().zip(read_input_files(&library_files)?) {
+            let (_, file) =
+                File::read::<()>(&bytes).map_err(|_| Error::ParsingFile(path.to_path_buf()))?;
+            let name = match file.shared_object_name() {
+                Some(name) => BString::from(name.as_bytes()),
+                None => BString::from(path.file_name().unwrap_or_default().as_encoded_bytes()),
+            };
+
+            needed.push(strings.len() as u64);
+            strings.extend_from_slice(&name);
+            strings.push(0x00);
+        }
+
+        // All the runtime search paths are joined in a single entry.
+        if !configuration.rpath.is_empty() {
+            let tag = if configuration.new_dtags { DynamicTag::RunPath } else { DynamicTag::RPath };
+
+            run_path = Some((tag, strings.len() as u64));
+            strings.extend_from_slice(configuration.rpath.join(":").as_bytes());
+            strings.push(0x00);
+        }
+
+        Ok(Self {
+            strings,
+            needed,
+            run_path,
+            shared_object_name,
+            shared,
+            dynamic,
+            symbols: Vec::new(),
+            imports: HashMap::new(),
+            plt: Plt::default(),
+            init_array: false,
+            fini_array: false,
+        })
+    }
+
+    /// Check whether the input files have constructors or destructors: the
+    /// dynamic linker runs them, so the dynamic entries point to them.
+    pub(super) fn add_arrays(&mut self, files: &[InputFile]) {
+        if self.is_empty() {
+            return;
+        }
+
+        let has_sections = |r#type| {
+            files
+                .iter()
+                .flat_map(|input_file| &input_file.file.sections)
+                .any(|section| section.r#type == r#type)
+        };
+
+        self.init_array = has_sections(SectionType::ArrayOfConstructors);
+        self.fini_array = has_sections(SectionType::ArrayOfDestructors);
+    }
+
+    /// Collect the symbols participating in dynamic linking, and add their
+    /// names to the strings.
+    ///
+    /// A shared object exports all its global symbols. Global symbols that
+    /// are used but never defined are imported from the needed shared
+    /// objects.
+    pub(super) fn add_symbols<N>(&mut self, symbols: &Symbols<N>)
+    where
+        N: Number,
+    {
+        if self.is_empty() {
+            return;
+        }
+
+        if self.shared {
+            for (name, reference) in &symbols.globals {
+                self.symbols.push(DynamicSymbol {
+                    name_offset: self.strings.len() as u64,
+                    reference: *reference,
+                });
+                self.strings.extend_from_slice(name);
+                self.strings.push(0x00);
+            }
+        }
+
+        for (file_index, input_symbols) in symbols.per_file.iter().enumerate() {
+            for (symbol_index, symbol) in input_symbols.iter().enumerate() {
+                if !(symbol.is_global() || symbol.is_weak()) || symbol.is_defined() {
+                    continue;
+                }
+
+                let Some(name) = symbol.name else {
+                    continue;
+                };
+
+                if symbols.global_index(&name).is_some() || self.imports.contains_key(name.as_ref())
+                {
+                    continue;
+                }
+
+                // The index is shifted by one because of the null symbol.
+                self.imports.insert(name.as_ref().to_owned(), self.symbols.len() as u32 + 1);
+                self.symbols.push(DynamicSymbol {
+                    name_offset: self.strings.len() as u64,
+                    reference: SymbolReference { file: file_index, symbol: symbol_index },
+                });
+                self.strings.extend_from_slice(&name);
+                self.strings.push(0x00);
+            }
+        }
+
+        self.merge_strings();
+    }
+
+    /// Merge the strings: identical strings, e.g. a needed library and a
+    /// symbol with the same name, are stored once, and the offsets in the
+    /// strings are rewritten accordingly.
+    fn merge_strings(&mut self) {
+        let (strings, offset) = merge::merge_string_table(&self.strings);
+
+        for name_offset in self
+            .needed
+            .iter_mut()
+            .chain(self.run_path.as_mut().map(|(_, run_path)| run_path))
+            .chain(self.shared_object_name.as_mut())
+            .chain(self.symbols.iter_mut().map(|symbol| &mut symbol.name_offset))
+        {
+            *name_offset = offset(*name_offset);
+        }
+
+        self.strings = strings;
+    }
+
+    /// Add a PLT stub for each imported symbol which is called, i.e. which is
+    /// the target of a `R_X86_64_PLT32` relocation. Only x86-64 is supported.
+    pub(super) fn add_plt<N>(
+        &mut self,
+        files: &[InputFile],
+        symbols: &Symbols<N>,
+    ) -> Result<(), Error>
+    where
+        N: Number,
+    {
+        if self.imports.is_empty() {
+            return Ok(());
+        }
+
+        for (file_index, input_file) in files.iter().enumerate() {
+            let file = &input_file.file;
+
+            if file.machine != Machine::X86_64 {
+                continue;
+            }
+
+            for section in file
+                .sections
+                .iter()
+                .filter(|section| section.r#type == SectionType::RelocationWithAddends)
+            {
+                let relocations = section
+                    .data
+                    .relocations::<()>()
+                    .ok_or_else(|| Error::ParsingRelocation(input_file.path().to_path_buf()))?;
+
+                for relocation in relocations {
+                    let relocation = relocation
+                        .map_err(|_| Error::ParsingRelocation(input_file.path().to_path_buf()))?;
+
+                    if relocation.r#type != R_X86_64_PLT32 {
+                        continue;
+                    }
+
+                    let reference = SymbolReference {
+                        file: file_index,
+                        symbol: relocation.symbol_index as usize,
+                    };
+                    let Some(name) = symbols.get(reference).and_then(|symbol| symbol.name) else {
+                        continue;
+                    };
+
+                    if let Some(dynamic_symbol_index) = self.imports.get(name.as_ref()) {
+                        self.plt.push(&name, *dynamic_symbol_index);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check whether the output file needs dynamic entries.
+    pub(super) fn is_empty(&self) -> bool {
+        self.needed.is_empty() && self.run_path.is_none() && !self.shared && !self.dynamic
+    }
+
+    /// Get the size, in bytes, of the dynamic entries.
+    pub(super) fn size(&self) -> usize {
+        // One entry per needed shared object, plus the name of the shared
+        // object, plus the runtime search paths, plus the address and the size
+        // of the strings, plus the address and the entry size of the symbols,
+        // plus the address of the hash table, plus the address, the size and
+        // the type of the relocations of the PLT and the address of its GOT
+        // slots if any, plus the address and the size of the arrays of
+        // constructors and destructors if any, plus the last null entry.
+        (self.needed.len()
+            + usize::from(self.shared_object_name.is_some())
+            + usize::from(self.run_path.is_some())
+            + if self.plt.is_empty() { 0 } else { 4 }
+            + 2 * usize::from(self.init_array)
+            + 2 * usize::from(self.fini_array)
+            + 6)
+            * Dynamic::SIZE
+    }
+
+    /// Get the size, in bytes, of the dynamic symbols, including the null
+    /// symbol.
+    pub(super) fn symbols_size(&self) -> usize {
+        (self.symbols.len() + 1) * Symbol::SIZE
+    }
+
+    /// Get the number of buckets of the hash table: the biggest of
+    /// [`HASH_BUCKET_COUNTS`] not exceeding the number of symbols.
+    fn hash_bucket_count(&self) -> usize {
+        HASH_BUCKET_COUNTS
+            .iter()
+            .copied()
+            .take_while(|count| *count <= self.symbols.len())
+            .last()
+            .unwrap_or(1)
+    }
+
+    /// Get the size, in bytes, of the hash table of the dynamic symbols.
+    pub(super) fn hash_size(&self) -> usize {
+        // The number of buckets, the number of chains, the buckets, and one
+        // chain per dynamic symbol, including the null symbol.
+        (2 + self.hash_bucket_count() + self.symbols.len() + 1) * 4
+    }
+
+    /// Encode the SysV hash table of the dynamic symbols.
+    ///
+    /// A symbol is found by hashing its name with [`elf_hash`]: the bucket
+    /// of the hash holds the index of a first symbol, and the chain of each
+    /// symbol holds the index of the next symbol with the same bucket, until
+    /// the null symbol.
+    pub(super) fn hash_table(&self, endianness: Endianness) -> Vec<u8> {
+        let bucket_count = self.hash_bucket_count();
+        let mut buckets = vec![0u32; bucket_count];
+        let mut chains = vec![0u32; self.symbols.len() + 1];
+
+        for (index, dynamic_symbol) in self.symbols.iter().enumerate() {
+            let name = self.strings[dynamic_symbol.name_offset as usize..]
+                .split(|byte| *byte == 0x00)
+                .next()
+                .unwrap_or_default();
+            let bucket = &mut buckets[elf_hash(name) as usize % bucket_count];
+
+            // The index is shifted by one because of the null symbol.
+            chains[index + 1] = *bucket;
+            *bucket = (index + 1) as u32;
+        }
+
+        let write_u32 = match endianness {
+            Endianness::Big => BigEndian::write_u32,
+            Endianness::Little => LittleEndian::write_u32,
+        };
+
+        [bucket_count as u32, chains.len() as u32]
+            .into_iter()
+            .chain(buckets)
+            .chain(chains)
+            .flat_map(write_u32)
+            .collect()
+    }
+
+    /// Encode the dynamic entries, once the addresses of the sections
+    /// generated for the dynamic linking are known.
+    pub(super) fn entries(&self, layout: &Layout, endianness: Endianness) -> Vec<u8> {
+        let address =
+            |index: Option<usize>| index.map_or(0, |index| layout.sections[index].virtual_address);
+        let plt_entries = (!self.plt.is_empty()).then(|| {
+            [
+                Dynamic { tag: DynamicTag::PltGot, value: address(layout.plt_got) },
+                Dynamic {
+                    tag: DynamicTag::PltRelocationsSize,
+                    value: self.plt.relocations_size() as u64,
+                },
+                Dynamic { tag: DynamicTag::PltRelocationsType, value: DynamicTag::Rela.into() },
+                Dynamic {
+                    tag: DynamicTag::JumpRelocations,
+                    value: address(layout.plt_relocations),
+                },
+            ]
+        });
+
+        let size =
+            |index: Option<usize>| index.map_or(0, |index| layout.sections[index].memory_size());
+        let init_array_entries = self.init_array.then(|| {
+            [
+                Dynamic { tag: DynamicTag::InitArray, value: address(layout.init_array) },
+                Dynamic { tag: DynamicTag::InitArraySize, value: size(layout.init_array) },
+            ]
+        });
+        let fini_array_entries = self.fini_array.then(|| {
+            [
+                Dynamic { tag: DynamicTag::FiniArray, value: address(layout.fini_array) },
+                Dynamic { tag: DynamicTag::FiniArraySize, value: size(layout.fini_array) },
+            ]
+        });
+
+        let entries = self
+            .needed
+            .iter()
+            .map(|offset| Dynamic { tag: DynamicTag::Needed, value: *offset })
+            .chain(
+                self.shared_object_name
+                    .map(|offset| Dynamic { tag: DynamicTag::SharedObjectName, value: offset }),
+            )
+            .chain(self.run_path.map(|(tag, offset)| Dynamic { tag, value: offset }))
+            .chain([
+                Dynamic { tag: DynamicTag::StringTable, value: address(layout.dynamic_strings) },
+                Dynamic { tag: DynamicTag::StringTableSize, value: self.strings.len() as u64 },
+                Dynamic { tag: DynamicTag::SymbolTable, value: address(layout.dynamic_symbols) },
+                Dynamic { tag: DynamicTag::SymbolEntrySize, value: Symbol::SIZE as u64 },
+                Dynamic { tag: DynamicTag::Hash, value: address(layout.dynamic_hash) },
+            ])
+            .chain(plt_entries.into_iter().flatten())
+            .chain(init_array_entries.into_iter().flatten())
+            .chain(fini_array_entries.into_iter().flatten())
+            .chain([Dynamic { tag: DynamicTag::Null, value: 0 }]);
+
+        let mut data = Vec::with_capacity(self.size());
+
+        for entry in entries {
+            match endianness {
+                Endianness::Big => entry.write::<BigEndian, _>(&mut data),
+                Endianness::Little => entry.write::<LittleEndian, _>(&mut data),
+            }
+            .expect("Writing in a `Vec` cannot fail");
+        }
+
+        data
+    }
+
+    /// Encode the dynamic symbols, once the symbols have their final address.
+    /// Imported symbols are undefined.
+    pub(super) fn symbol_table<N>(
+        &self,
+        symbols: &Symbols<N>,
+        layout: &Layout,
+        endianness: Endianness,
+    ) -> Vec<u8>
+    where
+        N: Number,
+    {
+        let null_symbol = Symbol {
+            name: None,
+            name_offset: Address(0),
+            r#type: SymbolType::NoType,
+            binding: SymbolBinding::Local,
+            section_index_where_symbol_is_defined: SectionIndex::Undefined,
+            value: Address(0),
+            size: 0,
+        };
+        let dynamic_symbols = self.symbols.iter().map(|dynamic_symbol| {
+            let symbol =
+                symbols.get(dynamic_symbol.reference).expect("The symbol reference must be valid");
+            let (section_index, value) = symbols
+                .output_location(dynamic_symbol.reference, layout)
+                .unwrap_or((SectionIndex::Undefined, 0));
+
+            Symbol {
+                name: None,
+                name_offset: Address(dynamic_symbol.name_offset),
+                section_index_where_symbol_is_defined: section_index,
+                value: Address(value),
+                ..symbol
+            }
+        });
+
+        let mut data = Vec::with_capacity(self.symbols_size());
+
+        for symbol in [null_symbol].into_iter().chain(dynamic_symbols) {
+            match endianness {
+                Endianness::Big => symbol.write::<BigEndian, _>(&mut data),
+                Endianness::Little => symbol.write::<LittleEndian, _>(&mut data),
+            }
+            .expect("Writing in a `Vec` cannot fail");
+        }
+
+        data
+    }
+}
+
+/// Find the library `name`, i.e. `lib<name>.<extension>` where `extension` is
+/// `so` for a shared library or `a` for an archive, in the first directory of
+/// `library_paths` containing it.
+pub(super) fn find_library(
+    name: &str,
+    extension: &str,
+    library_paths: &[PathBuf],
+) -> Option<PathBuf> {
+    let file_name = format!("lib{name}.{extension}");
+
+    library_paths
+        .iter()
+        .map(|library_path| library_path.join(&file_name))
+        .find(|path| path.is_file())
+}
@@ -0,0 +1,177 @@
+use std::borrow::Cow;
+
+use weld_object::{
+    elf64::{Data, DataType, EhFrameEntry, Endianness, FrameDescriptionEntry},
+    prelude::*,
+};
+
+use super::{endianness, layout::Layout, InputFile};
+
+/// Name of the section holding the unwind information.
+pub(super) const EH_FRAME_SECTION_NAME: &str = ".eh_frame";
+
+/// Name of the section holding the table to find the unwind information of
+/// an address.
+pub(super) const EH_FRAME_HDR_SECTION_NAME: &str = ".eh_frame_hdr";
+
+/// Version of the `.eh_frame_hdr` section.
+const EH_FRAME_HDR_VERSION: u8 = 1;
+
+/// Size, in bytes, of the fixed part of the `.eh_frame_hdr` section: the
+/// version, the encodings, the pointer to `.eh_frame`, and the number of
+/// FDEs.
+const EH_FRAME_HDR_HEADER_SIZE: usize = 12;
+
+/// Size, in bytes, of an entry of the table of the `.eh_frame_hdr` section.
+const EH_FRAME_HDR_ENTRY_SIZE: usize = 8;
+
+/// Pointer encoding format: an absolute pointer.
+const DW_EH_PE_ABSPTR: u8 = 0x00;
+
+/// Pointer encoding format: an unsigned 4-byte value.
+const DW_EH_PE_UDATA4: u8 = 0x03;
+
+/// Pointer encoding format: a signed 4-byte value.
+const DW_EH_PE_SDATA4: u8 = 0x0b;
+
+/// Pointer encoding application: relative to the address of the value.
+const DW_EH_PE_PCREL: u8 = 0x10;
+
+/// Pointer encoding application: relative to the address of the
+/// `.eh_frame_hdr` section.
+const DW_EH_PE_DATAREL: u8 = 0x30;
+
+/// Pointer encoding format: the value is omitted.
+const DW_EH_PE_OMIT: u8 = 0xff;
+
+/// Mask of the application of a pointer encoding.
+const DW_EH_PE_APPLICATION_MASK: u8 = 0x70;
+
+/// Get the FDEs of an input `.eh_frame` section, given its data. The entries
+/// following a malformed one are ignored.
+fn fdes<'a>(data: &'a Data<'a>) -> impl Iterator<Item = FrameDescriptionEntry<'a>> {
+    data.eh_frame_entries::<()>().map_while(Result::ok).filter_map(|entry| match entry {
+        EhFrameEntry::Fde(fde) => Some(fde),
+        EhFrameEntry::Cie(_) => None,
+    })
+}
+
+/// Get the size, in bytes, of the `.eh_frame_hdr` section, given the input
+/// `.eh_frame` sections.
+pub(super) fn hdr_size<'a, I>(sections: I) -> usize
+where
+    I: Iterator<Item = &'a Section<'a>>,
+{
+    let fde_count = sections.map(|section| fdes(&section.data).count()).sum::<usize>();
+
+    EH_FRAME_HDR_HEADER_SIZE + fde_count * EH_FRAME_HDR_ENTRY_SIZE
+}
+
+/// Encode the `.eh_frame_hdr` section, once the relocations of the output
+/// `.eh_frame` section are applied.
+///
+/// The table maps the initial location of each FDE to the FDE itself, sorted
+/// by initial location, so that the unwinder can do a binary search. If an
+/// initial location can't be resolved, or doesn't fit in the table, the table
+/// is omitted: the unwinder falls back to a linear search of `.eh_frame`.
+pub(super) fn hdr(files: &[InputFile], layout: &Layout, first_file: &File) -> Vec<u8> {
+    let (Some(eh_frame_index), Some(eh_frame_hdr_index)) = (layout.eh_frame, layout.eh_frame_hdr)
+    else {
+        return Vec::new();
+    };
+
+    let eh_frame = &layout.sections[eh_frame_index];
+    let hdr_address = layout.sections[eh_frame_hdr_index].virtual_address;
+    let size = layout.sections[eh_frame_hdr_index].data.len();
+
+    // Read the FDEs from the output section, where the relocations are
+    // applied, one input section at a time: each one may end with a zero
+    // terminator.
+    let mut table = Some(Vec::new());
+
+    for (file_index, input_file) in files.iter().enumerate() {
+        for (section_index, section) in input_file.file.sections.iter().enumerate() {
+            let Some(placement) = layout.placements[file_index][section_index]
+                .filter(|placement| placement.section == eh_frame_index)
+            else {
+                continue;
+            };
+
+            let start = placement.offset as usize;
+            let data = Data::new(
+                Cow::Borrowed(&eh_frame.data[start..start + section.data.len()]),
+                DataType::ProgramData,
+                endianness(first_file),
+                first_file.machine,
+                None,
+            );
+            let section_address = eh_frame.virtual_address + placement.offset;
+
+            for fde in fdes(&data) {
+                let initial_location = match fde.pointer_encoding & DW_EH_PE_APPLICATION_MASK {
+                    DW_EH_PE_ABSPTR => Some(fde.initial_location as u64),
+                    DW_EH_PE_PCREL => Some(
+                        (section_address + fde.initial_location_offset)
+                            .wrapping_add_signed(fde.initial_location),
+                    ),
+                    _ => None,
+                };
+                let relative =
+                    |address: u64| i32::try_from(address.wrapping_sub(hdr_address) as i64);
+
+                table = table.and_then(|mut table| {
+                    let initial_location = relative(initial_location?).ok()?;
+                    let fde_address = relative(section_address + fde.offset).ok()?;
+
+                    table.push((initial_location, fde_address));
+
+                    Some(table)
+                });
+            }
+        }
+    }
+
+    let write_u32 = match first_file.endianness {
+        Endianness::Big => BigEndian::write_u32,
+        Endianness::Little => LittleEndian::write_u32,
+    };
+
+    // The pointer to `.eh_frame` is relative to its own field.
+    let eh_frame_pointer = eh_frame.virtual_address.wrapping_sub(hdr_address + 4) as u32;
+    let mut data = Vec::with_capacity(size);
+
+    match table {
+        Some(mut table) => {
+            table.sort_unstable();
+
+            data.extend_from_slice(&[
+                EH_FRAME_HDR_VERSION,
+                DW_EH_PE_PCREL | DW_EH_PE_SDATA4,
+                DW_EH_PE_UDATA4,
+                DW_EH_PE_DATAREL | DW_EH_PE_SDATA4,
+            ]);
+            data.extend(write_u32(eh_frame_pointer));
+            data.extend(write_u32(table.len() as u32));
+
+            for (initial_location, fde_address) in table {
+                data.extend(write_u32(initial_location as u32));
+                data.extend(write_u32(fde_address as u32));
+            }
+        }
+
+        None => {
+            data.extend_from_slice(&[
+                EH_FRAME_HDR_VERSION,
+                DW_EH_PE_PCREL | DW_EH_PE_SDATA4,
+                DW_EH_PE_OMIT,
+                DW_EH_PE_OMIT,
+            ]);
+            data.extend(write_u32(eh_frame_pointer));
+        }
+    }
+
+    // The size of the section is fixed by the layout.
+    data.resize(size, 0);
+
+    data
+}
@@ -0,0 +1,144 @@
+//! Garbage collection of the unused input sections, see
+//! [`Configuration::gc_sections`].
+
+use bstr::ByteSlice;
+use weld_object::{
+    elf64::{SectionFlag, SectionIndex, SectionType, SymbolType},
+    Number,
+};
+
+use super::{
+    symbols::{SymbolReference, Symbols},
+    InputFile,
+};
+use crate::Configuration;
+
+/// Prefixes of the names of the sections that are run by the dynamic loader
+/// or the C runtime, and are never referenced: they are always kept.
+const KEPT_SECTION_PREFIXES: &[&str] =
+    &[".init", ".fini", ".preinit_array", ".init_array", ".fini_array", ".ctors", ".dtors"];
+
+/// The input sections that are used, indexed by input file index, then by
+/// input section index.
+#[derive(Debug)]
+pub(super) struct LiveSections(Vec<Vec<bool>>);
+
+impl LiveSections {
+    /// Mark the input sections that are reachable from the roots: the
+    /// section defining the entry point, the sections defining the exported
+    /// symbols of a shared object, and the sections that are always kept.
+    /// The relocations of a live section make the sections defining their
+    /// symbols live too. A `LinkOrder` section is live if the section it is
+    /// linked to is live.
+    pub(super) fn new<N>(
+        files: &[InputFile],
+        symbols: &Symbols<N>,
+        configuration: &Configuration,
+    ) -> Self
+    where
+        N: Number,
+    {
+        let mut live = files
+            .iter()
+            .map(|input_file| vec![false; input_file.file.sections.len()])
+            .collect::<Vec<_>>();
+        let mut work_list = Vec::new();
+
+        let mark_symbol = |reference: SymbolReference, work_list: &mut Vec<(usize, usize)>| {
+            if let Some(SectionIndex::Ok(section_index)) =
+                symbols.get(reference).map(|symbol| symbol.section_index_where_symbol_is_defined)
+            {
+                work_list.push((reference.file, section_index));
+            }
+        };
+
+        for name in ["_start", "main"] {
+            if let Some(reference) = symbols.global(name.as_bytes().as_bstr()) {
+                mark_symbol(reference, &mut work_list);
+                break;
+            }
+        }
+
+        if configuration.shared {
+            for (_, reference) in &symbols.globals {
+                mark_symbol(*reference, &mut work_list);
+            }
+        }
+
+        for (file_index, input_file) in files.iter().enumerate() {
+            for (section_index, section) in input_file.file.sections.iter().enumerate() {
+                let name = section.name.as_deref().map(|name| name.as_bytes()).unwrap_or_default();
+
+                if !section.flags.contains(SectionFlag::Allocable)
+                    || KEPT_SECTION_PREFIXES
+                        .iter()
+                        .any(|prefix| name.starts_with(prefix.as_bytes()))
+                {
+                    work_list.push((file_index, section_index));
+                }
+            }
+        }
+
+        while let Some((file_index, section_index)) = work_list.pop() {
+            match live[file_index].get_mut(section_index) {
+                Some(is_live) if !*is_live => *is_live = true,
+                _ => continue,
+            }
+
+            let file = &files[file_index].file;
+
+            for (other_section_index, section) in file.sections.iter().enumerate() {
+                // The `LinkOrder` sections linked to this section.
+                if section.flags.contains(SectionFlag::LinkOrder)
+                    && section.link == SectionIndex::Ok(section_index)
+                {
+                    work_list.push((file_index, other_section_index));
+                }
+
+                // The relocations applying to this section.
+                if section.r#type != SectionType::RelocationWithAddends
+                    || section.information as usize != section_index
+                {
+                    continue;
+                }
+
+                let Some(relocations) = section.data.relocations::<()>() else {
+                    continue;
+                };
+
+                for relocation in relocations.filter_map(Result::ok) {
+                    let reference = SymbolReference {
+                        file: file_index,
+                        symbol: relocation.symbol_index as usize,
+                    };
+                    let Some(symbol) = symbols.get(reference) else {
+                        continue;
+                    };
+
+                    // Global symbols are defined elsewhere, maybe in another
+                    // input file.
+                    let definition = if symbol.is_local() || symbol.r#type == SymbolType::Section {
+                        Some(reference)
+                    } else {
+                        symbol.name.as_deref().and_then(|name| symbols.global(name))
+                    };
+
+                    if let Some(definition) = definition {
+                        mark_symbol(definition, &mut work_list);
+                    }
+                }
+            }
+        }
+
+        Self(live)
+    }
+
+    /// Check whether an input section is live.
+    pub(super) fn is_live(&self, file_index: usize, section_index: usize) -> bool {
+        self.0
+            .get(file_index)
+            .and_then(|sections| sections.get(section_index))
+            .copied()
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,71 @@
+//! Deduplication of the COMDAT section groups, e.g. the instantiations of a
+//! template or of an inline function emitted by several input files.
+
+use std::collections::HashSet;
+
+use bstr::BString;
+use weld_object::elf64::{File, GroupFlag, SectionType};
+
+use super::InputFile;
+
+/// Find the input sections to discard: the first COMDAT group with a given
+/// signature is kept, and the next ones are discarded along with their member
+/// sections. The result is indexed by input file index, then by input section
+/// index.
+pub(super) fn discarded_sections(files: &[InputFile]) -> Vec<Vec<bool>> {
+    let mut signatures = HashSet::<BString>::new();
+
+    files
+        .iter()
+        .map(|input_file| {
+            let file = &input_file.file;
+            let mut discarded = vec![false; file.sections.len()];
+
+            for (section_index, section) in file.sections.iter().enumerate() {
+                let Some(group) = section.data.group() else {
+                    continue;
+                };
+
+                if !group.flags.contains(GroupFlag::Comdat) {
+                    continue;
+                }
+
+                let Some(signature) = group_signature(file, section_index) else {
+                    continue;
+                };
+
+                if signatures.insert(signature) {
+                    continue;
+                }
+
+                discarded[section_index] = true;
+
+                for member in group.sections {
+                    if let Some(discarded) = discarded.get_mut(member as usize) {
+                        *discarded = true;
+                    }
+                }
+            }
+
+            discarded
+        })
+        .collect()
+}
+
+/// Get the signature of a group, i.e. the name of the symbol designated by
+/// the group section.
+fn group_signature(file: &File, section_index: usize) -> Option<BString> {
+    let section = &file.sections[section_index];
+
+    let symbol_table = section
+        .link
+        .resolve(&file.sections)
+        .filter(|symbol_table| symbol_table.r#type == SectionType::SymbolTable)?;
+    let symbol = symbol_table
+        .data
+        .symbols::<()>(file.strings_section_of(symbol_table))?
+        .nth(section.information as usize)?
+        .ok()?;
+
+    symbol.name.map(|name| name.into_owned())
+}
@@ -0,0 +1,1072 @@
+use std::{cmp, collections::HashMap, num::NonZeroU64, ops::Range};
+
+use bstr::{BStr, BString, ByteSlice};
+use weld_object::elf64::{
+    File, Program, ProgramFlag, ProgramFlags, Section, SectionFlag, SectionFlags, SectionIndex,
+    SectionType,
+};
+
+use super::{
+    dynamic::{
+        DynamicLinking, DYNAMIC_HASH_SECTION_NAME, DYNAMIC_SECTION_NAME,
+        DYNAMIC_STRINGS_SECTION_NAME, DYNAMIC_SYMBOLS_SECTION_NAME,
+    },
+    eh_frame::{self, EH_FRAME_HDR_SECTION_NAME, EH_FRAME_SECTION_NAME},
+    gc::LiveSections,
+    merge::{self, StringPiece},
+    plt::{PLT_GOT_SECTION_NAME, PLT_RELOCATIONS_SECTION_NAME, PLT_SECTION_NAME},
+    Error, InputFile,
+};
+use crate::{Configuration, SortSection};
+
+/// The default virtual address where the image of an executable is loaded.
+pub(super) const DEFAULT_IMAGE_BASE: u64 = 0x40_0000;
+
+/// The maximum page size used to align segments, unless the target or the
+/// configuration uses another one, see [`Configuration::max_page_size`].
+pub(super) const DEFAULT_MAX_PAGE_SIZE: u64 = 0x1000;
+
+/// Name of the section holding the path of the interpreter.
+const INTERPRETER_SECTION_NAME: &str = ".interp";
+
+/// Name of the section holding the array of constructors.
+const INIT_ARRAY_SECTION_NAME: &str = ".init_array";
+
+/// Name of the section holding the array of destructors.
+const FINI_ARRAY_SECTION_NAME: &str = ".fini_array";
+
+/// A section of the output file, made of the concatenation of input sections.
+#[derive(Debug)]
+pub(super) struct OutputSection {
+    /// Name of the section.
+    pub(super) name: BString,
+    /// Type of the section.
+    pub(super) r#type: SectionType,
+    /// Flags of the section.
+    pub(super) flags: SectionFlags,
+    /// Alignment of the section, i.e. the biggest alignment of its input
+    /// sections.
+    pub(super) alignment: u64,
+    /// Data of the section. It is empty for `NoBits` sections.
+    pub(super) data: Vec<u8>,
+    /// Size of a `NoBits` section, which has no data but occupies memory.
+    pub(super) no_bits_size: u64,
+    /// Offset of the section in the file image.
+    pub(super) offset: u64,
+    /// Virtual address of the section in memory.
+    pub(super) virtual_address: u64,
+    /// Virtual address requested for the section, if any.
+    pub(super) fixed_address: Option<u64>,
+}
+
+impl OutputSection {
+    /// Get the size of the section in memory.
+    pub(super) fn memory_size(&self) -> u64 {
+        self.data.len() as u64 + self.no_bits_size
+    }
+}
+
+/// A loadable segment, grouping consecutive output sections sharing the same
+/// permissions.
+#[derive(Debug)]
+pub(super) struct Segment {
+    /// Flags of the segment.
+    pub(super) flags: ProgramFlags,
+    /// Output sections contained by this segment.
+    pub(super) sections: Range<usize>,
+    /// Offset of the segment in the file image.
+    pub(super) offset: u64,
+    /// Virtual address of the segment in memory.
+    pub(super) virtual_address: u64,
+    /// Size of the segment in the file image.
+    pub(super) file_size: u64,
+    /// Size of the segment in memory. It is bigger than the size in the file
+    /// image when the segment ends with `NoBits` sections.
+    pub(super) memory_size: u64,
+}
+
+/// Where an input section lands in the output.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Placement {
+    /// Index of the output section.
+    pub(super) section: usize,
+    /// Offset of the input section inside the output section.
+    pub(super) offset: u64,
+}
+
+/// Layout of the output file.
+///
+/// Allocable input sections are merged into output sections, which are grouped
+/// by permissions into segments, which are finally assigned an offset and a
+/// virtual address.
+#[derive(Debug)]
+pub(super) struct Layout {
+    /// All output sections, in file order.
+    pub(super) sections: Vec<OutputSection>,
+    /// All loadable segments, in file order.
+    pub(super) segments: Vec<Segment>,
+    /// The thread-local storage template, i.e. the sections having the
+    /// `HasThreadLocalData` flag, if any. It lies inside a loadable segment.
+    pub(super) tls: Option<Segment>,
+    /// Placements of input sections, indexed by input file index, then by
+    /// input section index.
+    pub(super) placements: Vec<Vec<Option<Placement>>>,
+    /// String pieces of the merged string sections, indexed by input file
+    /// index and input section index.
+    merged_strings: HashMap<(usize, usize), Vec<StringPiece>>,
+    /// Links between output sections, from the index of an output section to
+    /// the index of the output section it is linked to, e.g. for sections
+    /// with the `LinkOrder` flag.
+    pub(super) links: HashMap<usize, usize>,
+    /// Index of the output section holding the path of the interpreter, if
+    /// any.
+    pub(super) interpreter: Option<usize>,
+    /// Index of the output section holding the dynamic entries, if any.
+    pub(super) dynamic: Option<usize>,
+    /// Index of the output section holding the strings of the dynamic
+    /// entries, if any.
+    pub(super) dynamic_strings: Option<usize>,
+    /// Index of the output section holding the dynamic symbols, if any.
+    pub(super) dynamic_symbols: Option<usize>,
+    /// Index of the output section holding the hash table of the dynamic
+    /// symbols, if any.
+    pub(super) dynamic_hash: Option<usize>,
+    /// Index of the output section holding the PLT stubs, if any.
+    pub(super) plt: Option<usize>,
+    /// Index of the output section holding the GOT slots of the PLT stubs,
+    /// if any.
+    pub(super) plt_got: Option<usize>,
+    /// Index of the output section holding the relocations of the GOT slots
+    /// of the PLT stubs, if any.
+    pub(super) plt_relocations: Option<usize>,
+    /// Index of the output section holding the array of constructors, if
+    /// any.
+    pub(super) init_array: Option<usize>,
+    /// Index of the output section holding the array of destructors, if any.
+    pub(super) fini_array: Option<usize>,
+    /// Index of the output section holding the unwind information, if any.
+    pub(super) eh_frame: Option<usize>,
+    /// Index of the output section holding the table to find the unwind
+    /// information of an address, if any.
+    pub(super) eh_frame_hdr: Option<usize>,
+    /// The first offset after the content of all segments.
+    pub(super) end_offset: u64,
+    /// The page size used to align segments.
+    pub(super) page_size: u64,
+}
+
+impl Layout {
+    /// Merge input sections into output sections, and lay them out from
+    /// `image_base`, with segments aligned to `page_size`.
+    ///
+    /// Sections listed by [`Configuration::section_start`] start a new
+    /// segment at a fixed address; the next segments are laid out after them.
+    /// Debug sections are merged too, unless they are stripped. They are not
+    /// loaded in memory, so they don't belong to any segment.
+    ///
+    /// If `interpreter` is present, an `.interp` section holding it is created
+    /// at the beginning of the first segment. If `dynamic_linking` is not
+    /// empty, a `.hash`, a `.dynsym`, a `.dynstr` and a `.dynamic` sections
+    /// holding it are created too, along with a `.plt`, a `.got.plt` and a
+    /// `.rela.plt` sections if there are PLT stubs. The dynamic symbols are
+    /// written later, once the symbols have their final address.
+    ///
+    /// If there is an `.eh_frame` section, an `.eh_frame_hdr` section is
+    /// created before it. Its table is written later, once the relocations of
+    /// `.eh_frame` are applied.
+    ///
+    /// The input sections of the duplicate COMDAT groups are not merged. If
+    /// `live_sections` is present, only the live input sections are merged.
+    ///
+    /// A relocatable output, see [`Configuration::relocatable`], has no
+    /// segment, and no `.eh_frame_hdr` section: its sections are only
+    /// assigned an offset, and their virtual address is 0.
+    pub(super) fn new(
+        files: &[InputFile],
+        configuration: &Configuration,
+        image_base: u64,
+        page_size: u64,
+        interpreter: Option<&[u8]>,
+        dynamic_linking: &DynamicLinking,
+        live_sections: Option<&LiveSections>,
+    ) -> Result<Self, Error> {
+        let mut layout =
+            Self::merge(files, configuration, interpreter, dynamic_linking, live_sections);
+        layout.page_size = page_size;
+
+        if configuration.relocatable {
+            layout.assign_offsets();
+
+            return Ok(layout);
+        }
+
+        layout.assign_addresses(image_base);
+
+        // The sections generated for the dynamic linking refer to the
+        // addresses of each other.
+        if let Some(dynamic) = layout.dynamic {
+            let endianness = files[0].file.endianness;
+            let entries = dynamic_linking.entries(&layout, endianness);
+
+            layout.sections[dynamic].data = entries;
+
+            if let Some(dynamic_hash) = layout.dynamic_hash {
+                layout.sections[dynamic_hash].data = dynamic_linking.hash_table(endianness);
+            }
+
+            if let (Some(plt), Some(plt_got), Some(plt_relocations)) =
+                (layout.plt, layout.plt_got, layout.plt_relocations)
+            {
+                let dynamic_address = layout.sections[dynamic].virtual_address;
+                let plt_address = layout.sections[plt].virtual_address;
+                let plt_got_address = layout.sections[plt_got].virtual_address;
+
+                layout.sections[plt].data = dynamic_linking.plt.stubs(plt_address, plt_got_address);
+                layout.sections[plt_got].data =
+                    dynamic_linking.plt.got(dynamic_address, plt_address, endianness);
+                layout.sections[plt_relocations].data =
+                    dynamic_linking.plt.relocations(plt_got_address, endianness);
+            }
+        }
+
+        layout.check_overlaps()?;
+
+        Ok(layout)
+    }
+
+    /// Get the number of program headers of the output file. A dynamic
+    /// output has a program for the dynamic entries, and a program for the
+    /// program headers themselves. An output with an `.eh_frame_hdr` section
+    /// or with thread-local sections has a program for them.
+    pub(super) fn program_count(&self) -> usize {
+        self.segments.len()
+            + usize::from(self.interpreter.is_some())
+            + 2 * usize::from(self.dynamic.is_some())
+            + usize::from(self.tls.is_some())
+            + usize::from(self.eh_frame_hdr.is_some())
+    }
+
+    /// Get the alignment of the thread-local storage template, i.e. the
+    /// biggest alignment of its sections, or 1 if there is none.
+    pub(super) fn tls_alignment(&self) -> u64 {
+        self.tls.as_ref().map_or(1, |tls| {
+            self.sections[tls.sections.clone()]
+                .iter()
+                .map(|section| section.alignment)
+                .fold(1, cmp::max)
+        })
+    }
+
+    /// Get the virtual address of the thread-local storage template, or 0 if
+    /// there is none. The values of the thread-local symbols are relative to
+    /// it.
+    pub(super) fn tls_address(&self) -> u64 {
+        self.tls.as_ref().map_or(0, |tls| tls.virtual_address)
+    }
+
+    /// Get the value of the thread pointer, as if the thread-local storage
+    /// block was at the address of its template.
+    ///
+    /// On x86-64, the thread-local storage block of the executable lies just
+    /// before the thread pointer, at an offset aligned as the block requires.
+    pub(super) fn thread_pointer(&self) -> u64 {
+        self.tls.as_ref().map_or(0, |tls| {
+            tls.virtual_address + tls.memory_size.next_multiple_of(self.tls_alignment())
+        })
+    }
+
+    /// Get the address of an offset inside an input section, if the section
+    /// has been placed in the output.
+    pub(super) fn address(
+        &self,
+        file_index: usize,
+        section_index: usize,
+        offset: u64,
+    ) -> Option<u64> {
+        self.output_offset(file_index, section_index, offset)
+            .map(|(section, offset)| self.sections[section].virtual_address + offset)
+    }
+
+    /// Get the output section, and the offset inside it, of an offset inside
+    /// an input section, if the section has been placed in the output.
+    ///
+    /// Prefer this method over [`Self::placement`] when the input section may
+    /// be a merged string section.
+    pub(super) fn output_offset(
+        &self,
+        file_index: usize,
+        section_index: usize,
+        offset: u64,
+    ) -> Option<(usize, u64)> {
+        let placement = self.placement(file_index, section_index)?;
+
+        match self.merged_strings.get(&(file_index, section_index)) {
+            Some(pieces) => Some((placement.section, merge::output_offset(pieces, offset)?)),
+            None => Some((placement.section, placement.offset + offset)),
+        }
+    }
+
+    /// Check whether an input section is a merged string section.
+    pub(super) fn is_merged_strings(&self, file_index: usize, section_index: usize) -> bool {
+        self.merged_strings.contains_key(&(file_index, section_index))
+    }
+
+    /// Get the placement of an input section, if any.
+    pub(super) fn placement(&self, file_index: usize, section_index: usize) -> Option<Placement> {
+        self.placements.get(file_index)?.get(section_index).copied().flatten()
+    }
+
+    /// Create output sections from allocable input sections, and concatenate
+    /// their data.
+    fn merge(
+        files: &[InputFile],
+        configuration: &Configuration,
+        interpreter: Option<&[u8]>,
+        dynamic_linking: &DynamicLinking,
+        live_sections: Option<&LiveSections>,
+    ) -> Self {
+        let is_output = |file_index: usize, section_index: usize, section: &Section| {
+            (is_loadable(section) || (!configuration.strip_debug && is_debug(section)))
+                && !files[file_index].is_discarded(section_index)
+                && live_sections
+                    .is_none_or(|live_sections| live_sections.is_live(file_index, section_index))
+        };
+        let fixed_address = |name: &BStr| {
+            configuration
+                .section_starts
+                .iter()
+                .find(|(section_name, _)| section_name.as_bytes() == name.as_bytes())
+                .map(|(_, address)| *address)
+        };
+
+        // Collect the output sections, in order of appearance. The `.interp`
+        // section, if any, comes first.
+        let mut sections = Vec::<OutputSection>::new();
+
+        if let Some(interpreter) = interpreter {
+            let mut data = interpreter.to_vec();
+            data.push(0x00);
+
+            sections.push(OutputSection {
+                name: BString::from(INTERPRETER_SECTION_NAME),
+                r#type: SectionType::ProgramData,
+                flags: SectionFlag::Allocable.into(),
+                alignment: 1,
+                data,
+                no_bits_size: 0,
+                offset: 0,
+                virtual_address: 0,
+                fixed_address: fixed_address(INTERPRETER_SECTION_NAME.as_bytes().as_bstr()),
+            });
+        }
+
+        if !dynamic_linking.is_empty() {
+            sections.push(OutputSection {
+                name: BString::from(DYNAMIC_HASH_SECTION_NAME),
+                r#type: SectionType::SymbolHashTable,
+                flags: SectionFlag::Allocable.into(),
+                alignment: 8,
+                data: vec![0; dynamic_linking.hash_size()],
+                no_bits_size: 0,
+                offset: 0,
+                virtual_address: 0,
+                fixed_address: fixed_address(DYNAMIC_HASH_SECTION_NAME.as_bytes().as_bstr()),
+            });
+
+            sections.push(OutputSection {
+                name: BString::from(DYNAMIC_SYMBOLS_SECTION_NAME),
+                r#type: SectionType::DynamicLoaderSymbolTable,
+                flags: SectionFlag::Allocable.into(),
+                alignment: 8,
+                data: vec![0; dynamic_linking.symbols_size()],
+                no_bits_size: 0,
+                offset: 0,
+                virtual_address: 0,
+                fixed_address: fixed_address(DYNAMIC_SYMBOLS_SECTION_NAME.as_bytes().as_bstr()),
+            });
+
+            sections.push(OutputSection {
+                name: BString::from(DYNAMIC_STRINGS_SECTION_NAME),
+                r#type: SectionType::StringTable,
+                flags: SectionFlag::Allocable.into(),
+                alignment: 1,
+                data: dynamic_linking.strings.clone(),
+                no_bits_size: 0,
+                offset: 0,
+                virtual_address: 0,
+                fixed_address: fixed_address(DYNAMIC_STRINGS_SECTION_NAME.as_bytes().as_bstr()),
+            });
+
+            // The entries are written once the addresses are assigned.
+            sections.push(OutputSection {
+                name: BString::from(DYNAMIC_SECTION_NAME),
+                r#type: SectionType::DynamicLinkingTable,
+                flags: SectionFlag::Allocable | SectionFlag::Writable,
+                alignment: 8,
+                data: vec![0; dynamic_linking.size()],
+                no_bits_size: 0,
+                offset: 0,
+                virtual_address: 0,
+                fixed_address: fixed_address(DYNAMIC_SECTION_NAME.as_bytes().as_bstr()),
+            });
+        }
+
+        // The PLT stubs, their GOT slots and their relocations are written
+        // once the addresses are assigned.
+        if !dynamic_linking.plt.is_empty() {
+            sections.push(OutputSection {
+                name: BString::from(PLT_RELOCATIONS_SECTION_NAME),
+                r#type: SectionType::RelocationWithAddends,
+                flags: SectionFlag::Allocable | SectionFlag::InfoLink,
+                alignment: 8,
+                data: vec![0; dynamic_linking.plt.relocations_size()],
+                no_bits_size: 0,
+                offset: 0,
+                virtual_address: 0,
+                fixed_address: fixed_address(PLT_RELOCATIONS_SECTION_NAME.as_bytes().as_bstr()),
+            });
+
+            sections.push(OutputSection {
+                name: BString::from(PLT_SECTION_NAME),
+                r#type: SectionType::ProgramData,
+                flags: SectionFlag::Allocable | SectionFlag::Executable,
+                alignment: 16,
+                data: vec![0; dynamic_linking.plt.size()],
+                no_bits_size: 0,
+                offset: 0,
+                virtual_address: 0,
+                fixed_address: fixed_address(PLT_SECTION_NAME.as_bytes().as_bstr()),
+            });
+
+            sections.push(OutputSection {
+                name: BString::from(PLT_GOT_SECTION_NAME),
+                r#type: SectionType::ProgramData,
+                flags: SectionFlag::Allocable | SectionFlag::Writable,
+                alignment: 8,
+                data: vec![0; dynamic_linking.plt.got_size()],
+                no_bits_size: 0,
+                offset: 0,
+                virtual_address: 0,
+                fixed_address: fixed_address(PLT_GOT_SECTION_NAME.as_bytes().as_bstr()),
+            });
+        }
+
+        for (file_index, input_file) in files.iter().enumerate() {
+            for (_, section) in
+                input_file.file.sections.iter().enumerate().filter(|(section_index, section)| {
+                    is_output(file_index, *section_index, section)
+                })
+            {
+                let name = output_section_name(section);
+                // The groups are resolved: the output sections belong to none.
+                let mut flags = section.flags;
+                flags.remove(SectionFlag::IsPartOfAGroup);
+
+                match sections.iter_mut().find(|output_section| output_section.name == name) {
+                    Some(output_section) => {
+                        output_section.flags |= flags;
+
+                        // An output section is `NoBits` only if all its input
+                        // sections are.
+                        if output_section.r#type == SectionType::NoBits {
+                            output_section.r#type = section.r#type;
+                        }
+
+                        // An `.eh_frame` section mixing `SHT_PROGBITS` and
+                        // `SHT_X86_64_UNWIND` input sections is `ProgramData`.
+                        if output_section.r#type == SectionType::Unwind
+                            && section.r#type == SectionType::ProgramData
+                        {
+                            output_section.r#type = SectionType::ProgramData;
+                        }
+                    }
+                    None => sections.push(OutputSection {
+                        name: name.to_owned(),
+                        r#type: section.r#type,
+                        flags,
+                        alignment: 1,
+                        data: Vec::new(),
+                        no_bits_size: 0,
+                        offset: 0,
+                        virtual_address: 0,
+                        fixed_address: fixed_address(name),
+                    }),
+                }
+            }
+        }
+
+        // The `.eh_frame_hdr` section comes before the `.eh_frame` section. It
+        // has an entry per FDE.
+        if let Some(eh_frame) = sections
+            .iter()
+            .position(|section| section.name == EH_FRAME_SECTION_NAME)
+            .filter(|eh_frame| sections[*eh_frame].flags.contains(SectionFlag::Allocable))
+            .filter(|_| !configuration.relocatable)
+        {
+            let size = eh_frame::hdr_size(files.iter().enumerate().flat_map(
+                |(file_index, input_file)| {
+                    input_file.file.sections.iter().enumerate().filter_map(
+                        move |(section_index, section)| {
+                            (output_section_name(section) == EH_FRAME_SECTION_NAME
+                                && is_output(file_index, section_index, section))
+                            .then_some(section)
+                        },
+                    )
+                },
+            ));
+
+            sections.insert(
+                eh_frame,
+                OutputSection {
+                    name: BString::from(EH_FRAME_HDR_SECTION_NAME),
+                    r#type: SectionType::ProgramData,
+                    flags: SectionFlag::Allocable.into(),
+                    alignment: 4,
+                    data: vec![0; size],
+                    no_bits_size: 0,
+                    offset: 0,
+                    virtual_address: 0,
+                    fixed_address: fixed_address(EH_FRAME_HDR_SECTION_NAME.as_bytes().as_bstr()),
+                },
+            );
+        }
+
+        // Sort output sections by segments, and place the `NoBits` sections at
+        // the end of their segment. The thread-local sections come first in
+        // their segment, so that they are contiguous. Non-allocable sections
+        // come last. The sort is stable, so the order of appearance is kept
+        // otherwise.
+        sections.sort_by_key(|section| {
+            (
+                !section.flags.contains(SectionFlag::Allocable),
+                segment_rank(segment_flags(section.flags)),
+                !is_thread_local(section.flags),
+                section.r#type == SectionType::NoBits,
+            )
+        });
+
+        // Collect the contributions of the input sections to the output
+        // sections, and sort them if asked. The sort is stable, so the input
+        // order is kept otherwise.
+        let mut contributions = files
+            .iter()
+            .enumerate()
+            .flat_map(|(file_index, input_file)| {
+                input_file
+                    .file
+                    .sections
+                    .iter()
+                    .enumerate()
+                    .filter(move |(input_section_index, section)| {
+                        is_output(file_index, *input_section_index, section)
+                    })
+                    .map(move |(input_section_index, section)| {
+                        (file_index, input_section_index, section)
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        match configuration.sort_section {
+            Some(SortSection::Name) => {
+                contributions.sort_by(|(_, _, left), (_, _, right)| {
+                    section_name(left).cmp(section_name(right))
+                });
+            }
+            Some(SortSection::Alignment) => {
+                contributions.sort_by_key(|(_, _, section)| {
+                    cmp::Reverse(section.alignment.get().map_or(1, NonZeroU64::get))
+                });
+            }
+            None => {}
+        }
+
+        // Constructors and destructors with a priority, e.g. in
+        // `.init_array.00100`, come first, by increasing priority. The others
+        // keep the input order.
+        contributions.sort_by_key(|(_, _, section)| array_priority(section));
+
+        // Concatenate input sections into output sections. Merged string
+        // sections are collected, and merged afterwards.
+        let mut placements = files
+            .iter()
+            .map(|input_file| vec![None; input_file.file.sections.len()])
+            .collect::<Vec<_>>();
+        let mut strings_to_merge = HashMap::<usize, Vec<(usize, usize)>>::new();
+
+        for (file_index, input_section_index, section) in contributions {
+            let name = output_section_name(section);
+            let (section_index, output_section) = sections
+                .iter_mut()
+                .enumerate()
+                .find(|(_, output_section)| output_section.name == name)
+                .expect("The output section must have been created");
+
+            let alignment = section.alignment.get().map_or(1, NonZeroU64::get);
+            output_section.alignment = output_section.alignment.max(alignment);
+
+            if is_mergeable_strings(section) {
+                strings_to_merge
+                    .entry(section_index)
+                    .or_default()
+                    .push((file_index, input_section_index));
+                placements[file_index][input_section_index] =
+                    Some(Placement { section: section_index, offset: 0 });
+
+                continue;
+            }
+
+            let offset = section.alignment.align_up(output_section.memory_size());
+
+            // `NoBits` sections only occupy memory, unless they are merged with
+            // sections that have data.
+            if output_section.r#type == SectionType::NoBits {
+                output_section.no_bits_size = offset + section.data.logical_size();
+            } else if section.r#type == SectionType::NoBits {
+                let size = offset + section.data.logical_size();
+                output_section.data.resize(size as usize, 0);
+            } else {
+                output_section.data.resize(offset as usize, 0);
+                output_section.data.extend_from_slice(&section.data);
+            }
+
+            placements[file_index][input_section_index] =
+                Some(Placement { section: section_index, offset });
+        }
+
+        let mut merged_strings = HashMap::new();
+
+        for (section_index, inputs) in strings_to_merge {
+            let (pool, pieces) = merge::merge_strings(
+                &inputs
+                    .iter()
+                    .map(|(file_index, input_section_index)| {
+                        &*files[*file_index].file.sections[*input_section_index].data
+                    })
+                    .map(|data| &**data)
+                    .collect::<Vec<_>>(),
+            );
+
+            sections[section_index].data = pool;
+            merged_strings.extend(inputs.into_iter().zip(pieces));
+        }
+
+        // The link of a `LinkOrder` input section points to another input
+        // section of the same file: now that the indices of the output sections
+        // are known, rewrite it to the output section of the latter.
+        let mut links = HashMap::new();
+
+        for (file_index, input_file) in files.iter().enumerate() {
+            for (input_section_index, section) in input_file.file.sections.iter().enumerate() {
+                if !section.flags.contains(SectionFlag::LinkOrder) {
+                    continue;
+                }
+
+                let (Some(placement), SectionIndex::Ok(linked_section_index)) =
+                    (placements[file_index][input_section_index], &section.link)
+                else {
+                    continue;
+                };
+
+                if let Some(linked_placement) =
+                    placements[file_index].get(*linked_section_index).copied().flatten()
+                {
+                    links.entry(placement.section).or_insert(linked_placement.section);
+                }
+            }
+        }
+
+        // Group output sections into segments. The first segment is always
+        // present: it holds the file header and the program headers.
+        let mut segments = vec![Segment {
+            flags: ProgramFlag::Read.into(),
+            sections: 0..0,
+            offset: 0,
+            virtual_address: 0,
+            file_size: 0,
+            memory_size: 0,
+        }];
+
+        for (section_index, section) in sections
+            .iter()
+            .enumerate()
+            .take_while(|(_, section)| section.flags.contains(SectionFlag::Allocable))
+        {
+            let flags = segment_flags(section.flags);
+            let segment = segments.last_mut().expect("There is at least one segment");
+
+            // A section with a fixed address always starts a new segment.
+            if segment.flags == flags && section.fixed_address.is_none() {
+                segment.sections.end = section_index + 1;
+            } else {
+                segments.push(Segment {
+                    flags,
+                    sections: section_index..section_index + 1,
+                    offset: 0,
+                    virtual_address: 0,
+                    file_size: 0,
+                    memory_size: 0,
+                });
+            }
+        }
+
+        // The thread-local sections form the thread-local storage template.
+        // Its first section is aligned as the whole template, so that the
+        // offsets inside the template keep the alignments.
+        let tls = sections.iter().position(|section| is_thread_local(section.flags)).map(|first| {
+            let end = first
+                + sections[first..]
+                    .iter()
+                    .take_while(|section| is_thread_local(section.flags))
+                    .count();
+            let alignment =
+                sections[first..end].iter().map(|section| section.alignment).fold(1, cmp::max);
+            sections[first].alignment = alignment;
+
+            Segment {
+                flags: ProgramFlag::Read.into(),
+                sections: first..end,
+                offset: 0,
+                virtual_address: 0,
+                file_size: 0,
+                memory_size: 0,
+            }
+        });
+
+        let interpreter = interpreter.and_then(|_| {
+            sections.iter().position(|section| section.name == INTERPRETER_SECTION_NAME)
+        });
+
+        let position = |name: &str| sections.iter().position(|section| section.name == name);
+        let dynamic = position(DYNAMIC_SECTION_NAME).filter(|_| !dynamic_linking.is_empty());
+        let dynamic_strings =
+            position(DYNAMIC_STRINGS_SECTION_NAME).filter(|_| !dynamic_linking.is_empty());
+        let dynamic_symbols =
+            position(DYNAMIC_SYMBOLS_SECTION_NAME).filter(|_| !dynamic_linking.is_empty());
+        let dynamic_hash =
+            position(DYNAMIC_HASH_SECTION_NAME).filter(|_| !dynamic_linking.is_empty());
+        let plt = position(PLT_SECTION_NAME).filter(|_| !dynamic_linking.plt.is_empty());
+        let plt_got = position(PLT_GOT_SECTION_NAME).filter(|_| !dynamic_linking.plt.is_empty());
+        let plt_relocations =
+            position(PLT_RELOCATIONS_SECTION_NAME).filter(|_| !dynamic_linking.plt.is_empty());
+        let init_array = position(INIT_ARRAY_SECTION_NAME);
+        let fini_array = position(FINI_ARRAY_SECTION_NAME);
+        let eh_frame = position(EH_FRAME_SECTION_NAME);
+        let eh_frame_hdr = position(EH_FRAME_HDR_SECTION_NAME);
+
+        Self {
+            sections,
+            segments,
+            tls,
+            placements,
+            merged_strings,
+            links,
+            interpreter,
+            dynamic,
+            dynamic_strings,
+            dynamic_symbols,
+            dynamic_hash,
+            plt,
+            plt_got,
+            plt_relocations,
+            init_array,
+            fini_array,
+            eh_frame,
+            eh_frame_hdr,
+            end_offset: 0,
+            page_size: DEFAULT_MAX_PAGE_SIZE,
+        }
+    }
+
+    /// Assign an offset and a virtual address to all segments and output
+    /// sections.
+    fn assign_addresses(&mut self, image_base: u64) {
+        let headers_size = (File::HEADER_SIZE + self.program_count() * Program::SIZE) as u64;
+        let mut offset = 0u64;
+        // The difference between the virtual addresses and the offsets of the
+        // current segment.
+        let mut address_delta = image_base;
+
+        for (nth, segment) in self.segments.iter_mut().enumerate() {
+            offset = offset.next_multiple_of(self.page_size);
+
+            // The offset of a segment must be congruent to its virtual address
+            // modulo the page size.
+            if let Some(fixed_address) = self.sections[segment.sections.clone()]
+                .first()
+                .and_then(|section| section.fixed_address)
+            {
+                offset += fixed_address % self.page_size;
+                address_delta = fixed_address.wrapping_sub(offset);
+            }
+
+            segment.offset = offset;
+            segment.virtual_address = offset.wrapping_add(address_delta);
+
+            if nth == 0 {
+                offset += headers_size;
+            }
+
+            // `NoBits` sections are at the end of the segment, or at the end
+            // of the thread-local sections: they move the virtual address, but
+            // not the offset.
+            let mut address = offset.wrapping_add(address_delta);
+
+            for section in &mut self.sections[segment.sections.clone()] {
+                if section.fixed_address.is_none() {
+                    address = address.next_multiple_of(section.alignment);
+                }
+
+                if section.r#type != SectionType::NoBits {
+                    offset = address.wrapping_sub(address_delta);
+                }
+
+                section.offset = offset;
+                section.virtual_address = address;
+
+                offset += section.data.len() as u64;
+                address += section.memory_size();
+            }
+
+            segment.file_size = offset - segment.offset;
+            segment.memory_size = address.wrapping_sub(segment.virtual_address);
+
+            // The next segment must start after the memory of this segment,
+            // while keeping offsets and virtual addresses congruent.
+            address_delta = address_delta.wrapping_add(
+                (segment.memory_size - segment.file_size).next_multiple_of(self.page_size),
+            );
+        }
+
+        // The thread-local storage template has a file image for its
+        // sections with data, and occupies memory for all its sections.
+        if let Some(tls) = &mut self.tls {
+            let sections = &self.sections[tls.sections.clone()];
+            let first = &sections[0];
+            let last = &sections[sections.len() - 1];
+
+            tls.offset = first.offset;
+            tls.virtual_address = first.virtual_address;
+            tls.file_size = sections
+                .iter()
+                .filter(|section| section.r#type != SectionType::NoBits)
+                .map(|section| section.offset + section.data.len() as u64 - first.offset)
+                .max()
+                .unwrap_or(0);
+            tls.memory_size = last.virtual_address + last.memory_size() - first.virtual_address;
+        }
+
+        self.end_offset = offset;
+    }
+
+    /// Assign an offset to all allocable output sections, for a relocatable
+    /// output: there is no segment, and the virtual addresses stay 0, so that
+    /// the addresses inside the output sections are relative to them.
+    fn assign_offsets(&mut self) {
+        self.segments.clear();
+        self.tls = None;
+
+        let mut offset = File::HEADER_SIZE as u64;
+
+        for section in self
+            .sections
+            .iter_mut()
+            .take_while(|section| section.flags.contains(SectionFlag::Allocable))
+        {
+            offset = offset.next_multiple_of(section.alignment);
+            section.offset = offset;
+            offset += section.data.len() as u64;
+        }
+
+        self.end_offset = offset;
+    }
+
+    /// Check that no segments overlap in memory, at the page granularity,
+    /// and that no loaded sections overlap, in memory or in the file image.
+    fn check_overlaps(&self) -> Result<(), Error> {
+        let segments = self
+            .segments
+            .iter()
+            .enumerate()
+            .filter(|(nth, segment)| *nth == 0 || segment.memory_size > 0)
+            .map(|(_, segment)| {
+                let start = segment.virtual_address - segment.virtual_address % self.page_size;
+                let end = (segment.virtual_address + segment.memory_size)
+                    .next_multiple_of(self.page_size);
+                let name = match self.sections[segment.sections.clone()].first() {
+                    Some(section) => section.name.to_str_lossy().into_owned(),
+                    None => "file headers".to_owned(),
+                };
+
+                (start..cmp::max(end, start + 1), name)
+            });
+
+        if let Some((left, right)) = find_overlap(segments) {
+            return Err(Error::OverlappingSections(left, right));
+        }
+
+        let loaded_sections = self
+            .segments
+            .iter()
+            .flat_map(|segment| &self.sections[segment.sections.clone()])
+            .collect::<Vec<_>>();
+
+        let in_memory =
+            loaded_sections.iter().filter(|section| section.memory_size() > 0).map(|section| {
+                (
+                    section.virtual_address..section.virtual_address + section.memory_size(),
+                    section.name.to_str_lossy().into_owned(),
+                )
+            });
+
+        if let Some((left, right)) = find_overlap(in_memory) {
+            return Err(Error::OverlappingSections(left, right));
+        }
+
+        let in_file =
+            loaded_sections.iter().filter(|section| !section.data.is_empty()).map(|section| {
+                (
+                    section.offset..section.offset + section.data.len() as u64,
+                    section.name.to_str_lossy().into_owned(),
+                )
+            });
+
+        if let Some((left, right)) = find_overlap(in_file) {
+            return Err(Error::OverlappingSectionsInFile(left, right));
+        }
+
+        Ok(())
+    }
+}
+
+/// Find two named ranges that overlap, if any.
+fn find_overlap<I>(ranges: I) -> Option<(String, String)>
+where
+    I: Iterator<Item = (Range<u64>, String)>,
+{
+    let mut ranges = ranges.collect::<Vec<_>>();
+    ranges.sort_by_key(|(range, _)| range.start);
+
+    ranges.windows(2).find_map(|pair| {
+        let [(left, left_name), (right, right_name)] = pair else { unreachable!() };
+
+        (left.end > right.start).then(|| (left_name.clone(), right_name.clone()))
+    })
+}
+
+/// Check whether an input section must be loaded in memory, and thus be part
+/// of a segment. On x86-64, the unwind information, i.e. `.eh_frame`, may be
+/// typed `SHT_X86_64_UNWIND` instead of `SHT_PROGBITS`, e.g. by LLVM.
+fn is_loadable(section: &Section) -> bool {
+    section.flags.contains(SectionFlag::Allocable)
+        && matches!(
+            section.r#type,
+            SectionType::ProgramData
+                | SectionType::Unwind
+                | SectionType::NoBits
+                | SectionType::Note
+                | SectionType::ArrayOfConstructors
+                | SectionType::ArrayOfDestructors
+                | SectionType::ArrayOfPreConstructors
+        )
+}
+
+/// Check whether an input section holds debug information, like
+/// `.debug_info`.
+fn is_debug(section: &Section) -> bool {
+    !section.flags.contains(SectionFlag::Allocable)
+        && section.r#type == SectionType::ProgramData
+        && section_name(section).starts_with(b".debug_")
+}
+
+/// Get the name of an input section, or an empty name if it has none.
+fn section_name<'a>(section: &'a Section) -> &'a BStr {
+    section.name.as_ref().map(|name| name.as_bstr()).unwrap_or_default()
+}
+
+/// Check whether an input section contains null-terminated strings that can be
+/// merged.
+fn is_mergeable_strings(section: &Section) -> bool {
+    section.flags.contains(SectionFlag::Merge | SectionFlag::Strings)
+        && section.entity_size.is_some_and(|entity_size| entity_size.get() == 1)
+}
+
+/// Get the name of the output section for a given input section, e.g.
+/// `.text.foo` is merged into `.text`.
+///
+/// Mergeable string sections keep their own name: they are merged into their
+/// own string pool.
+fn output_section_name<'a>(section: &'a Section) -> &'a BStr {
+    const PREFIXES: &[&str] = &[
+        ".text",
+        ".rodata",
+        ".data",
+        ".bss",
+        ".tdata",
+        ".tbss",
+        INIT_ARRAY_SECTION_NAME,
+        FINI_ARRAY_SECTION_NAME,
+    ];
+
+    let name = section_name(section);
+
+    if is_mergeable_strings(section) {
+        return name;
+    }
+
+    for prefix in PREFIXES {
+        if let Some(suffix) = name.strip_prefix(prefix.as_bytes()) {
+            if suffix.is_empty() || suffix.starts_with(b".") {
+                return prefix.as_bytes().as_bstr();
+            }
+        }
+    }
+
+    name
+}
+
+/// Get the priority of an array of constructors or destructors, from the
+/// suffix of its name, e.g. `100` for `.init_array.00100`.
+///
+/// Sections without a priority, including the sections that are not arrays,
+/// come last.
+fn array_priority(section: &Section) -> u64 {
+    let name = section_name(section);
+
+    [INIT_ARRAY_SECTION_NAME, FINI_ARRAY_SECTION_NAME]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix.as_bytes())?.strip_prefix(b"."))
+        .and_then(|priority| priority.to_str().ok()?.parse::<u64>().ok())
+        .unwrap_or(u64::MAX)
+}
+
+/// Check whether a section holds thread-local data, i.e. is part of the
+/// thread-local storage template, like `.tdata` or `.tbss`.
+fn is_thread_local(section_flags: SectionFlags) -> bool {
+    section_flags.contains(SectionFlag::Allocable | SectionFlag::HasThreadLocalData)
+}
+
+/// Get the flags of the segment that will contain a section with the given
+/// flags.
+fn segment_flags(section_flags: SectionFlags) -> ProgramFlags {
+    let mut flags = ProgramFlags::from(ProgramFlag::Read);
+
+    if section_flags.contains(SectionFlag::Writable) {
+        flags |= ProgramFlag::Write;
+    }
+
+    if section_flags.contains(SectionFlag::Executable) {
+        flags |= ProgramFlag::Execute;
+    }
+
+    flags
+}
+
+/// Rank segments: read-only first, then executable, then writable.
+fn segment_rank(flags: ProgramFlags) -> u8 {
+    match (flags.contains(ProgramFlag::Execute), flags.contains(ProgramFlag::Write)) {
+        (false, false) => 0,
+        (true, false) => 1,
+        (false, true) => 2,
+        (true, true) => 3,
+    }
+}
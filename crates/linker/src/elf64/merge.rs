@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+/// A null-terminated string of an input section, moved in a string pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct StringPiece {
+    /// Offset of the string in the input section.
+    pub(super) input_offset: u64,
+    /// Offset of the string in the string pool.
+    pub(super) output_offset: u64,
+}
+
+/// Merge the null-terminated strings of several sections into a single string
+/// pool.
+///
+/// Identical strings are stored once, and a string that is a suffix of another
+/// string shares the storage of its tail, e.g. `"lo"` is stored inside
+/// `"hello"`. This is called tail merging.
+///
+/// It returns the string pool, along with the pieces of each section, sorted
+/// by input offset. Bytes after the last null byte of a section are ignored.
+pub(super) fn merge_strings(sections: &[&[u8]]) -> (Vec<u8>, Vec<Vec<StringPiece>>) {
+    // Collect all the strings, without their null byte.
+    let strings_per_section = sections
+        .iter()
+        .map(|section| {
+            let mut input_offset = 0;
+
+            section
+                .split_inclusive(|byte| *byte == 0)
+                .filter(|string| string.ends_with(&[0]))
+                .map(|string| {
+                    let piece = (input_offset, &string[..string.len() - 1]);
+                    input_offset += string.len() as u64;
+
+                    piece
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // Sort the unique strings by their reversed bytes, in descending order: a
+    // string that is a suffix of another string comes right after it.
+    let mut strings =
+        strings_per_section.iter().flatten().map(|(_, string)| *string).collect::<Vec<_>>();
+    strings.sort_unstable_by(|left, right| right.iter().rev().cmp(left.iter().rev()));
+    strings.dedup();
+
+    // Build the pool.
+    let mut pool = Vec::new();
+    let mut output_offsets = HashMap::<&[u8], u64>::with_capacity(strings.len());
+    let mut previous: Option<(&[u8], u64)> = None;
+
+    for string in strings {
+        let output_offset = match previous {
+            Some((previous_string, previous_offset)) if previous_string.ends_with(string) => {
+                previous_offset + (previous_string.len() - string.len()) as u64
+            }
+
+            _ => {
+                let output_offset = pool.len() as u64;
+                pool.extend_from_slice(string);
+                pool.push(0);
+                previous = Some((string, output_offset));
+
+                output_offset
+            }
+        };
+
+        output_offsets.insert(string, output_offset);
+    }
+
+    let pieces = strings_per_section
+        .iter()
+        .map(|strings| {
+            strings
+                .iter()
+                .map(|(input_offset, string)| StringPiece {
+                    input_offset: *input_offset,
+                    output_offset: output_offsets[string],
+                })
+                .collect()
+        })
+        .collect();
+
+    (pool, pieces)
+}
+
+/// Merge the strings of a string table, e.g. `.strtab` or `.dynstr`, like
+/// [`merge_strings`] does. The empty string stays at offset 0, as required by
+/// the ELF format.
+///
+/// It returns the merged string table, along with a function mapping an offset
+/// in `strings` to an offset in the merged string table.
+pub(super) fn merge_string_table(strings: &[u8]) -> (Vec<u8>, impl Fn(u64) -> u64) {
+    let (pool, mut pieces) = merge_strings(&[strings.get(1..).unwrap_or_default()]);
+    let pieces = pieces.pop().unwrap_or_default();
+
+    let mut table = Vec::with_capacity(pool.len() + 1);
+    table.push(0);
+    table.extend_from_slice(&pool);
+
+    let offset = move |input_offset: u64| match input_offset {
+        0 => 0,
+        input_offset => output_offset(&pieces, input_offset - 1).map_or(0, |offset| offset + 1),
+    };
+
+    (table, offset)
+}
+
+/// Find the offset in the string pool of an offset in an input section.
+///
+/// `pieces` must be sorted by input offset.
+pub(super) fn output_offset(pieces: &[StringPiece], input_offset: u64) -> Option<u64> {
+    let index = pieces.partition_point(|piece| piece.input_offset <= input_offset);
+    let piece = pieces.get(index.checked_sub(1)?)?;
+
+    Some(piece.output_offset + (input_offset - piece.input_offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_strings() {
+        let (pool, pieces) = merge_strings(&[b"hello\0", b"lo\0world\0hello\0"]);
+
+        assert_eq!(pool, b"hello\0world\0");
+        assert_eq!(
+            pieces,
+            [
+                vec![StringPiece { input_offset: 0, output_offset: 0 }],
+                vec![
+                    // `"lo"` is the tail of `"hello"`.
+                    StringPiece { input_offset: 0, output_offset: 3 },
+                    StringPiece { input_offset: 3, output_offset: 6 },
+                    StringPiece { input_offset: 9, output_offset: 0 },
+                ],
+            ]
+        );
+
+        assert_eq!(output_offset(&pieces[1], 1), Some(4));
+        assert_eq!(output_offset(&pieces[1], 4), Some(7));
+    }
+
+    #[test]
+    fn test_merge_string_table() {
+        // Two string tables, concatenated, both with `"main"`.
+        let (table, offset) = merge_string_table(b"\0main\0foo\0main\0domain\0");
+
+        assert_eq!(table, b"\0foo\0domain\0");
+
+        // The empty string stays at offset 0.
+        assert_eq!(offset(0), 0);
+        // Both `"main"` point to the same string, which is the tail of
+        // `"domain"`.
+        assert_eq!(offset(1), 7);
+        assert_eq!(offset(10), 7);
+        assert_eq!(offset(6), 1);
+        assert_eq!(offset(15), 5);
+        assert_eq!(&table[7..12], b"main\0");
+    }
+}
@@ -1,12 +1,50 @@
-use std::{io, num::NonZeroUsize};
+use std::{
+    borrow::Cow,
+    fs,
+    io::{self, BufWriter, IntoInnerError, Read as _},
+    num::{NonZeroU64, NonZeroUsize},
+    ops::Deref,
+    path::{Path, PathBuf},
+};
 
 use async_channel::unbounded;
+use bstr::{BStr, BString, ByteSlice};
 use futures_lite::future::block_on;
 use weld_errors::error;
 use weld_file::{FileReader, Picker as FilePicker};
+use weld_object::{
+    arena::Arena,
+    elf64::{
+        Address, Alignment, Builder, Data, DataType, Dynamic, Endianness, FileType, Machine, OsAbi,
+        ProgramFlag, ProgramType, Relocation, SectionFlag, SectionFlags, SectionIndex, SectionType,
+    },
+    errors::{Error as ObjectError, ErrorKind, FileError, InvalidHeader},
+    prelude::*,
+};
 use weld_scheduler::ThreadPool;
 
-use crate::Configuration;
+use crate::{
+    target::{self, Architecture, OperatingSystem, Triple},
+    Configuration, LinkPhase, PostProcess, Warning, Warnings,
+};
+
+mod archive;
+mod dynamic;
+mod eh_frame;
+mod gc;
+mod groups;
+mod layout;
+mod merge;
+mod plt;
+mod relocations;
+mod symbols;
+
+use dynamic::DynamicLinking;
+use gc::LiveSections;
+use layout::{Layout, DEFAULT_IMAGE_BASE, DEFAULT_MAX_PAGE_SIZE};
+use plt::PLT_ENTRY_SIZE;
+use relocations::OutputRelocations;
+use symbols::{Names, SymbolTable, Symbols};
 
 error! {
     #[doc = "Elf64 errors."]
@@ -20,39 +58,759 @@ error! {
         #[help = "?"]
         ThreadPoolChannelClosed,
 
+        #[message = "I was not able to read an input file."]
+        #[formatted_message("I was not able to read the input file `{}`: {}.", .0.display(), .1)]
+        #[help = "Does the file exist, and is it readable?"]
+        ReadingFile(PathBuf, io::Error),
+
         #[code = E004]
         #[message = "I was not able to parse an object file correctly."]
-        #[formatted_message("I was not able to parse an object file correctly: {0}")]
-        #[help = "?"]
-        ObjectParser(weld_object::errors::Error<()>),
+        #[formatted_message("I was not able to parse the object file `{}` correctly.", .0.display())]
+        #[help = "Is it really an Elf64 object file?"]
+        ParsingFile(PathBuf),
+
+        #[code = E013]
+        #[message = "The file header of an object file is truncated."]
+        #[formatted_message("The file header of the object file `{}` is truncated.", .0.display())]
+        #[help = "Is the file complete? It may have been partially written."]
+        TruncatedFileHeader(PathBuf),
+
+        #[code = E023]
+        #[message = "A header of an object file is malformed."]
+        #[formatted_message("In the object file `{}`, {}.", .0.display(), .1)]
+        #[help = "The object file seems to be corrupted, or produced by a buggy tool."]
+        InvalidHeader(PathBuf, InvalidHeader),
+
+        #[code = E026]
+        #[message = "An input file is not an ELF file."]
+        #[formatted_message("The input file `{}` is not an ELF file: {}.", .0.display(), .1)]
+        #[help = "Is it really an object file? It may be a script or a text file given by mistake."]
+        NotAnElfFile(PathBuf, InvalidHeader),
+
+        #[message = "I was not able to parse the symbols of an object file correctly."]
+        #[formatted_message("I was not able to parse the symbols of the object file `{}` correctly.", .0.display())]
+        #[help = "The symbol table of this file seems to be malformed."]
+        ParsingSymbol(PathBuf),
+
+        #[message = "I was not able to parse an archive correctly."]
+        #[formatted_message("I was not able to parse the archive `{}` correctly.", .0.display())]
+        #[help = "Is it a valid static archive, as produced by `ar`?"]
+        ParsingArchive(PathBuf),
+
+        #[code = E024]
+        #[message = "The name of a symbol of an object file is malformed."]
+        #[formatted_message("The name of the symbol #{1} of the object file `{}`, at offset `{2}` of the string table, is malformed.", .0.display())]
+        #[help = "The string table of this file seems to be malformed."]
+        MalformedSymbolName(PathBuf, usize, u64),
+
+        #[message = "I was not able to parse the relocations of an object file correctly."]
+        #[formatted_message("I was not able to parse the relocations of the object file `{}` correctly.", .0.display())]
+        #[help = "The relocation tables of this file seem to be malformed."]
+        ParsingRelocation(PathBuf),
+
+        #[code = E005]
+        #[message = "A symbol is used but is never defined."]
+        #[formatted_message("The symbol `{0}` is used but is never defined.")]
+        #[help = "Maybe an object file or a library is missing from the input files?"]
+        UndefinedSymbol(String),
+
+        #[code = E006]
+        #[message = "A symbol is defined more than once."]
+        #[formatted_message("The symbol `{0}` is defined in `{}` and in `{}`.", .1.display(), .2.display())]
+        #[help = "Maybe the same object file has been given twice?"]
+        DuplicateSymbol(String, PathBuf, PathBuf),
+
+        #[code = E021]
+        #[message = "A symbol defined with `--defsym` is defined by an input file too."]
+        #[formatted_message("The symbol `{0}` is defined with `--defsym` and in `{}`.", .1.display())]
+        #[help = "Remove the `--defsym` option, or the definition in the input file."]
+        DefinedSymbolConflict(String, PathBuf),
+
+        #[code = E007]
+        #[message = "I don't support a relocation type yet."]
+        #[formatted_message("I don't support the relocation type `{0}` found in `{}` yet.", .1.display())]
+        #[help = "Please open an issue with the object file, it will help!"]
+        UnsupportedRelocation(String, PathBuf),
+
+        #[code = E008]
+        #[message = "The value computed by a relocation doesn't fit in its field."]
+        #[formatted_message("The value computed by the relocation `{0}` against the symbol `{1}` doesn't fit in its field.")]
+        #[help = "The symbol is probably too far from where it's used."]
+        RelocationOverflow(String, String),
+
+        #[code = E009]
+        #[message = "The entry point of the program is missing."]
+        #[formatted_message("The entry point of the program, `_start`, is missing.")]
+        #[help = "Maybe an object file is missing from the input files?"]
+        MissingEntryPoint,
+
+        #[code = E010]
+        #[message = "The image base is not aligned to the page size."]
+        #[formatted_message("The image base, `{0:#x}`, is not aligned to the page size, `{1:#x}`.")]
+        #[help = "Try an image base that is a multiple of the page size, like `0x400000`."]
+        ImageBaseNotPageAligned(u64, u64),
+
+        #[code = E011]
+        #[message = "Two sections overlap in memory."]
+        #[formatted_message("The `{0}` and `{1}` sections overlap in memory.")]
+        #[help = "Check the addresses given to `--section-start` and `--image-base`."]
+        OverlappingSections(String, String),
+
+        #[code = E017]
+        #[message = "Two sections overlap in the file."]
+        #[formatted_message("The `{0}` and `{1}` sections overlap in the file.")]
+        #[help = "This is a bug in `weld`, please report it!"]
+        OverlappingSectionsInFile(String, String),
+
+        #[code = E020]
+        #[message = "The file alignment is not a power of two."]
+        #[formatted_message("The file alignment, `{0}`, is not a power of two.")]
+        #[help = "Try a power of two, like `4096`."]
+        FileAlignNotPowerOfTwo(u64),
+
+        #[code = E027]
+        #[message = "The maximum page size is not a power of two."]
+        #[formatted_message("The maximum page size, `{0:#x}`, is not a power of two.")]
+        #[help = "Try a power of two, like `0x1000` or `0x10000`."]
+        MaxPageSizeNotPowerOfTwo(u64),
+
+        #[code = E028]
+        #[message = "An input file doesn't have the same endianness as the other input files."]
+        #[formatted_message("The input file `{}` doesn't have the same endianness, i.e. byte order, as `{}`.", .0.display(), .1.display())]
+        #[help = "All the input files must be compiled for the same target."]
+        MixedEndianness(PathBuf, PathBuf),
+
+        #[code = E029]
+        #[message = "There is no object file to link."]
+        #[help = "Archives only provide the symbols used by other object files: add the object files using them."]
+        NoInputFile,
+
+        #[code = E022]
+        #[message = "I don't know the byte order of the target."]
+        #[formatted_message("I don't know the byte order, i.e. the endianness, of the `{0}` target.")]
+        #[help = "Check the architecture of the target, e.g. `x86_64` or `powerpc64`."]
+        UnknownEndianness(String),
+
+        #[code = E012]
+        #[message = "I don't know the dynamic linker of the target."]
+        #[formatted_message("I don't know the dynamic linker of the `{0}` target.")]
+        #[help = "Use `--dynamic-linker` to set the path of the dynamic linker."]
+        MissingDynamicLinker(String),
+
+        #[code = E018]
+        #[message = "I was not able to find a library."]
+        #[formatted_message("I was not able to find the library `{0}`, i.e. `lib{0}.so`, or `lib{0}.a` for a fully-static executable.")]
+        #[help = "Maybe add the directory containing it with `-L <path>`?"]
+        LibraryNotFound(String),
+
+        #[code = E019]
+        #[message = "A fully-static executable cannot use a shared library."]
+        #[formatted_message("A fully-static executable cannot use the shared library `{}`.", .0.display())]
+        #[help = "Remove `--static` to produce a dynamically-linked executable, or provide the archive `lib<name>.a`."]
+        SharedLibraryInStaticLink(PathBuf),
+
+        #[code = E014]
+        #[message = "Warnings are treated as errors."]
+        #[formatted_message("{} warning(s) treated as errors.", .0.len())]
+        #[help = "Fix the warnings, or remove `--fatal-warnings`."]
+        FatalWarnings(#[related] Vec<Warning>),
+
+        #[message = "I was not able to write the output file."]
+        #[formatted_message("I was not able to write the output file `{}`: {}.", .0.display(), .1)]
+        #[help = "Is the output directory writable?"]
+        WritingOutput(PathBuf, io::Error),
+
+        #[message = "I was not able to post-process the output file."]
+        #[formatted_message("I was not able to post-process the output file `{}`: {}.", .0.display(), .1)]
+        #[help = "The hook given to `Configuration::post_process` has failed."]
+        PostProcessingOutput(PathBuf, io::Error),
+    }
+}
+
+/// Bytes of an input file.
+type Bytes = Box<dyn Deref<Target = [u8]> + Send>;
+
+/// A parsed input file.
+#[derive(Debug)]
+pub(crate) struct InputFile<'a> {
+    /// The parsed file, which knows its path.
+    pub(crate) file: File<'a>,
+    /// Whether each input section is discarded, indexed by input section
+    /// index: the sections of a duplicate COMDAT group are discarded.
+    pub(crate) discarded_sections: Vec<bool>,
+}
+
+impl<'a> InputFile<'a> {
+    /// Check whether an input section is discarded, see
+    /// [`Self::discarded_sections`].
+    pub(crate) fn is_discarded(&self, section_index: usize) -> bool {
+        self.discarded_sections.get(section_index).copied().unwrap_or(false)
+    }
+
+    /// Get the path of the input file, for diagnostics.
+    pub(crate) fn path(&self) -> &'a Path {
+        self.file.path.expect("An input file is always read with its path")
+    }
+}
+
+pub(crate) fn link(mut configuration: Configuration) -> Result<Warnings, Error> {
+    // Without a post-processing hook, the output doesn't have to be in memory
+    // at once: it's streamed into the output file.
+    if configuration.post_process.is_none() {
+        return link_to_file(&configuration);
+    }
+
+    let (output, warnings) = link_to_vec(&configuration)?;
+    let output = post_process(&mut configuration, output)?;
+
+    write_output_file(&configuration.output_file, &output)
+        .map_err(|error| Error::WritingOutput(configuration.output_file.to_path_buf(), error))?;
+
+    Ok(warnings)
+}
+
+pub(crate) fn link_to_vec(configuration: &Configuration) -> Result<(Vec<u8>, Warnings), Error> {
+    link_with(configuration, |builder| {
+        let mut output = builder.build()?;
+
+        // The padding comes after everything, i.e. after the section headers.
+        output.resize(output.len() + file_padding(configuration, output.len() as u64) as usize, 0);
+
+        Ok(output)
+    })
+}
+
+/// Link, and write the output file incrementally, through a [`BufWriter`],
+/// instead of building it in memory first, see [`link_to_vec`].
+fn link_to_file(configuration: &Configuration) -> Result<Warnings, Error> {
+    let path = &configuration.output_file;
+    let ((), warnings) = link_with(configuration, |builder| {
+        let mut writer = BufWriter::new(fs::File::create(path)?);
+        let size = builder.build_into(&mut writer)?;
+
+        // The padding comes after everything, i.e. after the section headers.
+        io::copy(&mut io::repeat(0).take(file_padding(configuration, size)), &mut writer)?;
+        writer.into_inner().map_err(IntoInnerError::into_error)?;
+
+        make_executable(path)
+    })?;
+
+    Ok(warnings)
+}
+
+/// Link, and hand the builder of the output file to `emit`, which writes
+/// it.
+fn link_with<T, F>(configuration: &Configuration, emit: F) -> Result<(T, Warnings), Error>
+where
+    F: FnOnce(Builder<'_>) -> io::Result<T>,
+{
+    let mut warnings = Warnings::new(configuration.no_warnings);
+    let input_paths = configuration
+        .input_files
+        .iter()
+        .cloned()
+        .chain(archive::static_libraries(configuration)?)
+        .collect::<Vec<_>>();
+    let input_files_bytes = read_input_files(&input_paths)?;
+
+    // The arena owns the names which can't be borrowed from the input files,
+    // and outlives them.
+    let arena = Arena::new();
+
+    // An archive is expanded to its members, which are parsed like the other
+    // object files, but are linked only if they are needed.
+    let mut input_objects = Vec::with_capacity(input_files_bytes.len());
+
+    for (path, bytes) in input_paths.iter().zip(&input_files_bytes) {
+        input_objects.extend(archive::input_objects(path, bytes)?);
+    }
+
+    // Parse all input files.
+    let mut input_files = Vec::<InputFile>::with_capacity(input_objects.len());
+
+    for archive::InputObject { path, bytes, .. } in &input_objects {
+        configuration.report(LinkPhase::ParsingInput(path));
+
+        let (_, mut file) =
+            File::read_named::<FileError>(path, bytes).map_err(|error| match error {
+                ObjectError::Failure(FileError { code: ErrorKind::Eof, .. }) => {
+                    Error::TruncatedFileHeader(path.to_path_buf())
+                }
+                ObjectError::Failure(FileError {
+                    invalid_header: Some(invalid_header @ InvalidHeader::NotAnElfFile { .. }),
+                    ..
+                }) => Error::NotAnElfFile(path.to_path_buf(), invalid_header),
+                ObjectError::Failure(FileError {
+                    invalid_header: Some(invalid_header), ..
+                }) => Error::InvalidHeader(path.to_path_buf(), invalid_header),
+                _ => Error::ParsingFile(path.to_path_buf()),
+            })?;
+        file.fetch_section_names_in(&arena);
+
+        input_files.push(InputFile { file, discarded_sections: Vec::new() });
+    }
+
+    // Keep the needed members of the archives only, at the position of their
+    // archive.
+    let is_member = input_objects.iter().map(|object| object.is_member).collect::<Vec<_>>();
+    let selected_files = archive::selected_files(&input_files, &is_member);
+    let mut selected_files = selected_files.into_iter();
+    input_files.retain(|_| selected_files.next().unwrap_or_default());
+
+    if input_files.is_empty() {
+        return Err(Error::NoInputFile);
+    }
+
+    for input_file in &input_files {
+        if input_file.file.r#type != FileType::RelocatableFile {
+            warnings.push(Warning::NonRelocatableInputFile(input_file.path().to_path_buf()));
+        }
+
+        if input_file.file.endianness != input_files[0].file.endianness {
+            return Err(Error::MixedEndianness(
+                input_file.path().to_path_buf(),
+                input_files[0].path().to_path_buf(),
+            ));
+        }
+    }
+
+    // Only one copy of each COMDAT group is kept.
+    let discarded_sections = groups::discarded_sections(&input_files);
+
+    for (input_file, discarded_sections) in input_files.iter_mut().zip(discarded_sections) {
+        input_file.discarded_sections = discarded_sections;
+    }
+
+    // All input files have the same endianness: it's matched once, so that
+    // reading the symbols doesn't branch on it.
+    match input_files[0].file.endianness {
+        Endianness::Big => {
+            link_input_files::<BigEndian, _, _>(configuration, &arena, &input_files, warnings, emit)
+        }
+        Endianness::Little => link_input_files::<LittleEndian, _, _>(
+            configuration,
+            &arena,
+            &input_files,
+            warnings,
+            emit,
+        ),
+    }
+}
+
+/// Link the parsed input files, whose endianness is `N`, see [`link_with`].
+/// The names are interned in `arena`, which is owned by the link.
+fn link_input_files<N, T, F>(
+    configuration: &Configuration,
+    arena: &Arena,
+    input_files: &[InputFile],
+    mut warnings: Warnings,
+    emit: F,
+) -> Result<(T, Warnings), Error>
+where
+    N: Number,
+    F: FnOnce(Builder<'_>) -> io::Result<T>,
+{
+    if let (false, Some(soname)) = (configuration.shared, &configuration.soname) {
+        warnings.push(Warning::IgnoredSoname(soname.clone()));
+    }
+
+    // A shared object is loaded anywhere, so its image base is `0`.
+    let image_base = configuration.image_base.unwrap_or(if configuration.shared {
+        0
+    } else {
+        DEFAULT_IMAGE_BASE
+    });
+
+    let page_size =
+        configuration.max_page_size.unwrap_or_else(|| default_max_page_size(&configuration.target));
+
+    if !page_size.is_power_of_two() {
+        return Err(Error::MaxPageSizeNotPowerOfTwo(page_size));
+    }
+
+    if !image_base.is_multiple_of(page_size) {
+        return Err(Error::ImageBaseNotPageAligned(image_base, page_size));
+    }
+
+    if let Some(file_align) = configuration.file_align.filter(|align| !align.is_power_of_two()) {
+        return Err(Error::FileAlignNotPowerOfTwo(file_align));
+    }
+
+    // Lay out the sections, resolve the symbols, and apply the relocations.
+    // A dynamic executable, or an executable which needs shared libraries,
+    // needs an interpreter to be started. A static executable, a shared
+    // object or a relocatable output never has one.
+    let mut dynamic_linking = DynamicLinking::new(configuration)?;
+    let interpreter = if (configuration.dynamic || !dynamic_linking.is_empty())
+        && !configuration.static_link
+        && !configuration.shared
+        && !configuration.relocatable
+    {
+        match &configuration.dynamic_linker {
+            Some(dynamic_linker) => Some(dynamic_linker.as_os_str().as_encoded_bytes()),
+            None => Some(
+                default_dynamic_linker(&configuration.target)
+                    .ok_or_else(|| Error::MissingDynamicLinker(configuration.target.to_string()))?
+                    .as_bytes(),
+            ),
+        }
+    } else {
+        None
+    };
+
+    // The symbols are resolved before the layout, as the dynamic symbols
+    // have to be known to size `.dynsym` and `.dynstr`.
+    configuration.report(LinkPhase::ResolvingSymbols);
+    let names = Names::new(configuration, arena);
+    let symbols = Symbols::<N>::resolve(input_files, configuration, &names)?;
+    symbols.trace(input_files, &configuration.trace_symbols, &mut warnings);
+
+    if configuration.static_link {
+        symbols.check_undefined()?;
+    }
+
+    dynamic_linking.add_symbols(&symbols);
+    dynamic_linking.add_plt(input_files, &symbols)?;
+    dynamic_linking.add_arrays(input_files);
+
+    // Remove the unused sections, if asked.
+    let live_sections =
+        configuration.gc_sections.then(|| LiveSections::new(input_files, &symbols, configuration));
+
+    if let (Some(live_sections), true) = (&live_sections, configuration.print_gc_sections) {
+        for (file_index, input_file) in input_files.iter().enumerate() {
+            for (section_index, section) in input_file.file.sections.iter().enumerate() {
+                // Empty sections are not worth reporting.
+                if section.flags.contains(SectionFlag::Allocable)
+                    && section.data.logical_size() != 0
+                    && !live_sections.is_live(file_index, section_index)
+                {
+                    warnings.push(Warning::RemovedSection(
+                        section.name.as_deref().unwrap_or_default().to_str_lossy().into_owned(),
+                        input_file.path().to_path_buf(),
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut layout = Layout::new(
+        input_files,
+        configuration,
+        image_base,
+        page_size,
+        interpreter,
+        &dynamic_linking,
+        live_sections.as_ref(),
+    )?;
+
+    // A segment both writable and executable is a security hazard: code can
+    // be injected, then run.
+    for segment in &layout.segments {
+        if segment.flags.contains(ProgramFlag::Write | ProgramFlag::Execute) {
+            warnings.push(Warning::WritableExecutableSegment(
+                layout.sections[segment.sections.clone()]
+                    .iter()
+                    .map(|section| section.name.to_str_lossy())
+                    .collect::<Vec<_>>()
+                    .join("`, `"),
+            ));
+        }
+    }
+
+    if let Some(dynamic_symbols) = layout.dynamic_symbols {
+        layout.sections[dynamic_symbols].data =
+            dynamic_linking.symbol_table(&symbols, &layout, input_files[0].file.endianness);
+    }
+
+    let retained_symbols = configuration
+        .retain_symbols_file
+        .as_deref()
+        .map(symbols::read_retained_symbols)
+        .transpose()?;
+    let symbol_table =
+        symbols.output_table(&layout, retained_symbols.as_ref(), configuration.relocatable);
+
+    // A symbol inconsistent with the layout is a bug in `weld`: it is checked
+    // in debug builds only, e.g. when testing.
+    debug_assert_eq!(
+        symbols.check_addresses(input_files, &layout, &symbol_table),
+        Ok(()),
+        "The address of a symbol is inconsistent with the layout",
+    );
+
+    // The relocations of a relocatable output are kept for the next link,
+    // instead of being applied.
+    configuration.report(LinkPhase::ApplyingRelocations);
+    let output_relocations = if configuration.relocatable {
+        relocations::rewrite(input_files, &symbols, &layout, &symbol_table)?
+    } else {
+        relocations::apply(
+            input_files,
+            &symbols,
+            &mut layout,
+            &dynamic_linking.plt,
+            configuration.emit_relocations.then_some(&symbol_table),
+        )?
+    };
+
+    // The table of `.eh_frame_hdr` refers to the relocated FDEs.
+    if let Some(eh_frame_hdr) = layout.eh_frame_hdr {
+        layout.sections[eh_frame_hdr].data =
+            eh_frame::hdr(input_files, &layout, &input_files[0].file);
+    }
+
+    // The entry point is `_start`, or `main` as a fallback. A shared object
+    // or a relocatable output doesn't need one.
+    let entry_point = match symbols.global(b"_start".as_bstr()) {
+        _ if configuration.relocatable => Some(0),
+        Some(reference) => symbols.address(reference, &layout),
+        None if configuration.shared => Some(0),
+        None => symbols.global(b"main".as_bstr()).and_then(|reference| {
+            warnings.push(Warning::EntryPointFallback("main".to_owned()));
+
+            symbols.address(reference, &layout)
+        }),
+    }
+    .ok_or(Error::MissingEntryPoint)?;
+
+    // Build the output file.
+    configuration.report(LinkPhase::WritingOutput);
+
+    let first_file = &input_files[0].file;
+    let os_abi = os_abi(configuration.target.operating_system).unwrap_or(first_file.os_abi);
+    let mut builder =
+        Builder::new(target_endianness(&configuration.target)?, first_file.machine, os_abi);
+    builder.set_entry(Address(entry_point));
+
+    if configuration.shared {
+        builder.set_type(FileType::SharedObject);
+    } else if configuration.relocatable {
+        builder.set_type(FileType::RelocatableFile);
+    }
+
+    // The program of the program headers comes first, in dynamic output. The
+    // program headers follow the file header, in the first segment.
+    if layout.dynamic.is_some() {
+        let size = (layout.program_count() * Program::SIZE) as u64;
+        let offset = File::HEADER_SIZE as u64;
+        let virtual_address = layout.segments[0].virtual_address + offset;
+
+        builder.add_program(Program {
+            r#type: ProgramType::ProgramHeader,
+            segment_flags: ProgramFlag::Read.into(),
+            offset: Address(offset),
+            virtual_address: Address(virtual_address),
+            physical_address: Some(Address(virtual_address)),
+            segment_size_in_file_image: Address(size),
+            segment_size_in_memory: Address(size),
+            alignment: Alignment::new(8).unwrap(),
+            data: Data::new(
+                Cow::Borrowed(&[]),
+                DataType::ProgramData,
+                endianness(first_file),
+                first_file.machine,
+                None,
+            ),
+        });
+    }
+
+    // The interpreter program must come before any loadable program.
+    if let Some(interpreter) = layout.interpreter.map(|index| &layout.sections[index]) {
+        builder.add_program(Program {
+            r#type: ProgramType::Interpreter,
+            segment_flags: ProgramFlag::Read.into(),
+            offset: Address(interpreter.offset),
+            virtual_address: Address(interpreter.virtual_address),
+            physical_address: Some(Address(interpreter.virtual_address)),
+            segment_size_in_file_image: Address(interpreter.data.len() as u64),
+            segment_size_in_memory: Address(interpreter.data.len() as u64),
+            alignment: Alignment::new(1).unwrap(),
+            data: Data::new(
+                Cow::Borrowed(&[]),
+                DataType::ProgramData,
+                endianness(first_file),
+                first_file.machine,
+                None,
+            ),
+        });
+    }
+
+    for segment in &layout.segments {
+        builder.add_program(Program {
+            r#type: ProgramType::Load,
+            segment_flags: segment.flags,
+            offset: Address(segment.offset),
+            virtual_address: Address(segment.virtual_address),
+            physical_address: Some(Address(segment.virtual_address)),
+            segment_size_in_file_image: Address(segment.file_size),
+            segment_size_in_memory: Address(segment.memory_size),
+            alignment: Alignment::new(layout.page_size).unwrap(),
+            data: Data::new(
+                Cow::Borrowed(&[]),
+                DataType::ProgramData,
+                endianness(first_file),
+                first_file.machine,
+                None,
+            ),
+        });
+    }
+
+    // The dynamic program comes after the loadable programs.
+    if let Some(dynamic) = layout.dynamic.map(|index| &layout.sections[index]) {
+        builder.add_program(Program {
+            r#type: ProgramType::Dynamic,
+            segment_flags: ProgramFlag::Read | ProgramFlag::Write,
+            offset: Address(dynamic.offset),
+            virtual_address: Address(dynamic.virtual_address),
+            physical_address: Some(Address(dynamic.virtual_address)),
+            segment_size_in_file_image: Address(dynamic.data.len() as u64),
+            segment_size_in_memory: Address(dynamic.data.len() as u64),
+            alignment: Alignment::new(dynamic.alignment).unwrap(),
+            data: Data::new(
+                Cow::Borrowed(&[]),
+                DataType::ProgramData,
+                endianness(first_file),
+                first_file.machine,
+                None,
+            ),
+        });
+    }
+
+    // The thread-local storage program is the template used to initialize
+    // the thread-local data of each thread.
+    if let Some(tls) = &layout.tls {
+        builder.add_program(Program {
+            r#type: ProgramType::ThreadLocalStorage,
+            segment_flags: tls.flags,
+            offset: Address(tls.offset),
+            virtual_address: Address(tls.virtual_address),
+            physical_address: Some(Address(tls.virtual_address)),
+            segment_size_in_file_image: Address(tls.file_size),
+            segment_size_in_memory: Address(tls.memory_size),
+            alignment: Alignment::new(layout.tls_alignment()).unwrap(),
+            data: Data::new(
+                Cow::Borrowed(&[]),
+                DataType::ProgramData,
+                endianness(first_file),
+                first_file.machine,
+                None,
+            ),
+        });
+    }
+
+    // The program of `.eh_frame_hdr` lets the unwinder find it.
+    if let Some(eh_frame_hdr) = layout.eh_frame_hdr.map(|index| &layout.sections[index]) {
+        builder.add_program(Program {
+            r#type: ProgramType::GnuEhFrame,
+            segment_flags: ProgramFlag::Read.into(),
+            offset: Address(eh_frame_hdr.offset),
+            virtual_address: Address(eh_frame_hdr.virtual_address),
+            physical_address: Some(Address(eh_frame_hdr.virtual_address)),
+            segment_size_in_file_image: Address(eh_frame_hdr.data.len() as u64),
+            segment_size_in_memory: Address(eh_frame_hdr.data.len() as u64),
+            alignment: Alignment::new(eh_frame_hdr.alignment).unwrap(),
+            data: Data::new(
+                Cow::Borrowed(&[]),
+                DataType::ProgramData,
+                endianness(first_file),
+                first_file.machine,
+                None,
+            ),
+        });
+    }
+
+    for (index, output_section) in layout.sections.iter().enumerate() {
+        let (link, information, entity_size) = section_links(&layout, index);
+
+        builder.add_section(Section {
+            name: Some(Cow::Borrowed(output_section.name.as_bstr())),
+            name_offset: Address(0),
+            r#type: output_section.r#type,
+            flags: output_section.flags,
+            virtual_address: Address(output_section.virtual_address),
+            offset: Address(output_section.offset),
+            segment_size_in_file_image: Address(output_section.memory_size()),
+            link,
+            information,
+            alignment: Alignment::new(output_section.alignment).unwrap(),
+            entity_size,
+            data: Data::new(
+                Cow::Borrowed(&output_section.data),
+                output_section.r#type.into(),
+                endianness(first_file),
+                first_file.machine,
+                entity_size,
+            ),
+        });
+    }
+
+    add_non_allocable_sections(
+        &mut builder,
+        first_file,
+        &layout,
+        &symbol_table,
+        &output_relocations,
+    );
+
+    if configuration.fatal_warnings && warnings.iter().any(|warning| !warning.is_report()) {
+        return Err(Error::FatalWarnings(warnings.fatal()));
+    }
+
+    let output = emit(builder)
+        .map_err(|error| Error::WritingOutput(configuration.output_file.to_path_buf(), error))?;
+
+    Ok((output, warnings))
+}
+
+/// Get the number of zero bytes to pad an output file of `size` bytes with,
+/// see [`Configuration::file_align`].
+fn file_padding(configuration: &Configuration, size: u64) -> u64 {
+    configuration.file_align.map_or(0, |file_align| size.next_multiple_of(file_align) - size)
+}
+
+/// Run the post-processing hook of the configuration on the bytes of the
+/// output file, if any. The hook can run once only.
+pub(crate) fn post_process(
+    configuration: &mut Configuration,
+    output: Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    match configuration.post_process.take() {
+        Some(PostProcess(hook)) => hook(output)
+            .map_err(|error| Error::PostProcessingOutput(configuration.output_file.clone(), error)),
+        None => Ok(output),
     }
 }
 
-pub(crate) fn link(configuration: Configuration) -> Result<(), Error> {
+/// Read all input files in parallel.
+///
+/// The bytes are returned in the same order as the input files.
+fn read_input_files(input_files: &[PathBuf]) -> Result<Vec<Bytes>, Error> {
     // SAFETY: It's OK to `unwrap` as 4 is not 0.
     let thread_pool = ThreadPool::new(NonZeroUsize::new(4).unwrap()).map_err(Error::ThreadPool)?;
 
-    let (sender, receiver) = unbounded::<Result<(), Error>>();
+    let (sender, receiver) = unbounded::<(usize, Result<Bytes, Error>)>();
 
-    for input_file_name in configuration.input_files {
+    for (nth, input_file_name) in input_files.iter().enumerate() {
         let sender = sender.clone();
+        let input_file_name = input_file_name.to_path_buf();
 
         thread_pool
             .execute(async move {
-                let work = async move {
-                    dbg!(&input_file_name);
-                    let input_file = FilePicker::open(input_file_name).unwrap();
+                let work = async {
+                    let input_file = FilePicker::open(&input_file_name)
+                        .map_err(|error| Error::ReadingFile(input_file_name.clone(), error))?;
 
-                    let file_content = input_file.read_as_bytes().await.unwrap();
-                    let bytes: &[u8] = file_content.as_ref();
-                    dbg!(weld_object::elf64::File::read(bytes).map_err(Error::ObjectParser)?);
-                    dbg!(std::thread::current().name());
+                    let bytes = input_file
+                        .read_as_bytes()
+                        .await
+                        .map_err(|error| Error::ReadingFile(input_file_name.clone(), error))?;
 
-                    Ok(())
+                    Ok(Box::new(bytes) as Bytes)
                 };
 
                 sender
-                    .send(work.await)
+                    .send((nth, work.await))
                     .await
                     .expect("work' sender channel has been closed prematuraly");
             })
@@ -62,10 +820,2298 @@ pub(crate) fn link(configuration: Configuration) -> Result<(), Error> {
     drop(sender);
 
     block_on(async {
+        let mut input_files_bytes = Vec::with_capacity(input_files.len());
+
         while let Ok(received) = receiver.recv().await {
-            dbg!(&received);
+            input_files_bytes.push(received);
+        }
+
+        if input_files_bytes.len() != input_files.len() {
+            return Err(Error::ThreadPoolChannelClosed);
+        }
+
+        input_files_bytes.sort_by_key(|(nth, _)| *nth);
+
+        input_files_bytes.into_iter().map(|(_, bytes)| bytes).collect()
+    })
+}
+
+/// Add the sections that are generated by the linker and that are not loaded
+/// in memory: the relocation sections if any, the symbol table and its string
+/// table.
+fn add_non_allocable_sections<'a>(
+    builder: &mut Builder<'a>,
+    first_file: &File,
+    layout: &Layout,
+    symbol_table: &'a SymbolTable,
+    output_relocations: &[OutputRelocations],
+) {
+    let endianness = endianness(first_file);
+
+    // Indices of the sections, shifted by one because of the null section.
+    let relocation_sections_index = layout.sections.len() + 1;
+    let symbol_table_index = relocation_sections_index + output_relocations.len();
+    let strings_index = symbol_table_index + 1;
+
+    for output_relocation in output_relocations {
+        let mut data = Vec::with_capacity(output_relocation.relocations.len() * 24);
+
+        for relocation in &output_relocation.relocations {
+            match first_file.endianness {
+                Endianness::Big => relocation.write::<BigEndian, _>(&mut data),
+                Endianness::Little => relocation.write::<LittleEndian, _>(&mut data),
+            }
+            .expect("Writing in a `Vec` cannot fail");
+        }
+
+        let target = &layout.sections[output_relocation.section];
+        let mut name = BString::from(".rela");
+        name.extend_from_slice(&target.name);
+
+        builder.add_section(Section {
+            name: Some(Cow::Owned(name)),
+            name_offset: Address(0),
+            r#type: SectionType::RelocationWithAddends,
+            flags: SectionFlag::InfoLink.into(),
+            virtual_address: Address(0),
+            offset: Address(0),
+            segment_size_in_file_image: Address(data.len() as u64),
+            link: SectionIndex::Ok(symbol_table_index),
+            information: (output_relocation.section + 1) as u32,
+            alignment: Alignment::new(8).unwrap(),
+            entity_size: NonZeroU64::new(24),
+            data: Data::new(
+                Cow::Owned(data),
+                DataType::RelocationTable,
+                endianness,
+                first_file.machine,
+                NonZeroU64::new(24),
+            ),
+        });
+    }
+
+    let mut symbols = Vec::with_capacity(symbol_table.symbols.len() * 24);
+
+    for symbol in &symbol_table.symbols {
+        match first_file.endianness {
+            Endianness::Big => symbol.write::<BigEndian, _>(&mut symbols),
+            Endianness::Little => symbol.write::<LittleEndian, _>(&mut symbols),
+        }
+        .expect("Writing in a `Vec` cannot fail");
+    }
+
+    builder.add_section(Section {
+        name: Some(Cow::Borrowed(BStr::new(".symtab"))),
+        name_offset: Address(0),
+        r#type: SectionType::SymbolTable,
+        flags: SectionFlags::empty(),
+        virtual_address: Address(0),
+        offset: Address(0),
+        segment_size_in_file_image: Address(symbols.len() as u64),
+        link: SectionIndex::Ok(strings_index),
+        information: symbol_table.first_global,
+        alignment: Alignment::new(8).unwrap(),
+        entity_size: NonZeroU64::new(24),
+        data: Data::new(
+            Cow::Owned(symbols),
+            DataType::SymbolTable,
+            endianness,
+            first_file.machine,
+            NonZeroU64::new(24),
+        ),
+    });
+
+    builder.add_section(Section {
+        name: Some(Cow::Borrowed(BStr::new(".strtab"))),
+        name_offset: Address(0),
+        r#type: SectionType::StringTable,
+        flags: SectionFlags::empty(),
+        virtual_address: Address(0),
+        offset: Address(0),
+        segment_size_in_file_image: Address(symbol_table.strings.len() as u64),
+        link: SectionIndex::Undefined,
+        information: 0,
+        alignment: Alignment::new(1).unwrap(),
+        entity_size: None,
+        data: Data::new(
+            Cow::Borrowed(&symbol_table.strings),
+            DataType::StringTable,
+            endianness,
+            first_file.machine,
+            None,
+        ),
+    });
+}
+
+/// Get the link, the information and the entity size of an output section.
+///
+/// The sections generated for the dynamic linking have some: the dynamic
+/// entries and the dynamic symbols are linked to their strings, the hash table
+/// and the relocations of the PLT to the dynamic symbols. The links of the
+/// input sections, e.g. `LinkOrder` sections, are rewritten to the output
+/// sections by the layout. Indices are shifted by one because of the null
+/// section.
+fn section_links(layout: &Layout, index: usize) -> (SectionIndex, u32, Option<NonZeroU64>) {
+    let link = |index: Option<usize>| {
+        index.map_or(SectionIndex::Undefined, |index| SectionIndex::Ok(index + 1))
+    };
+
+    match Some(index) {
+        index if index == layout.dynamic => {
+            (link(layout.dynamic_strings), 0, NonZeroU64::new(Dynamic::SIZE as u64))
+        }
+        // All the dynamic symbols are global, except the null symbol.
+        index if index == layout.dynamic_symbols => {
+            (link(layout.dynamic_strings), 1, NonZeroU64::new(Symbol::SIZE as u64))
+        }
+        index if index == layout.dynamic_hash => {
+            (link(layout.dynamic_symbols), 0, NonZeroU64::new(4))
         }
+        // The relocations of the PLT apply to its GOT slots.
+        index if index == layout.plt_relocations => (
+            link(layout.dynamic_symbols),
+            layout.plt_got.map_or(0, |plt_got| plt_got as u32 + 1),
+            NonZeroU64::new(Relocation::SIZE as u64),
+        ),
+        index if index == layout.plt => {
+            (SectionIndex::Undefined, 0, NonZeroU64::new(PLT_ENTRY_SIZE))
+        }
+        // Other links have been rewritten to point to output sections.
+        _ => (link(layout.links.get(&index).copied()), 0, None),
+    }
+}
+
+/// Get the ELF machine of an architecture, if `weld` supports it.
+pub(crate) fn machine(architecture: Architecture) -> Option<Machine> {
+    match architecture {
+        Architecture::X86_64 => Some(Machine::X86_64),
+        _ => None,
+    }
+}
 
-        Ok(())
+/// Get the ELF OS ABI of an operating system, if it has a specific one.
+fn os_abi(operating_system: OperatingSystem) -> Option<OsAbi> {
+    Some(match operating_system {
+        OperatingSystem::Linux => OsAbi::SystemV,
+        OperatingSystem::Freebsd => OsAbi::FreeBsd,
+        OperatingSystem::Netbsd => OsAbi::NetBsd,
+        OperatingSystem::Openbsd => OsAbi::OpenBsd,
+        OperatingSystem::Solaris => OsAbi::Solaris,
+        OperatingSystem::Aix => OsAbi::Aix,
+        OperatingSystem::Hurd => OsAbi::GnuHurd,
+        OperatingSystem::Cloudabi => OsAbi::CloudAbi,
+        _ => return None,
     })
 }
+
+/// Get the default dynamic linker, i.e. the interpreter, of a target.
+fn default_dynamic_linker(target: &Triple) -> Option<&'static str> {
+    match (target.operating_system, target.architecture) {
+        (OperatingSystem::Linux, Architecture::X86_64) => Some("/lib64/ld-linux-x86-64.so.2"),
+        (OperatingSystem::Linux, Architecture::Aarch64(_)) => Some("/lib/ld-linux-aarch64.so.1"),
+        _ => None,
+    }
+}
+
+/// Get the maximum page size of a target, i.e. the largest page size of the
+/// systems running it.
+fn default_max_page_size(target: &Triple) -> u64 {
+    match target.architecture {
+        Architecture::Aarch64(_) => 0x1_0000,
+        _ => DEFAULT_MAX_PAGE_SIZE,
+    }
+}
+
+/// Get the endianness of a target, i.e. the byte order of the output file.
+pub(crate) fn target_endianness(target: &Triple) -> Result<Endianness, Error> {
+    match target.endianness() {
+        Ok(target::Endianness::Big) => Ok(Endianness::Big),
+        Ok(target::Endianness::Little) => Ok(Endianness::Little),
+        Err(()) => Err(Error::UnknownEndianness(target.to_string())),
+    }
+}
+
+/// Get the endianness of a file, as expected by [`Data`].
+fn endianness(file: &File) -> weld_object::Endianness {
+    match file.endianness {
+        Endianness::Big => weld_object::Endianness::Big,
+        Endianness::Little => weld_object::Endianness::Little,
+    }
+}
+
+/// Write the output file, and make it executable.
+fn write_output_file(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    fs::write(path, bytes)?;
+
+    make_executable(path)
+}
+
+/// Make the output file executable.
+fn make_executable(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use weld_object::elf64::{
+        elf_hash, relocation_type_name, DynamicTag, SymbolBinding, SymbolType,
+    };
+
+    use super::*;
+    use crate::{target::Triple, SortSection, SymbolValue};
+
+    fn link_fixtures<F>(output_file_name: &str, configure: F) -> Result<Vec<u8>, Error>
+    where
+        F: FnOnce(Configuration) -> Configuration,
+    {
+        link_files(
+            output_file_name,
+            vec![
+                PathBuf::from("tests/fixtures/start_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/exit_elf_amd64.o"),
+            ],
+            configure,
+        )
+    }
+
+    fn link_files<F>(
+        output_file_name: &str,
+        input_files: Vec<PathBuf>,
+        configure: F,
+    ) -> Result<Vec<u8>, Error>
+    where
+        F: FnOnce(Configuration) -> Configuration,
+    {
+        let output_file = env::temp_dir().join(output_file_name);
+
+        link(configure(Configuration::new(Triple::host(), input_files, output_file.clone())))
+            .map(|_| ())?;
+
+        let output = fs::read(&output_file).unwrap();
+        fs::remove_file(&output_file).unwrap();
+
+        Ok(output)
+    }
+
+    #[test]
+    fn test_retain_symbols_file() {
+        let retain_file = env::temp_dir().join("weld_test_retain_symbols_file.txt");
+        fs::write(&retain_file, "main\n\n").unwrap();
+
+        let input_files = vec![
+            PathBuf::from("tests/fixtures/start_elf_amd64.o"),
+            PathBuf::from("tests/fixtures/exit_elf_amd64.o"),
+            PathBuf::from("tests/fixtures/main_elf_amd64.o"),
+        ];
+        let output =
+            link_files("weld_test_retain_symbols_file", input_files.clone(), |configuration| {
+                configuration.emit_relocations(true).retain_symbols_file(&retain_file)
+            })
+            .unwrap();
+        let reference_output =
+            link_files("weld_test_retain_symbols_file_reference", input_files, |configuration| {
+                configuration.emit_relocations(true)
+            })
+            .unwrap();
+        fs::remove_file(&retain_file).unwrap();
+
+        let (_, file) = File::read_resolved::<()>(&output).unwrap();
+        let (_, reference_file) = File::read_resolved::<()>(&reference_output).unwrap();
+        let symbol_table = file.section_by_name(".symtab").unwrap();
+        let first_global = symbol_table.information as usize;
+        let symbols = file.symbols().collect::<Vec<_>>();
+        let globals = symbols[first_global..]
+            .iter()
+            .map(|symbol| symbol.name.as_deref().unwrap().to_str_lossy())
+            .collect::<Vec<_>>();
+
+        // Only `main` is kept among the global symbols.
+        assert_eq!(globals, ["main"]);
+        assert!(symbols[..first_global].iter().any(|symbol| symbol.r#type == SymbolType::Section));
+
+        // The relocations against the dropped symbols are rewritten against
+        // section symbols: the relocated code is the same.
+        let relocations = file
+            .section_by_name(".rela.text")
+            .unwrap()
+            .data
+            .relocations::<()>()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(relocations.len(), 3);
+        assert!(relocations
+            .iter()
+            .all(|relocation| (relocation.symbol_index as usize) < first_global));
+        assert_eq!(
+            file.section_by_name(".text").unwrap().data,
+            reference_file.section_by_name(".text").unwrap().data,
+        );
+
+        // A missing file is reported.
+        let result = link_fixtures("weld_test_retain_symbols_file_missing", |configuration| {
+            configuration.retain_symbols_file("tests/fixtures/missing.txt")
+        });
+
+        assert!(
+            matches!(result, Err(Error::ReadingFile(path, _)) if path.ends_with("missing.txt"))
+        );
+    }
+
+    #[test]
+    fn test_emit_relocations() {
+        let output = link_fixtures("weld_test_emit_relocations", |configuration| {
+            configuration.emit_relocations(true)
+        })
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let section_index = |name: &str| {
+            file.sections
+                .iter()
+                .position(|section| section.name.as_deref().is_some_and(|n| n == name))
+                .unwrap()
+        };
+
+        let relocation_section = &file.sections[section_index(".rela.text")];
+
+        assert_eq!(relocation_section.r#type, SectionType::RelocationWithAddends);
+        assert_eq!(relocation_section.link, SectionIndex::Ok(section_index(".symtab")));
+        assert_eq!(relocation_section.information as usize, section_index(".text"));
+
+        let text_section = &file.sections[section_index(".text")];
+        let relocations = relocation_section
+            .data
+            .relocations::<()>()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(relocations.len(), 3);
+
+        // Offsets are virtual addresses inside `.text`.
+        for relocation in relocations {
+            assert!(relocation.offset.0 >= text_section.virtual_address.0);
+            assert!(
+                relocation.offset.0
+                    < text_section.virtual_address.0 + text_section.segment_size_in_file_image.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_relocatable() {
+        // `do_exit` is defined by another file, which is not linked.
+        let output = link_files(
+            "weld_test_relocatable",
+            vec![PathBuf::from("tests/fixtures/start_elf_amd64.o")],
+            |configuration| configuration.relocatable(true),
+        )
+        .unwrap();
+        let (_, file) = File::read_resolved::<()>(&output).unwrap();
+
+        assert_eq!(file.r#type, FileType::RelocatableFile);
+        assert!(file.programs.is_empty());
+        assert_eq!(file.entry_point, None);
+        assert!(file.sections.iter().all(|section| section.virtual_address == Address(0)));
+
+        let text = file.section_by_name(".text").unwrap();
+        let relocations = file
+            .section_by_name(".rela.text")
+            .unwrap()
+            .data
+            .relocations::<()>()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(relocations.len(), 3);
+
+        // Offsets are relative to `.text`.
+        for relocation in &relocations {
+            assert!(relocation.offset.0 < text.segment_size_in_file_image.0);
+        }
+
+        // The undefined symbol is kept, and the relocation against it too.
+        let symbols = file.symbols().collect::<Vec<_>>();
+        let do_exit = symbols
+            .iter()
+            .position(|symbol| symbol.name.as_deref() == Some(b"do_exit".as_bstr()))
+            .unwrap();
+
+        assert!(symbols[do_exit].is_undefined());
+        assert!(symbols[do_exit].is_global());
+        assert!(relocations.iter().any(|relocation| relocation.symbol_index as usize == do_exit));
+    }
+
+    #[test]
+    fn test_link_order_section() {
+        let output = link_files(
+            "weld_test_link_order_section",
+            vec![
+                PathBuf::from("tests/fixtures/start_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/exit_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/link_order_elf_amd64.o"),
+            ],
+            |configuration| configuration,
+        )
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let section_index = |name: &str| {
+            file.sections
+                .iter()
+                .position(|section| section.name.as_deref().is_some_and(|n| n == name))
+                .unwrap()
+        };
+
+        let ordered_section = &file.sections[section_index(".ordered")];
+
+        // The link points to the merged `.text`, not to the input one.
+        assert!(ordered_section.flags.contains(SectionFlag::LinkOrder));
+        assert_eq!(ordered_section.link, SectionIndex::Ok(section_index(".text")));
+    }
+
+    #[test]
+    fn test_gc_sections() {
+        let input_files = vec![PathBuf::from("tests/fixtures/gc_elf_amd64.o")];
+        let configuration = |gc_sections| {
+            Configuration::new(
+                Triple::host(),
+                input_files.clone(),
+                env::temp_dir().join("weld_test_gc_sections"),
+            )
+            .gc_sections(gc_sections)
+            .print_gc_sections(true)
+        };
+        let text = |output: &[u8]| {
+            let (_, mut file) = File::read::<()>(output).unwrap();
+            file.fetch_section_names();
+
+            file.section_data(".text").unwrap().to_vec()
+        };
+
+        // `movl $42, %edi` and `movl $7, %edi`.
+        let used = &[0xbf, 0x2a, 0x00, 0x00, 0x00];
+        let unused = &[0xbf, 0x07, 0x00, 0x00, 0x00];
+
+        let (output, warnings) = link_to_vec(&configuration(true)).unwrap();
+        let text_with_gc = text(&output);
+
+        assert!(text_with_gc.windows(used.len()).any(|bytes| bytes == used));
+        assert!(!text_with_gc.windows(unused.len()).any(|bytes| bytes == unused));
+        assert!(matches!(
+            &warnings.into_iter().collect::<Vec<_>>()[..],
+            [Warning::RemovedSection(section, file)]
+                if section == ".text.unused" && *file == input_files[0]
+        ));
+
+        // Without the garbage collection, everything is kept.
+        let (output, warnings) = link_to_vec(&configuration(false)).unwrap();
+        let text_without_gc = text(&output);
+
+        assert!(text_without_gc.windows(unused.len()).any(|bytes| bytes == unused));
+        assert!(warnings.is_empty());
+
+        // The removed sections are reports, not warnings: they are neither
+        // ignored, nor treated as errors.
+        let (_, warnings) =
+            link_to_vec(&configuration(true).no_warnings(true).fatal_warnings(true)).unwrap();
+
+        assert!(matches!(
+            &warnings.into_iter().collect::<Vec<_>>()[..],
+            [Warning::RemovedSection(section, _)] if section == ".text.unused"
+        ));
+    }
+
+    #[test]
+    fn test_wrap() {
+        let output = link_files(
+            "weld_test_wrap",
+            vec![
+                PathBuf::from("tests/fixtures/call_foo_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/foo_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/wrap_foo_elf_amd64.o"),
+            ],
+            |configuration| configuration.wrap("foo"),
+        )
+        .unwrap();
+
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let address = |name: &str| {
+            file.symbols_with_sections()
+                .find(|(symbol, _)| symbol.name.as_deref() == Some(name.as_bytes().as_bstr()))
+                .map(|(symbol, _)| symbol.value.0)
+                .unwrap()
+        };
+        // The target of the `call` instruction at `address`.
+        let call_target = |address: u64| {
+            let text = file.section_by_name(".text").unwrap();
+            let offset = (address - text.virtual_address.0) as usize;
+            let instruction = &text.data[offset..][..5];
+
+            assert_eq!(instruction[0], 0xe8);
+
+            (address as i64 + 5 + i32::from_le_bytes(instruction[1..].try_into().unwrap()) as i64)
+                as u64
+        };
+
+        // `_start` calls `foo`, which is redirected to `__wrap_foo`.
+        assert_eq!(call_target(address("_start")), address("__wrap_foo"));
+
+        // `__wrap_foo` calls `__real_foo`, which is the original `foo`.
+        assert_eq!(call_target(address("__wrap_foo")), address("foo"));
+    }
+
+    #[test]
+    fn test_trace_symbol() {
+        let input_files = vec![
+            PathBuf::from("tests/fixtures/call_foo_elf_amd64.o"),
+            PathBuf::from("tests/fixtures/foo_elf_amd64.o"),
+        ];
+        let configuration = Configuration::new(
+            Triple::host(),
+            input_files.clone(),
+            env::temp_dir().join("weld_test_trace_symbol"),
+        )
+        .trace_symbol("foo");
+
+        let (_, warnings) = link_to_vec(&configuration).unwrap();
+
+        // `call_foo` references `foo`, and `foo` defines it.
+        assert!(matches!(
+            &warnings.into_iter().collect::<Vec<_>>()[..],
+            [
+                Warning::TracedSymbolReference(reference, referencing_file),
+                Warning::TracedSymbolDefinition(definition, defining_file),
+                Warning::TracedSymbolResolution(resolution, resolved_file),
+            ] if reference == "foo"
+                && *referencing_file == input_files[0]
+                && definition == "foo"
+                && *defining_file == input_files[1]
+                && resolution == "foo"
+                && *resolved_file == input_files[1]
+        ));
+
+        // Untraced symbols are not reported.
+        let (_, warnings) = link_to_vec(&configuration.trace_symbol("bar")).unwrap();
+
+        assert_eq!(warnings.len(), 3);
+    }
+
+    #[test]
+    fn test_defsym() {
+        let link_defsym = |configure: fn(Configuration) -> Configuration| {
+            link_files(
+                "weld_test_defsym",
+                vec![PathBuf::from("tests/fixtures/defsym_elf_amd64.o")],
+                configure,
+            )
+        };
+        let symbol_value = |file: &File, name: &str| {
+            file.symbols_with_sections()
+                .find(|(symbol, _)| symbol.name.as_deref() == Some(name.as_bytes().as_bstr()))
+                .map(|(symbol, _)| {
+                    (
+                        symbol.value.0,
+                        symbol.section_index_where_symbol_is_defined == SectionIndex::Absolute,
+                    )
+                })
+                .unwrap()
+        };
+        // The immediate of `movl $origin, %edi`, at the entry point.
+        let immediate = |file: &File| {
+            let text = file.section_by_name(".text").unwrap();
+            let offset = (file.entry_point.unwrap().0 - text.virtual_address.0) as usize;
+
+            u32::from_le_bytes(text.data[offset + 1..][..4].try_into().unwrap())
+        };
+
+        // An absolute symbol.
+        let output = link_defsym(|configuration| {
+            configuration.defsym("origin", SymbolValue::Absolute(0x1000))
+        })
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        assert_eq!(immediate(&file), 0x1000);
+        assert_eq!(symbol_value(&file, "origin"), (0x1000, true));
+
+        // An alias, to an absolute symbol defined earlier.
+        let output = link_defsym(|configuration| {
+            configuration
+                .defsym("base", SymbolValue::Absolute(0x2000))
+                .defsym("origin", SymbolValue::Symbol("base".to_owned()))
+        })
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        assert_eq!(immediate(&file), 0x2000);
+        assert_eq!(symbol_value(&file, "origin"), (0x2000, true));
+
+        // An alias, to a symbol defined by an input file.
+        let output = link_defsym(|configuration| {
+            configuration.defsym("origin", SymbolValue::Symbol("_start".to_owned()))
+        })
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        assert_eq!(immediate(&file) as u64, file.entry_point.unwrap().0);
+        assert_eq!(symbol_value(&file, "origin"), (file.entry_point.unwrap().0, false));
+
+        // The alias must refer to a defined symbol.
+        assert!(matches!(
+            link_defsym(|configuration| {
+                configuration.defsym("origin", SymbolValue::Symbol("missing".to_owned()))
+            }),
+            Err(Error::UndefinedSymbol(name)) if name == "missing"
+        ));
+
+        // A symbol defined by an input file cannot be defined again.
+        assert!(matches!(
+            link_defsym(|configuration| {
+                configuration
+                    .defsym("origin", SymbolValue::Absolute(0x1000))
+                    .defsym("_start", SymbolValue::Absolute(0))
+            }),
+            Err(Error::DefinedSymbolConflict(name, _)) if name == "_start"
+        ));
+    }
+
+    #[test]
+    fn test_writable_executable_segment() {
+        let link_with = |input_files: &[&str]| {
+            let configuration = Configuration::new(
+                Triple::host(),
+                input_files
+                    .iter()
+                    .map(|name| PathBuf::from(format!("tests/fixtures/{name}_elf_amd64.o")))
+                    .collect(),
+                env::temp_dir().join("weld_test_writable_executable_segment"),
+            );
+
+            link_to_vec(&configuration).unwrap().1.into_iter().collect::<Vec<_>>()
+        };
+
+        // Writable sections and executable sections are in different
+        // segments.
+        assert!(link_with(&["bss"]).is_empty());
+
+        // A writable and executable section makes its segment writable and
+        // executable.
+        assert!(matches!(
+            &link_with(&["bss", "rwx"])[..],
+            [Warning::WritableExecutableSegment(sections)] if sections == ".patchable"
+        ));
+
+        // The warning is an error with `--fatal-warnings`.
+        let configuration = Configuration::new(
+            Triple::host(),
+            vec![
+                PathBuf::from("tests/fixtures/bss_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/rwx_elf_amd64.o"),
+            ],
+            env::temp_dir().join("weld_test_writable_executable_segment"),
+        )
+        .fatal_warnings(true);
+
+        assert!(matches!(link_to_vec(&configuration), Err(Error::FatalWarnings(_))));
+    }
+
+    #[test]
+    fn test_check_symbol_addresses() {
+        let configuration = Configuration::new(
+            Triple::host(),
+            vec![
+                PathBuf::from("tests/fixtures/start_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/exit_elf_amd64.o"),
+            ],
+            env::temp_dir().join("weld_test_check_symbol_addresses"),
+        );
+        let input_files_bytes = read_input_files(&configuration.input_files).unwrap();
+        let input_files = configuration
+            .input_files
+            .iter()
+            .zip(&input_files_bytes)
+            .map(|(path, bytes)| {
+                let (_, mut file) = File::read_named::<()>(path, bytes).unwrap();
+                file.fetch_section_names();
+
+                InputFile { file, discarded_sections: Vec::new() }
+            })
+            .collect::<Vec<_>>();
+
+        let dynamic_linking = DynamicLinking::new(&configuration).unwrap();
+        let arena = Arena::new();
+        let names = Names::new(&configuration, &arena);
+        let symbols =
+            Symbols::<LittleEndian>::resolve(&input_files, &configuration, &names).unwrap();
+        let mut layout = Layout::new(
+            &input_files,
+            &configuration,
+            DEFAULT_IMAGE_BASE,
+            DEFAULT_MAX_PAGE_SIZE,
+            None,
+            &dynamic_linking,
+            None,
+        )
+        .unwrap();
+        let symbol_table = symbols.output_table(&layout, None, false);
+
+        assert_eq!(symbols.check_addresses(&input_files, &layout, &symbol_table), Ok(()));
+
+        // Move the `.data` section of `exit.o`, which defines `answer`, without
+        // updating the symbol table.
+        let data_index = input_files[1]
+            .file
+            .sections
+            .iter()
+            .position(|section| section.name.as_deref().is_some_and(|name| name == ".data"))
+            .unwrap();
+        layout.placements[1][data_index].as_mut().unwrap().offset += 4;
+
+        assert_eq!(
+            symbols.check_addresses(&input_files, &layout, &symbol_table),
+            Err("answer".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_wrap_names_are_interned() {
+        let configuration = Configuration::new(
+            Triple::host(),
+            vec![
+                PathBuf::from("tests/fixtures/call_foo_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/foo_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/wrap_foo_elf_amd64.o"),
+            ],
+            env::temp_dir().join("weld_test_wrap_names_are_interned"),
+        )
+        .wrap("foo");
+        let input_files_bytes = read_input_files(&configuration.input_files).unwrap();
+        let arena = Arena::new();
+        let input_files = configuration
+            .input_files
+            .iter()
+            .zip(&input_files_bytes)
+            .map(|(path, bytes)| {
+                let (_, mut file) = File::read_named::<()>(path, bytes).unwrap();
+                file.fetch_section_names_in(&arena);
+
+                InputFile { file, discarded_sections: Vec::new() }
+            })
+            .collect::<Vec<_>>();
+
+        // The section names borrow the input files.
+        assert_eq!(arena.allocated_bytes(), 0);
+
+        let names = Names::new(&configuration, &arena);
+        let symbols =
+            Symbols::<LittleEndian>::resolve(&input_files, &configuration, &names).unwrap();
+
+        // `__wrap_foo` is interned once, when resolving the symbols.
+        let allocated_bytes = arena.allocated_bytes();
+        assert!(allocated_bytes > 0);
+
+        // Reading the symbols again doesn't allocate any name: all of them are
+        // borrowed, including the redirected references.
+        let symbol_names = symbols
+            .per_file
+            .iter()
+            .flat_map(|input_symbols| input_symbols.iter())
+            .filter_map(|symbol| symbol.name)
+            .collect::<Vec<_>>();
+
+        assert!(symbol_names.iter().all(|name| matches!(name, Cow::Borrowed(_))));
+        assert!(symbol_names.iter().any(|name| name.as_ref() == "__wrap_foo"));
+        assert_eq!(arena.allocated_bytes(), allocated_bytes);
+    }
+
+    #[test]
+    fn test_file_align() {
+        let unaligned =
+            link_fixtures("weld_test_file_align", |configuration| configuration).unwrap();
+        let output =
+            link_fixtures("weld_test_file_align", |configuration| configuration.file_align(4096))
+                .unwrap();
+
+        assert!(output.len().is_multiple_of(4096));
+        assert_eq!(&output[..unaligned.len()], unaligned.as_slice());
+        assert!(output[unaligned.len()..].iter().all(|byte| *byte == 0));
+
+        assert!(matches!(
+            link_fixtures("weld_test_file_align", |configuration| configuration.file_align(3)),
+            Err(Error::FileAlignNotPowerOfTwo(3))
+        ));
+    }
+
+    #[test]
+    fn test_link_to_file() {
+        let input_files = vec![
+            PathBuf::from("tests/fixtures/start_elf_amd64.o"),
+            PathBuf::from("tests/fixtures/exit_elf_amd64.o"),
+        ];
+
+        for configure in [
+            (|configuration| configuration) as fn(Configuration) -> Configuration,
+            |configuration| configuration.file_align(4096),
+            |configuration| configuration.dynamic(true),
+        ] {
+            // `link` streams the output into the file, without a
+            // post-processing hook.
+            let output_file = env::temp_dir().join("weld_test_link_to_file");
+            let configuration = configure(Configuration::new(
+                Triple::host(),
+                input_files.clone(),
+                output_file.clone(),
+            ));
+            let (in_memory, _) = link_to_vec(&configuration).unwrap();
+
+            link(configuration).unwrap();
+
+            let streamed = fs::read(&output_file).unwrap();
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+
+                assert_eq!(fs::metadata(&output_file).unwrap().permissions().mode() & 0o777, 0o755);
+            }
+
+            fs::remove_file(&output_file).unwrap();
+
+            assert_eq!(streamed, in_memory);
+        }
+    }
+
+    #[test]
+    fn test_parsing_symbol_error_has_the_path() {
+        let mut bytes = fs::read("tests/fixtures/exit_elf_amd64.o").unwrap();
+        let (_, file) = File::read::<()>(&bytes).unwrap();
+        let symbol_table_index = file
+            .sections
+            .iter()
+            .position(|section| section.r#type == SectionType::SymbolTable)
+            .unwrap();
+
+        // Corrupt the entity size of the symbol table.
+        let sh_offset = u64::from_le_bytes(bytes[40..48].try_into().unwrap()) as usize;
+        let entity_size_offset = sh_offset + symbol_table_index * Section::SIZE + 56;
+        bytes[entity_size_offset..entity_size_offset + 8].copy_from_slice(&23u64.to_le_bytes());
+
+        let input_file = env::temp_dir().join("weld_test_parsing_symbol_error_has_the_path.o");
+        fs::write(&input_file, bytes).unwrap();
+
+        let result = link_files(
+            "weld_test_parsing_symbol_error_has_the_path",
+            vec![PathBuf::from("tests/fixtures/start_elf_amd64.o"), input_file.clone()],
+            |configuration| configuration,
+        );
+
+        fs::remove_file(&input_file).unwrap();
+
+        assert!(matches!(result, Err(Error::ParsingSymbol(path)) if path == input_file));
+    }
+
+    #[test]
+    fn test_mixed_endianness() {
+        let mut builder = Builder::new(Endianness::Big, Machine::X86_64, OsAbi::SystemV);
+        builder.set_type(FileType::RelocatableFile);
+
+        let input_file = env::temp_dir().join("weld_test_mixed_endianness.o");
+        fs::write(&input_file, builder.build().unwrap()).unwrap();
+
+        let result = link_files(
+            "weld_test_mixed_endianness",
+            vec![PathBuf::from("tests/fixtures/start_elf_amd64.o"), input_file.clone()],
+            |configuration| configuration,
+        );
+
+        fs::remove_file(&input_file).unwrap();
+
+        assert!(matches!(
+            result,
+            Err(Error::MixedEndianness(path, first_path))
+                if path == input_file && first_path.ends_with("start_elf_amd64.o")
+        ));
+    }
+
+    #[test]
+    fn test_no_emit_relocations() {
+        let output =
+            link_fixtures("weld_test_no_emit_relocations", |configuration| configuration).unwrap();
+        let (_, file) = File::read::<()>(&output).unwrap();
+
+        assert!(file
+            .sections
+            .iter()
+            .all(|section| section.r#type != SectionType::RelocationWithAddends));
+    }
+
+    #[test]
+    fn test_image_base() {
+        let output = link_fixtures("weld_test_image_base", |configuration| {
+            configuration.image_base(0x80_0000)
+        })
+        .unwrap();
+        let (_, file) = File::read::<()>(&output).unwrap();
+
+        let first_load =
+            file.programs.iter().find(|program| program.r#type == ProgramType::Load).unwrap();
+
+        assert_eq!(first_load.virtual_address, Address(0x80_0000));
+        assert!(file.entry_point.unwrap().0 > 0x80_0000);
+
+        assert!(matches!(
+            link_fixtures("weld_test_image_base_unaligned", |configuration| {
+                configuration.image_base(0x80_0010)
+            }),
+            Err(Error::ImageBaseNotPageAligned(0x80_0010, DEFAULT_MAX_PAGE_SIZE))
+        ));
+    }
+
+    #[test]
+    fn test_max_page_size() {
+        let output = link_fixtures("weld_test_max_page_size", |configuration| {
+            configuration.max_page_size(0x1_0000)
+        })
+        .unwrap();
+        let (_, file) = File::read::<()>(&output).unwrap();
+
+        let loads = file
+            .programs
+            .iter()
+            .filter(|program| program.r#type == ProgramType::Load)
+            .collect::<Vec<_>>();
+
+        assert!(loads.len() > 1);
+
+        // Segments are aligned to the maximum page size, with their offsets
+        // congruent to their virtual addresses.
+        for load in loads {
+            assert_eq!(load.alignment, Alignment::new(0x1_0000).unwrap());
+            assert_eq!(load.offset.0 % 0x1_0000, load.virtual_address.0 % 0x1_0000);
+        }
+
+        // The image base must be aligned to the maximum page size.
+        assert!(matches!(
+            link_fixtures("weld_test_max_page_size_image_base", |configuration| {
+                configuration.max_page_size(0x1_0000).image_base(0x40_1000)
+            }),
+            Err(Error::ImageBaseNotPageAligned(0x40_1000, 0x1_0000))
+        ));
+
+        assert!(matches!(
+            link_fixtures("weld_test_max_page_size_invalid", |configuration| {
+                configuration.max_page_size(0x3000)
+            }),
+            Err(Error::MaxPageSizeNotPowerOfTwo(0x3000))
+        ));
+    }
+
+    #[test]
+    fn test_section_start() {
+        let output = link_fixtures("weld_test_section_start", |configuration| {
+            configuration.section_start(".text", 0x800_0000)
+        })
+        .unwrap();
+        let (_, file) = File::read::<()>(&output).unwrap();
+
+        assert!(file.programs.iter().any(|program| program.r#type == ProgramType::Load
+            && program.virtual_address == Address(0x800_0000)));
+        assert_eq!(file.entry_point, Some(Address(0x800_0000)));
+
+        // `.text` overlaps the file headers.
+        assert!(matches!(
+            link_fixtures("weld_test_section_start_overlap", |configuration| {
+                configuration.section_start(".text", 0x40_0000)
+            }),
+            Err(Error::OverlappingSections(..))
+        ));
+
+        // Two sections at the same address overlap.
+        assert!(matches!(
+            link_fixtures("weld_test_section_start_same_address", |configuration| {
+                configuration.section_start(".text", 0x80_0000).section_start(".rodata", 0x80_0000)
+            }),
+            Err(Error::OverlappingSections(left, right))
+                if [left.as_str(), right.as_str()] == [".rodata", ".text"]
+                    || [left.as_str(), right.as_str()] == [".text", ".rodata"]
+        ));
+    }
+
+    #[test]
+    fn test_sort_section_by_name() {
+        let output = link_files(
+            "weld_test_sort_section_by_name",
+            vec![PathBuf::from("tests/fixtures/sections_elf_amd64.o")],
+            |configuration| configuration.sort_section(SortSection::Name),
+        )
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let text_section = file
+            .sections
+            .iter()
+            .find(|section| section.name.as_deref().is_some_and(|name| name == ".text"))
+            .unwrap();
+
+        // `.text` comes first, then `.text.a`, `.text.b` and `.text.c`.
+        assert!(text_section.data.ends_with(b"abc"));
+    }
+
+    #[test]
+    fn test_debug_sections() {
+        let input_files = vec![
+            PathBuf::from("tests/fixtures/start_debug_elf_amd64.o"),
+            PathBuf::from("tests/fixtures/exit_debug_elf_amd64.o"),
+        ];
+
+        let input_size = input_files
+            .iter()
+            .map(|input_file| {
+                let bytes = fs::read(input_file).unwrap();
+                let (_, mut file) = File::read::<()>(&bytes).unwrap();
+                file.fetch_section_names();
+
+                file.sections
+                    .iter()
+                    .find(|section| {
+                        section.name.as_deref().is_some_and(|name| name == ".debug_info")
+                    })
+                    .unwrap()
+                    .data
+                    .len()
+            })
+            .sum::<usize>();
+
+        let output = link_files("weld_test_debug_sections", input_files.clone(), |configuration| {
+            configuration
+        })
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let debug_info = file
+            .sections
+            .iter()
+            .find(|section| section.name.as_deref().is_some_and(|name| name == ".debug_info"))
+            .unwrap();
+
+        assert!(!debug_info.flags.contains(SectionFlag::Allocable));
+        assert_eq!(debug_info.data.len(), input_size);
+
+        // The debug sections don't belong to any segment.
+        for program in &file.programs {
+            assert!(debug_info.offset.0 >= program.offset.0 + program.segment_size_in_file_image.0);
+        }
+
+        // With `--strip-debug`.
+        let output = link_files("weld_test_strip_debug", input_files, |configuration| {
+            configuration.strip_debug(true)
+        })
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        assert!(file.sections.iter().all(|section| !section
+            .name
+            .as_ref()
+            .is_some_and(|name| name.starts_with(b".debug_"))));
+    }
+
+    #[test]
+    fn test_interpreter() {
+        let output = link_fixtures("weld_test_interpreter", |configuration| {
+            configuration.dynamic(true).dynamic_linker("/lib/ld-weld.so")
+        })
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        assert_eq!(file.interpreter().as_deref(), Some(b"/lib/ld-weld.so".as_bstr()));
+        // The interpreter program comes right after the program headers one.
+        assert_eq!(file.programs[0].r#type, ProgramType::ProgramHeader);
+        assert_eq!(file.programs[1].r#type, ProgramType::Interpreter);
+
+        // The `.interp` section is covered by the interpreter program.
+        let interp = file
+            .sections
+            .iter()
+            .find(|section| section.name.as_deref().is_some_and(|name| name == ".interp"))
+            .unwrap();
+
+        assert_eq!(interp.virtual_address, file.programs[1].virtual_address);
+
+        // A static executable has no interpreter.
+        let output =
+            link_fixtures("weld_test_no_interpreter", |configuration| configuration).unwrap();
+        let (_, file) = File::read::<()>(&output).unwrap();
+
+        assert_eq!(file.interpreter(), None);
+    }
+
+    #[test]
+    fn test_needed_libraries() {
+        let output = link_fixtures("weld_test_needed_libraries", |configuration| {
+            configuration.library("foo").library_path("tests").library_path("tests/fixtures")
+        })
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        // Needing a library makes the executable dynamic.
+        assert!(file.interpreter().is_some());
+
+        let dynamic_section = file.section_by_name(".dynamic").unwrap();
+        let SectionIndex::Ok(dynamic_strings_index) = dynamic_section.link else {
+            panic!("The dynamic section must be linked to its strings");
+        };
+        let dynamic_strings = &file.sections[dynamic_strings_index];
+
+        assert_eq!(dynamic_strings.name.as_deref(), Some(b".dynstr".as_bstr()));
+
+        let dynamic_entries = dynamic_section
+            .data
+            .dynamic_entries::<()>()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let needed = dynamic_entries
+            .iter()
+            .filter(|dynamic| dynamic.tag == DynamicTag::Needed)
+            .map(|dynamic| dynamic_strings.data.string_at_offset(dynamic.value as usize))
+            .collect::<Vec<_>>();
+
+        assert_eq!(needed, [Some(Cow::Borrowed(b"libfoo.so".as_bstr()))]);
+        assert!(dynamic_entries.contains(&Dynamic {
+            tag: DynamicTag::StringTable,
+            value: dynamic_strings.virtual_address.0,
+        }));
+        assert_eq!(dynamic_entries.last().map(|dynamic| dynamic.tag), Some(DynamicTag::Null));
+
+        // The dynamic program covers the dynamic section.
+        let dynamic_program =
+            file.programs.iter().find(|program| program.r#type == ProgramType::Dynamic).unwrap();
+
+        assert_eq!(dynamic_program.virtual_address, dynamic_section.virtual_address);
+
+        // A missing library is an error.
+        assert!(matches!(
+            link_fixtures("weld_test_missing_library", |configuration| {
+                configuration.library("bar").library_path("tests/fixtures")
+            }),
+            Err(Error::LibraryNotFound(name)) if name == "bar"
+        ));
+
+        // A static executable cannot use a shared library.
+        assert!(matches!(
+            link_fixtures("weld_test_static_library", |configuration| {
+                configuration.library("foo").library_path("tests/fixtures").static_link(true)
+            }),
+            Err(Error::SharedLibraryInStaticLink(_))
+        ));
+    }
+
+    #[test]
+    fn test_dynamic_symbols() {
+        let output = link_files(
+            "weld_test_dynamic_symbols",
+            vec![PathBuf::from("tests/fixtures/foo_elf_amd64.o")],
+            |configuration| configuration.shared(true),
+        )
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let dynamic_symbols_section = file.section_by_name(".dynsym").unwrap();
+        let SectionIndex::Ok(dynamic_strings_index) = dynamic_symbols_section.link else {
+            panic!("The dynamic symbols must be linked to their strings");
+        };
+        let dynamic_strings = &file.sections[dynamic_strings_index];
+
+        assert_eq!(dynamic_strings.name.as_deref(), Some(b".dynstr".as_bstr()));
+
+        let dynamic_symbols = dynamic_symbols_section
+            .data
+            .symbols::<()>(Some(dynamic_strings))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(dynamic_symbols.len(), 2);
+        assert_eq!(dynamic_symbols[1].name.as_deref(), Some(b"foo".as_bstr()));
+        assert_eq!(dynamic_symbols[1].binding, SymbolBinding::Global);
+        assert_eq!(dynamic_symbols[1].r#type, SymbolType::Function);
+
+        // The dynamic entries point at the dynamic symbols and their strings.
+        let dynamic_entries = file
+            .section_by_name(".dynamic")
+            .unwrap()
+            .data
+            .dynamic_entries::<()>()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert!(dynamic_entries.contains(&Dynamic {
+            tag: DynamicTag::SymbolTable,
+            value: dynamic_symbols_section.virtual_address.0,
+        }));
+        assert!(dynamic_entries.contains(&Dynamic {
+            tag: DynamicTag::StringTable,
+            value: dynamic_strings.virtual_address.0,
+        }));
+    }
+
+    #[test]
+    fn test_dynamic_hash() {
+        let output = link_files(
+            "weld_test_dynamic_hash",
+            vec![
+                PathBuf::from("tests/fixtures/start_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/exit_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/foo_elf_amd64.o"),
+            ],
+            |configuration| configuration.shared(true),
+        )
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let hash_section = file.section_by_name(".hash").unwrap();
+        let SectionIndex::Ok(dynamic_symbols_index) = hash_section.link else {
+            panic!("The hash table must be linked to the dynamic symbols");
+        };
+        let dynamic_symbols_section = &file.sections[dynamic_symbols_index];
+        let SectionIndex::Ok(dynamic_strings_index) = dynamic_symbols_section.link else {
+            panic!("The dynamic symbols must be linked to their strings");
+        };
+        let dynamic_symbols = dynamic_symbols_section
+            .data
+            .symbols::<()>(Some(&file.sections[dynamic_strings_index]))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let words = hash_section
+            .data
+            .chunks(4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()) as usize)
+            .collect::<Vec<_>>();
+        let (bucket_count, chain_count) = (words[0], words[1]);
+        let (buckets, chains) = words[2..].split_at(bucket_count);
+
+        assert_eq!(chain_count, dynamic_symbols.len());
+        assert_eq!(chains.len(), chain_count);
+
+        // Every dynamic symbol is found by walking the chain of its bucket.
+        for (index, symbol) in dynamic_symbols.iter().enumerate().skip(1) {
+            let name = symbol.name.as_deref().unwrap();
+            let mut found = buckets[elf_hash(name) as usize % bucket_count];
+
+            while found != 0 && dynamic_symbols[found].name.as_deref() != Some(name) {
+                found = chains[found];
+            }
+
+            assert_eq!(found, index, "`{name}` is not found in the hash table");
+        }
+
+        assert!(file
+            .section_by_name(".dynamic")
+            .unwrap()
+            .data
+            .dynamic_entries::<()>()
+            .unwrap()
+            .any(|dynamic| dynamic
+                == Ok(Dynamic { tag: DynamicTag::Hash, value: hash_section.virtual_address.0 })));
+    }
+
+    #[test]
+    fn test_init_array() {
+        let link_constructors = |output_file_name, constructors: &[&str], dynamic| {
+            let mut input_files = vec![
+                PathBuf::from("tests/fixtures/start_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/exit_elf_amd64.o"),
+            ];
+            input_files.extend(
+                constructors
+                    .iter()
+                    .map(|name| PathBuf::from(format!("tests/fixtures/{name}_elf_amd64.o"))),
+            );
+
+            link_files(output_file_name, input_files, |configuration| {
+                configuration.dynamic(dynamic)
+            })
+            .unwrap()
+        };
+        let init_array = |output: &[u8]| {
+            let (_, mut file) = File::read::<()>(output).unwrap();
+            file.fetch_section_names();
+
+            let address = |name: &str| {
+                file.symbols_with_sections()
+                    .find(|(symbol, _)| symbol.name.as_deref() == Some(name.as_bytes().as_bstr()))
+                    .map(|(symbol, _)| symbol.value.0)
+            };
+            let addresses = file
+                .section_data(".init_array")
+                .unwrap()
+                .chunks_exact(8)
+                .map(|entry| Some(u64::from_le_bytes(entry.try_into().unwrap())))
+                .collect::<Vec<_>>();
+
+            (addresses, ["ctor_a", "ctor_b", "ctor_priority"].map(address))
+        };
+
+        // Constructors are in input order.
+        let output = link_constructors("weld_test_init_array", &["ctor_a", "ctor_b"], false);
+        let (addresses, [ctor_a, ctor_b, _]) = init_array(&output);
+
+        assert_eq!(addresses, [ctor_a, ctor_b]);
+
+        // Constructors with a priority come first.
+        let output =
+            link_constructors("weld_test_init_array", &["ctor_a", "ctor_b", "ctor_priority"], true);
+        let (addresses, [ctor_a, ctor_b, ctor_priority]) = init_array(&output);
+
+        assert_eq!(addresses, [ctor_priority, ctor_a, ctor_b]);
+
+        // The dynamic entries point to the constructors and the destructors.
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let dynamic_entries = file
+            .section_by_name(".dynamic")
+            .unwrap()
+            .data
+            .dynamic_entries::<()>()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        for (name, address_tag, size_tag, size) in [
+            (".init_array", DynamicTag::InitArray, DynamicTag::InitArraySize, 24),
+            (".fini_array", DynamicTag::FiniArray, DynamicTag::FiniArraySize, 8),
+        ] {
+            let section = file.section_by_name(name).unwrap();
+
+            assert!(dynamic_entries
+                .contains(&Dynamic { tag: address_tag, value: section.virtual_address.0 }));
+            assert!(dynamic_entries.contains(&Dynamic { tag: size_tag, value: size }));
+        }
+    }
+
+    #[test]
+    fn test_plt() {
+        let output = link_files(
+            "weld_test_plt",
+            vec![PathBuf::from("tests/fixtures/call_foo_elf_amd64.o")],
+            |configuration| configuration.library("foo").library_path("tests/fixtures"),
+        )
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        // One PLT stub, after the first special one.
+        let plt = file.section_by_name(".plt").unwrap();
+        let plt_got = file.section_by_name(".got.plt").unwrap();
+
+        assert_eq!(plt.data.len(), 32);
+
+        // One `R_X86_64_JUMP_SLOT` relocation against `foo`, for the GOT slot
+        // after the 3 reserved slots.
+        let plt_relocations = file.section_by_name(".rela.plt").unwrap();
+        let SectionIndex::Ok(dynamic_symbols_index) = plt_relocations.link else {
+            panic!("The relocations of the PLT must be linked to the dynamic symbols");
+        };
+        let dynamic_symbols_section = &file.sections[dynamic_symbols_index];
+        let SectionIndex::Ok(dynamic_strings_index) = dynamic_symbols_section.link else {
+            panic!("The dynamic symbols must be linked to their strings");
+        };
+        let dynamic_symbols = dynamic_symbols_section
+            .data
+            .symbols::<()>(Some(&file.sections[dynamic_strings_index]))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let relocations = plt_relocations
+            .data
+            .relocations::<()>()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(relocations.len(), 1);
+        assert_eq!(
+            relocation_type_name(Machine::X86_64, relocations[0].r#type),
+            "R_X86_64_JUMP_SLOT"
+        );
+        assert_eq!(relocations[0].offset.0, plt_got.virtual_address.0 + 3 * 8);
+        assert_eq!(
+            dynamic_symbols[relocations[0].symbol_index as usize].name.as_deref(),
+            Some(b"foo".as_bstr())
+        );
+
+        // The call to `foo` targets its PLT stub: `call` is the first
+        // instruction of `.text`.
+        let text = file.section_by_name(".text").unwrap();
+        let displacement = i32::from_le_bytes(text.data[1..5].try_into().unwrap());
+
+        assert_eq!(
+            text.virtual_address.0.wrapping_add_signed(5 + i64::from(displacement)),
+            plt.virtual_address.0 + 16
+        );
+    }
+
+    #[test]
+    fn test_dynamic_program() {
+        for (name, shared) in
+            [("weld_test_dynamic_program", false), ("weld_test_shared_program", true)]
+        {
+            let output =
+                link_fixtures(name, |configuration| configuration.dynamic(!shared).shared(shared))
+                    .unwrap();
+            let (_, mut file) = File::read::<()>(&output).unwrap();
+            file.fetch_section_names();
+
+            let dynamic_section = file.section_by_name(".dynamic").unwrap();
+            let dynamic_program = file
+                .programs
+                .iter()
+                .find(|program| program.r#type == ProgramType::Dynamic)
+                .unwrap();
+
+            assert_eq!(dynamic_program.offset, dynamic_section.offset);
+            assert_eq!(dynamic_program.virtual_address, dynamic_section.virtual_address);
+            assert_eq!(
+                dynamic_program.segment_size_in_file_image,
+                dynamic_section.segment_size_in_file_image
+            );
+            assert_eq!(
+                dynamic_program.segment_size_in_memory,
+                dynamic_section.segment_size_in_file_image
+            );
+
+            // The dynamic entries are mapped by a loadable program.
+            assert!(file.programs.iter().any(|program| {
+                program.r#type == ProgramType::Load
+                    && program.virtual_address.0 <= dynamic_program.virtual_address.0
+                    && dynamic_program.virtual_address.0 + dynamic_program.segment_size_in_memory.0
+                        <= program.virtual_address.0 + program.segment_size_in_memory.0
+            }));
+        }
+    }
+
+    #[test]
+    fn test_program_headers_program() {
+        let output = link_fixtures("weld_test_program_headers_program", |configuration| {
+            configuration.dynamic(true)
+        })
+        .unwrap();
+        let (_, file) = File::read::<()>(&output).unwrap();
+
+        // The program headers program comes first, and covers exactly the
+        // program headers.
+        let program_headers = &file.programs[0];
+        let program_headers_offset = u64::from_le_bytes(output[32..40].try_into().unwrap());
+        let program_count = u16::from_le_bytes(output[56..58].try_into().unwrap()) as u64;
+
+        assert_eq!(program_headers.r#type, ProgramType::ProgramHeader);
+        assert_eq!(program_headers.offset.0, program_headers_offset);
+        assert_eq!(
+            program_headers.segment_size_in_file_image.0,
+            program_count * Program::SIZE as u64
+        );
+        assert_eq!(
+            program_headers.segment_size_in_memory,
+            program_headers.segment_size_in_file_image
+        );
+        assert_eq!(program_count as usize, file.programs.len());
+
+        // Its virtual address is the one of the program headers in the first
+        // loadable program, which starts with the file header.
+        let first_load =
+            file.programs.iter().find(|program| program.r#type == ProgramType::Load).unwrap();
+
+        assert_eq!(first_load.offset.0, 0);
+        assert_eq!(
+            program_headers.virtual_address.0,
+            first_load.virtual_address.0 + program_headers_offset
+        );
+
+        // A static executable has none.
+        let output =
+            link_fixtures("weld_test_no_program_headers_program", |configuration| configuration)
+                .unwrap();
+        let (_, file) = File::read::<()>(&output).unwrap();
+
+        assert!(file.programs.iter().all(|program| program.r#type != ProgramType::ProgramHeader));
+    }
+
+    #[test]
+    fn test_soname() {
+        let output = link_fixtures("weld_test_soname", |configuration| {
+            configuration.shared(true).soname("libfoo.so.1")
+        })
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        assert_eq!(file.r#type, FileType::SharedObject);
+        assert!(file.interpreter().is_none());
+        assert_eq!(file.shared_object_name().as_deref(), Some(b"libfoo.so.1".as_bstr()));
+
+        // Without `--shared`, the name is ignored with a warning.
+        let configuration = Configuration::new(
+            Triple::host(),
+            vec![
+                PathBuf::from("tests/fixtures/start_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/exit_elf_amd64.o"),
+            ],
+            env::temp_dir().join("weld_test_ignored_soname"),
+        )
+        .soname("libfoo.so.1");
+        let (output, warnings) = link_to_vec(&configuration).unwrap();
+        let (_, file) = File::read::<()>(&output).unwrap();
+
+        assert_eq!(file.r#type, FileType::ExecutableFile);
+        assert!(matches!(
+            warnings.iter().collect::<Vec<_>>()[..],
+            [Warning::IgnoredSoname(ref name)] if name == "libfoo.so.1"
+        ));
+    }
+
+    #[test]
+    fn test_rpath() {
+        fn run_path(name: &str, new_dtags: bool) -> Option<(DynamicTag, BString)> {
+            let output = link_fixtures(name, |configuration| {
+                configuration.rpath("/a:/b").new_dtags(new_dtags)
+            })
+            .unwrap();
+            let (_, mut file) = File::read::<()>(&output).unwrap();
+            file.fetch_section_names();
+
+            let dynamic_section = file.section_by_name(".dynamic")?;
+            let SectionIndex::Ok(dynamic_strings_index) = dynamic_section.link else {
+                panic!("The dynamic section must be linked to its strings");
+            };
+            let dynamic_strings = &file.sections[dynamic_strings_index];
+
+            dynamic_section
+                .data
+                .dynamic_entries::<()>()
+                .unwrap()
+                .map(Result::unwrap)
+                .find(|dynamic| matches!(dynamic.tag, DynamicTag::RunPath | DynamicTag::RPath))
+                .map(|dynamic| {
+                    let path = dynamic_strings.data.string_at_offset(dynamic.value as usize);
+
+                    (dynamic.tag, path.unwrap().into_owned())
+                })
+        }
+
+        assert_eq!(
+            run_path("weld_test_runpath", true),
+            Some((DynamicTag::RunPath, "/a:/b".into()))
+        );
+        assert_eq!(run_path("weld_test_rpath", false), Some((DynamicTag::RPath, "/a:/b".into())));
+    }
+
+    #[test]
+    fn test_target_endianness() {
+        for (target, expected) in [
+            ("x86_64-unknown-linux-gnu", Endianness::Little),
+            ("aarch64-unknown-linux-gnu", Endianness::Little),
+            ("powerpc-unknown-linux-gnu", Endianness::Big),
+            ("s390x-unknown-linux-gnu", Endianness::Big),
+        ] {
+            assert_eq!(target_endianness(&target.parse().unwrap()).unwrap(), expected);
+        }
+
+        assert!(matches!(target_endianness(&Triple::unknown()), Err(Error::UnknownEndianness(_))));
+    }
+
+    #[test]
+    fn test_os_abi() {
+        let output = link_fixtures("weld_test_os_abi_freebsd", |mut configuration| {
+            configuration.target = "x86_64-unknown-freebsd".parse().unwrap();
+            configuration
+        })
+        .unwrap();
+        let (_, file) = File::read::<()>(&output).unwrap();
+
+        assert_eq!(file.os_abi, OsAbi::FreeBsd);
+
+        let output = link_fixtures("weld_test_os_abi_linux", |mut configuration| {
+            configuration.target = "x86_64-unknown-linux-gnu".parse().unwrap();
+            configuration
+        })
+        .unwrap();
+        let (_, file) = File::read::<()>(&output).unwrap();
+
+        assert_eq!(file.os_abi, OsAbi::SystemV);
+    }
+
+    #[test]
+    fn test_static_link() {
+        // A static executable has no interpreter, even if dynamic.
+        let output = link_fixtures("weld_test_static_link", |configuration| {
+            configuration.dynamic(true).static_link(true)
+        })
+        .unwrap();
+        let (_, file) = File::read::<()>(&output).unwrap();
+
+        assert_eq!(file.interpreter(), None);
+        assert!(file.programs.iter().all(|program| program.r#type == ProgramType::Load));
+
+        // All used symbols must be defined, even if never relocated.
+        let input_files = vec![PathBuf::from("tests/fixtures/static_elf_amd64.o")];
+
+        assert!(link_files("weld_test_not_static_link", input_files.clone(), |configuration| {
+            configuration
+        })
+        .is_ok());
+
+        let result = link_files("weld_test_static_link_undefined", input_files, |configuration| {
+            configuration.static_link(true)
+        });
+
+        assert!(matches!(result, Err(Error::UndefinedSymbol(symbol)) if symbol == "missing"));
+    }
+
+    #[test]
+    fn test_static_link_archive() {
+        // `libexit.a` contains `exit_elf_amd64.o`, which defines the symbols
+        // used by `start_elf_amd64.o`, and `foo_elf_amd64.o`, which is not
+        // needed.
+        let symbol_names = |output: Vec<u8>| {
+            let (_, mut file) = File::read::<()>(&output).unwrap();
+            file.fetch_section_names();
+
+            assert_eq!(file.interpreter(), None);
+            assert!(file.programs.iter().all(|program| program.r#type == ProgramType::Load));
+
+            file.section_by_name(".symtab")
+                .unwrap()
+                .data
+                .symbols::<()>(Some(file.section_by_name(".strtab").unwrap()))
+                .unwrap()
+                .filter_map(|symbol| Some(symbol.unwrap().name?.to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        let output = link_files(
+            "weld_test_static_link_archive",
+            vec![
+                PathBuf::from("tests/fixtures/start_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/libexit.a"),
+            ],
+            |configuration| configuration.static_link(true),
+        )
+        .unwrap();
+        let names = symbol_names(output);
+
+        assert!(names.iter().any(|name| name == "do_exit"));
+        assert!(names.iter().any(|name| name == "answer"));
+        assert!(!names.iter().any(|name| name == "foo"));
+
+        // The archive is found by its name with `-l`.
+        let output = link_files(
+            "weld_test_static_link_library",
+            vec![PathBuf::from("tests/fixtures/start_elf_amd64.o")],
+            |configuration| {
+                configuration.library("exit").library_path("tests/fixtures").static_link(true)
+            },
+        )
+        .unwrap();
+
+        assert_eq!(symbol_names(output), names);
+
+        // An unneeded archive is not linked at all.
+        let output = link_files(
+            "weld_test_static_link_unneeded_archive",
+            vec![
+                PathBuf::from("tests/fixtures/start_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/exit_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/libexit.a"),
+            ],
+            |configuration| configuration.static_link(true),
+        )
+        .unwrap();
+
+        assert!(!symbol_names(output).iter().any(|name| name == "foo"));
+
+        // An archive alone has no member to link.
+        let result = link_files(
+            "weld_test_static_link_archive_alone",
+            vec![PathBuf::from("tests/fixtures/libexit.a")],
+            |configuration| configuration,
+        );
+
+        assert!(matches!(result, Err(Error::NoInputFile)));
+    }
+
+    #[test]
+    fn test_symbol_table_order() {
+        let output = link_fixtures("weld_test_symbol_table_order", |configuration| {
+            configuration.emit_relocations(true)
+        })
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let symbol_table = file.section_by_name(".symtab").unwrap();
+        let symbols = symbol_table
+            .data
+            .symbols::<()>(Some(file.section_by_name(".strtab").unwrap()))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let first_global = symbol_table.information as usize;
+
+        // Local symbols come first.
+        assert!(first_global > 1);
+        assert!(symbols[..first_global]
+            .iter()
+            .all(|symbol| symbol.binding == SymbolBinding::Local));
+        assert!(symbols[first_global..]
+            .iter()
+            .all(|symbol| symbol.binding != SymbolBinding::Local));
+
+        // Relocations point to the right symbols.
+        let mut names = file
+            .section_by_name(".rela.text")
+            .unwrap()
+            .data
+            .relocations::<()>()
+            .unwrap()
+            .map(|relocation| &symbols[relocation.unwrap().symbol_index as usize])
+            .filter(|symbol| symbol.r#type != SymbolType::Section)
+            .map(|symbol| symbol.name.as_deref().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        names.sort();
+
+        assert_eq!(names, ["answer", "do_exit"]);
+    }
+
+    #[test]
+    fn test_undefined_weak_symbol() {
+        let output = link_files(
+            "weld_test_undefined_weak_symbol",
+            vec![PathBuf::from("tests/fixtures/weak_elf_amd64.o")],
+            |configuration| configuration.emit_relocations(true),
+        )
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let symbols = file
+            .section_by_name(".symtab")
+            .unwrap()
+            .data
+            .symbols::<()>(Some(file.section_by_name(".strtab").unwrap()))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let relocation = file
+            .section_by_name(".rela.text")
+            .unwrap()
+            .data
+            .relocations::<()>()
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let symbol = &symbols[relocation.symbol_index as usize];
+
+        assert_eq!(symbol.name.as_deref(), Some(b"undefined_weak".as_bstr()));
+        assert_eq!(symbol.binding, SymbolBinding::Weak);
+        assert_eq!(symbol.section_index_where_symbol_is_defined, SectionIndex::Undefined);
+    }
+
+    #[test]
+    fn test_absolute_symbol() {
+        let input_files = vec![
+            PathBuf::from("tests/fixtures/absolute_use_elf_amd64.o"),
+            PathBuf::from("tests/fixtures/absolute_elf_amd64.o"),
+        ];
+
+        // The value of an absolute symbol doesn't depend on the layout.
+        for image_base in [DEFAULT_IMAGE_BASE, 0x80_0000] {
+            let output =
+                link_files("weld_test_absolute_symbol", input_files.clone(), |configuration| {
+                    configuration.image_base(image_base)
+                })
+                .unwrap();
+            let (_, mut file) = File::read::<()>(&output).unwrap();
+            file.fetch_section_names();
+
+            // `mov edi, imm32`.
+            assert_eq!(
+                &file.section_by_name(".text").unwrap().data[..5],
+                &[0xbf, 0x00, 0x10, 0x00, 0x00]
+            );
+
+            let symbols = file
+                .section_by_name(".symtab")
+                .unwrap()
+                .data
+                .symbols::<()>(Some(file.section_by_name(".strtab").unwrap()))
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let end = symbols
+                .iter()
+                .find(|symbol| symbol.name.as_deref() == Some(b"_end".as_bstr()))
+                .unwrap();
+
+            assert_eq!(end.section_index_where_symbol_is_defined, SectionIndex::Absolute);
+            assert_eq!(end.value, Address(0x1000));
+        }
+    }
+
+    #[test]
+    fn test_no_bits_segment_size() {
+        let output = link_files(
+            "weld_test_no_bits_segment_size",
+            vec![PathBuf::from("tests/fixtures/bss_elf_amd64.o")],
+            |configuration| configuration,
+        )
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let writable_segment = file
+            .programs
+            .iter()
+            .find(|program| {
+                program.r#type == ProgramType::Load
+                    && program.segment_flags.contains(ProgramFlag::Write)
+            })
+            .unwrap();
+
+        // `.data` is 16 bytes, `.bss` is 48 bytes.
+        assert_eq!(writable_segment.segment_size_in_file_image, Address(16));
+        assert_eq!(writable_segment.segment_size_in_memory, Address(64));
+
+        // The `.bss` section has no bytes, but its data knows its size.
+        let bss = file.section_by_name(".bss").unwrap();
+
+        assert!(bss.data.is_nobits());
+        assert!(bss.data.is_empty());
+        assert_eq!(bss.data.logical_size(), 48);
+    }
+
+    #[test]
+    fn test_truncated_file_header() {
+        let input_file = env::temp_dir().join("weld_test_truncated_file_header.o");
+        fs::write(&input_file, &fs::read("tests/fixtures/exit_elf_amd64.o").unwrap()[..20])
+            .unwrap();
+
+        let result = link_files(
+            "weld_test_truncated_file_header",
+            vec![input_file.clone()],
+            |configuration| configuration,
+        );
+        fs::remove_file(&input_file).unwrap();
+
+        assert!(matches!(result, Err(Error::TruncatedFileHeader(path)) if path == input_file));
+    }
+
+    #[test]
+    fn test_not_an_elf_file() {
+        let input_file = env::temp_dir().join("weld_test_not_an_elf_file.o");
+        fs::write(&input_file, "#!/bin/sh\nexit 42\n").unwrap();
+
+        let result =
+            link_files("weld_test_not_an_elf_file", vec![input_file.clone()], |configuration| {
+                configuration
+            });
+        fs::remove_file(&input_file).unwrap();
+
+        let Err(error @ Error::NotAnElfFile(..)) = result else {
+            panic!("Expected a `NotAnElfFile` error, got {result:?}");
+        };
+
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "The input file `{}` is not an ELF file: the file starts with `#!/b` instead of \
+                the ELF magic number `\\x7fELF`.",
+                input_file.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_invalid_section_alignment() {
+        let input_file = env::temp_dir().join("weld_test_invalid_section_alignment.o");
+        let mut bytes = fs::read("tests/fixtures/exit_elf_amd64.o").unwrap();
+        // The alignment of the section header #1, i.e. `sh_addralign`.
+        let offset = u64::from_le_bytes(bytes[40..48].try_into().unwrap()) as usize + 64 + 48;
+        bytes[offset..offset + 8].copy_from_slice(&3u64.to_le_bytes());
+        fs::write(&input_file, &bytes).unwrap();
+
+        let result = link_files(
+            "weld_test_invalid_section_alignment",
+            vec![input_file.clone()],
+            |configuration| configuration,
+        );
+        fs::remove_file(&input_file).unwrap();
+
+        let Err(error @ Error::InvalidHeader(..)) = result else {
+            panic!("expected an invalid header error, got {result:?}");
+        };
+
+        assert!(error.to_string().contains("the section header #1, `3`"), "{error}");
+    }
+
+    #[test]
+    fn test_malformed_symbol_name() {
+        let bytes = fs::read("tests/fixtures/exit_elf_amd64.o").unwrap();
+        let (_, file) = File::read_resolved::<()>(&bytes).unwrap();
+        let symbol_table = file
+            .sections
+            .iter()
+            .find(|section| section.r#type == SectionType::SymbolTable)
+            .unwrap();
+        let strings_size = file.strings_section().unwrap().data.len() as u32;
+        let symbol_index = file
+            .symbols()
+            .position(|symbol| symbol.name.as_deref() == Some(b"do_exit".as_bstr()))
+            .unwrap();
+        // The name offset of the symbol, i.e. `st_name`.
+        let offset = symbol_table.offset.0 as usize + symbol_index * Symbol::SIZE;
+
+        // The name offset points past the string table, or to an empty name.
+        for (name_offset, test_name) in [
+            (strings_size + 10, "weld_test_malformed_symbol_name_past_strtab"),
+            (0, "weld_test_malformed_symbol_name_empty"),
+        ] {
+            let input_file = env::temp_dir().join(format!("{test_name}.o"));
+            let mut bytes = bytes.clone();
+            bytes[offset..offset + 4].copy_from_slice(&name_offset.to_le_bytes());
+            fs::write(&input_file, &bytes).unwrap();
+
+            let result =
+                link_files(test_name, vec![input_file.clone()], |configuration| configuration);
+            fs::remove_file(&input_file).unwrap();
+
+            assert!(
+                matches!(
+                    &result,
+                    Err(Error::MalformedSymbolName(path, index, offset))
+                        if *path == input_file
+                            && *index == symbol_index
+                            && *offset == u64::from(name_offset)
+                ),
+                "{result:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_empty_file() {
+        let input_file = env::temp_dir().join("weld_test_empty_file.o");
+        fs::write(&input_file, []).unwrap();
+
+        let result =
+            link_files("weld_test_empty_file", vec![input_file.clone()], |configuration| {
+                configuration
+            });
+        fs::remove_file(&input_file).unwrap();
+
+        assert!(matches!(
+            result,
+            Err(Error::ReadingFile(path, error))
+                if path == input_file && error.kind() == io::ErrorKind::InvalidInput
+        ));
+    }
+
+    #[test]
+    fn test_warnings() {
+        let input_files = vec![PathBuf::from("tests/fixtures/main_elf_amd64.o")];
+        let output_file = env::temp_dir().join("weld_test_warnings");
+        let configuration =
+            || Configuration::new(Triple::host(), input_files.clone(), output_file.clone());
+
+        // `main` is used as the entry point.
+        let warnings = link(configuration()).unwrap();
+        fs::remove_file(&output_file).unwrap();
+
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            warnings.iter().next(),
+            Some(Warning::EntryPointFallback(symbol)) if symbol == "main"
+        ));
+
+        // Warnings can be ignored.
+        let warnings = link(configuration().no_warnings(true)).unwrap();
+        fs::remove_file(&output_file).unwrap();
+
+        assert!(warnings.is_empty());
+
+        // Warnings can be treated as errors.
+        assert!(matches!(
+            link(configuration().fatal_warnings(true)),
+            Err(Error::FatalWarnings(warnings)) if warnings.len() == 1
+        ));
+        assert!(!output_file.exists());
+    }
+
+    #[test]
+    fn test_eh_frame_hdr() {
+        let output = link_files(
+            "weld_test_eh_frame_hdr",
+            vec![PathBuf::from("tests/fixtures/eh_frame_elf_amd64.o")],
+            |configuration| configuration,
+        )
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let eh_frame = file.section_by_name(".eh_frame").unwrap();
+        let eh_frame_hdr = file.section_by_name(".eh_frame_hdr").unwrap();
+        let text = file.section_by_name(".text").unwrap();
+
+        // The `.eh_frame_hdr` section is covered by its own program.
+        let program =
+            file.programs.iter().find(|program| program.r#type == ProgramType::GnuEhFrame).unwrap();
+
+        assert_eq!(program.virtual_address, eh_frame_hdr.virtual_address);
+        assert_eq!(program.segment_size_in_memory, eh_frame_hdr.segment_size_in_file_image);
+
+        let hdr_address = eh_frame_hdr.virtual_address.0;
+        let data = &**eh_frame_hdr.data;
+        let read_i32 =
+            |offset: usize| i32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as i64;
+
+        // Version, and encodings.
+        assert_eq!(&data[..4], &[0x01, 0x1b, 0x03, 0x3b]);
+        // The pointer to `.eh_frame` is relative to its own field.
+        assert_eq!(hdr_address.wrapping_add_signed(4 + read_i32(4)), eh_frame.virtual_address.0);
+        // The number of FDEs.
+        assert_eq!(read_i32(8), 2);
+
+        let table = (0..2)
+            .map(|nth| {
+                (
+                    hdr_address.wrapping_add_signed(read_i32(12 + nth * 8)),
+                    hdr_address.wrapping_add_signed(read_i32(16 + nth * 8)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // The FDE of `late` comes first in `.eh_frame`, but its function comes
+        // last in `.text`: the table is sorted by initial location.
+        assert!(table.is_sorted());
+        assert_eq!(table[0].0, text.virtual_address.0);
+        assert_eq!(table[0].1, eh_frame.virtual_address.0 + 0x2c);
+        assert_eq!(table[1].1, eh_frame.virtual_address.0 + 0x18);
+
+        // Without `.eh_frame`, there is no `.eh_frame_hdr`.
+        let output =
+            link_fixtures("weld_test_no_eh_frame_hdr", |configuration| configuration).unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        assert!(file.section_by_name(".eh_frame_hdr").is_none());
+        assert!(file.programs.iter().all(|program| program.r#type != ProgramType::GnuEhFrame));
+    }
+
+    #[test]
+    fn test_eh_frame_unwind_type() {
+        // The same input as `eh_frame_elf_amd64.o`, assembled by LLVM: its
+        // `.eh_frame` section is typed `SHT_X86_64_UNWIND`.
+        let input_file = PathBuf::from("tests/fixtures/eh_frame_unwind_elf_amd64.o");
+        let input = fs::read(&input_file).unwrap();
+        let (_, mut file) = File::read::<()>(&input).unwrap();
+        file.fetch_section_names();
+
+        assert_eq!(file.section_by_name(".eh_frame").unwrap().r#type, SectionType::Unwind);
+
+        let output =
+            link_files("weld_test_eh_frame_unwind_type", vec![input_file], |configuration| {
+                configuration
+            })
+            .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let eh_frame = file.section_by_name(".eh_frame").unwrap();
+        let eh_frame_hdr = file.section_by_name(".eh_frame_hdr").unwrap();
+
+        assert!(eh_frame.flags.contains(SectionFlag::Allocable));
+        assert!(file.programs.iter().any(|program| program.r#type == ProgramType::GnuEhFrame));
+        // The number of FDEs.
+        assert_eq!(&eh_frame_hdr.data[8..12], &2u32.to_le_bytes());
+    }
+
+    #[test]
+    fn test_thread_local_storage() {
+        let output = link_files(
+            "weld_test_thread_local_storage",
+            vec![PathBuf::from("tests/fixtures/tls_elf_amd64.o")],
+            |configuration| configuration,
+        )
+        .unwrap();
+        let (_, file) = File::read_resolved::<()>(&output).unwrap();
+
+        let tdata = file.section_by_name(".tdata").unwrap();
+        let tbss = file.section_by_name(".tbss").unwrap();
+        let text = file.section_by_name(".text").unwrap();
+
+        // The thread-local sections are covered by the thread-local storage
+        // program: its file image is `.tdata`, its memory is `.tdata` and
+        // `.tbss`.
+        let program = file
+            .programs
+            .iter()
+            .find(|program| program.r#type == ProgramType::ThreadLocalStorage)
+            .unwrap();
+
+        assert_eq!(program.offset, tdata.offset);
+        assert_eq!(program.virtual_address, tdata.virtual_address);
+        assert_eq!(program.segment_size_in_file_image, Address(4));
+        assert_eq!(program.segment_size_in_memory, Address(40));
+        assert_eq!(program.alignment, Alignment::new(16).unwrap());
+        assert_eq!(tbss.virtual_address.0, tdata.virtual_address.0 + 16);
+
+        // The template lies inside a writable loadable segment.
+        assert!(file.programs.iter().any(|program| program.r#type == ProgramType::Load
+            && program.segment_flags.contains(ProgramFlag::Write)
+            && program.virtual_address.0 <= tdata.virtual_address.0
+            && tbss.virtual_address.0 + 24
+                <= program.virtual_address.0 + program.segment_size_in_memory.0));
+
+        // The values of the thread-local symbols are relative to the
+        // template.
+        let value = |name: &str| {
+            file.symbols().find(|symbol| symbol.name.as_deref() == Some(name.into())).unwrap().value
+        };
+
+        assert_eq!(value("counter"), Address(0));
+        assert_eq!(value("buffer"), Address(16));
+
+        // The thread pointer is after the template, aligned: the offsets are
+        // relative to it.
+        let read_i32 =
+            |offset: usize| i32::from_le_bytes(text.data[offset..offset + 4].try_into().unwrap());
+
+        assert_eq!(read_i32(4), -48);
+        assert_eq!(read_i32(11), -32);
+    }
+
+    #[test]
+    fn test_post_process() {
+        let output_file = env::temp_dir().join("weld_test_post_process");
+        let configuration = || {
+            Configuration::new(
+                Triple::host(),
+                vec![
+                    PathBuf::from("tests/fixtures/start_elf_amd64.o"),
+                    PathBuf::from("tests/fixtures/exit_elf_amd64.o"),
+                ],
+                output_file.clone(),
+            )
+        };
+
+        // The hook receives the final image.
+        link(configuration().post_process(|output| {
+            assert!(File::read::<()>(&output).is_ok());
+
+            Ok(b"weld".to_vec())
+        }))
+        .unwrap();
+
+        assert_eq!(fs::read(&output_file).unwrap(), b"weld");
+        fs::remove_file(&output_file).unwrap();
+
+        // A failing hook fails the link, and no output file is written.
+        assert!(matches!(
+            link(configuration().post_process(|_| Err(io::Error::other("unsigned")))),
+            Err(Error::PostProcessingOutput(_, error)) if error.to_string() == "unsigned"
+        ));
+        assert!(!output_file.exists());
+    }
+
+    #[test]
+    fn test_merged_symbol_names() {
+        let output = link_files(
+            "weld_test_merged_symbol_names",
+            vec![
+                PathBuf::from("tests/fixtures/main_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/local_main_elf_amd64.o"),
+            ],
+            |configuration| configuration.no_warnings(true),
+        )
+        .unwrap();
+        let (_, mut file) = File::read::<()>(&output).unwrap();
+        file.fetch_section_names();
+
+        let symbol_table = file.section_by_name(".symtab").unwrap();
+        let strings = file.strings_section().unwrap();
+        let mains = symbol_table
+            .data
+            .symbols::<()>(Some(strings))
+            .unwrap()
+            .map(Result::unwrap)
+            .filter(|symbol| symbol.name.as_deref().is_some_and(|name| name == "main"))
+            .collect::<Vec<_>>();
+
+        // The local `main` and the global `main` share their name.
+        assert_eq!(mains.len(), 2);
+        assert_eq!(mains[0].binding, SymbolBinding::Local);
+        assert_eq!(mains[1].binding, SymbolBinding::Global);
+        assert_eq!(mains[0].name_offset, mains[1].name_offset);
+        assert_eq!(strings.data.find(b"main"), strings.data.rfind(b"main"));
+        // The empty string is at offset 0.
+        assert_eq!(strings.data[0], 0);
+    }
+
+    #[test]
+    fn test_comdat_groups() {
+        let output = link_files(
+            "weld_test_comdat_groups",
+            vec![
+                PathBuf::from("tests/fixtures/comdat_a_elf_amd64.o"),
+                PathBuf::from("tests/fixtures/comdat_b_elf_amd64.o"),
+            ],
+            |configuration| configuration,
+        )
+        .unwrap();
+        let (_, file) = File::read_resolved::<()>(&output).unwrap();
+
+        // Only the copy of `inline_fn` of the first file is kept, i.e. the one
+        // returning 42, not the one returning 43.
+        let text = file.section_by_name(".text").unwrap();
+        let inline_fn = file
+            .symbols()
+            .filter(|symbol| symbol.name.as_deref().is_some_and(|name| name == "inline_fn"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(inline_fn.len(), 1);
+        assert!(text.data.windows(5).any(|bytes| bytes == [0xb8, 0x2a, 0x00, 0x00, 0x00]));
+        assert!(!text.data.windows(5).any(|bytes| bytes == [0xb8, 0x2b, 0x00, 0x00, 0x00]));
+        assert!(!text.flags.contains(SectionFlag::IsPartOfAGroup));
+    }
+}
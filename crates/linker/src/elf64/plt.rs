@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use bstr::{BStr, BString};
+use weld_object::{
+    elf64::{Address, Endianness, Relocation},
+    prelude::*,
+};
+
+/// Name of the section holding the PLT stubs.
+pub(super) const PLT_SECTION_NAME: &str = ".plt";
+
+/// Name of the section holding the GOT slots used by the PLT stubs.
+pub(super) const PLT_GOT_SECTION_NAME: &str = ".got.plt";
+
+/// Name of the section holding the relocations of the GOT slots.
+pub(super) const PLT_RELOCATIONS_SECTION_NAME: &str = ".rela.plt";
+
+/// `R_X86_64_PLT32`.
+pub(super) const R_X86_64_PLT32: u32 = 4;
+
+/// `R_X86_64_JUMP_SLOT`.
+const R_X86_64_JUMP_SLOT: u32 = 7;
+
+/// Size, in bytes, of a PLT stub.
+pub(super) const PLT_ENTRY_SIZE: u64 = 16;
+
+/// Size, in bytes, of a GOT slot.
+const GOT_ENTRY_SIZE: u64 = 8;
+
+/// Number of reserved GOT slots: the address of `.dynamic`, and two slots
+/// filled by the dynamic linker to resolve the symbols lazily.
+const RESERVED_GOT_ENTRIES: u64 = 3;
+
+/// The procedure linkage table, i.e. the PLT, for x86-64.
+///
+/// A call to a function of a shared object goes through a PLT stub, which
+/// jumps to the address held by a GOT slot. This slot is filled by the
+/// dynamic linker thanks to a `R_X86_64_JUMP_SLOT` relocation. The first PLT
+/// stub is special: it calls the dynamic linker to resolve a symbol the first
+/// time it is used.
+#[derive(Debug, Default)]
+pub(super) struct Plt {
+    /// Index, in the dynamic symbols, of the symbol of each PLT stub.
+    entries: Vec<u32>,
+    /// Map a symbol name to the index of its PLT stub.
+    by_name: HashMap<BString, usize>,
+}
+
+impl Plt {
+    /// Add a PLT stub for a dynamic symbol, if it has none yet.
+    pub(super) fn push(&mut self, name: &BStr, dynamic_symbol_index: u32) {
+        if !self.by_name.contains_key(name) {
+            self.by_name.insert(name.to_owned(), self.entries.len());
+            self.entries.push(dynamic_symbol_index);
+        }
+    }
+
+    /// Check whether there is no PLT stub.
+    pub(super) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the index of the PLT stub of a symbol, if any.
+    pub(super) fn entry(&self, name: &BStr) -> Option<usize> {
+        self.by_name.get(name).copied()
+    }
+
+    /// Get the address of a PLT stub, given the address of the PLT. The first
+    /// stub, which is special, is skipped.
+    pub(super) fn entry_address(plt_address: u64, entry: usize) -> u64 {
+        plt_address + (entry as u64 + 1) * PLT_ENTRY_SIZE
+    }
+
+    /// Get the size, in bytes, of the PLT stubs.
+    pub(super) fn size(&self) -> usize {
+        (self.entries.len() + 1) * PLT_ENTRY_SIZE as usize
+    }
+
+    /// Get the size, in bytes, of the GOT slots.
+    pub(super) fn got_size(&self) -> usize {
+        (self.entries.len() + RESERVED_GOT_ENTRIES as usize) * GOT_ENTRY_SIZE as usize
+    }
+
+    /// Get the size, in bytes, of the relocations of the GOT slots.
+    pub(super) fn relocations_size(&self) -> usize {
+        self.entries.len() * Relocation::SIZE
+    }
+
+    /// Encode the PLT stubs, once the addresses of the PLT and of the GOT
+    /// slots are known.
+    pub(super) fn stubs(&self, plt_address: u64, got_address: u64) -> Vec<u8> {
+        // The displacement of an instruction operand, relative to the end of
+        // the instruction, i.e. to `%rip`.
+        let displacement =
+            |target: u64, next_instruction: u64| target.wrapping_sub(next_instruction) as u32;
+
+        let mut data = Vec::with_capacity(self.size());
+
+        // `push GOT[1](%rip)`, `jmp *GOT[2](%rip)`, `nop`.
+        data.extend_from_slice(&[0xff, 0x35]);
+        data.extend_from_slice(
+            &displacement(got_address + GOT_ENTRY_SIZE, plt_address + 6).to_le_bytes(),
+        );
+        data.extend_from_slice(&[0xff, 0x25]);
+        data.extend_from_slice(
+            &displacement(got_address + 2 * GOT_ENTRY_SIZE, plt_address + 12).to_le_bytes(),
+        );
+        data.extend_from_slice(&[0x0f, 0x1f, 0x40, 0x00]);
+
+        for entry in 0..self.entries.len() {
+            let entry_address = Self::entry_address(plt_address, entry);
+
+            // `jmp *GOT[n](%rip)`, `push n`, `jmp PLT[0]`.
+            data.extend_from_slice(&[0xff, 0x25]);
+            data.extend_from_slice(
+                &displacement(Self::got_slot_address(got_address, entry), entry_address + 6)
+                    .to_le_bytes(),
+            );
+            data.push(0x68);
+            data.extend_from_slice(&(entry as u32).to_le_bytes());
+            data.push(0xe9);
+            data.extend_from_slice(&displacement(plt_address, entry_address + 16).to_le_bytes());
+        }
+
+        data
+    }
+
+    /// Encode the GOT slots. The first slot holds the address of `.dynamic`.
+    /// Before the symbols are resolved, a slot holds the address of the
+    /// `push` instruction of its PLT stub.
+    pub(super) fn got(
+        &self,
+        dynamic_address: u64,
+        plt_address: u64,
+        endianness: Endianness,
+    ) -> Vec<u8> {
+        let write_u64 = match endianness {
+            Endianness::Big => BigEndian::write_u64,
+            Endianness::Little => LittleEndian::write_u64,
+        };
+
+        [dynamic_address, 0, 0]
+            .into_iter()
+            .chain((0..self.entries.len()).map(|entry| Self::entry_address(plt_address, entry) + 6))
+            .flat_map(write_u64)
+            .collect()
+    }
+
+    /// Encode the `R_X86_64_JUMP_SLOT` relocations of the GOT slots.
+    pub(super) fn relocations(&self, got_address: u64, endianness: Endianness) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.relocations_size());
+
+        for (entry, dynamic_symbol_index) in self.entries.iter().enumerate() {
+            let relocation = Relocation {
+                offset: Address(Self::got_slot_address(got_address, entry)),
+                symbol_index: *dynamic_symbol_index,
+                r#type: R_X86_64_JUMP_SLOT,
+                addend: 0,
+            };
+
+            match endianness {
+                Endianness::Big => relocation.write::<BigEndian, _>(&mut data),
+                Endianness::Little => relocation.write::<LittleEndian, _>(&mut data),
+            }
+            .expect("Writing in a `Vec` cannot fail");
+        }
+
+        data
+    }
+
+    /// Get the address of the GOT slot of a PLT stub.
+    fn got_slot_address(got_address: u64, entry: usize) -> u64 {
+        got_address + (RESERVED_GOT_ENTRIES + entry as u64) * GOT_ENTRY_SIZE
+    }
+}
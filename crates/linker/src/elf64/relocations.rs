@@ -0,0 +1,476 @@
+use bstr::ByteSlice;
+use weld_object::{
+    elf64::{
+        relocation_type_name, Address, Endianness, Machine, Relocation, SectionIndex, SectionType,
+        SymbolType,
+    },
+    prelude::*,
+};
+
+use super::{
+    layout::Layout,
+    plt::Plt,
+    symbols::{Resolution, SymbolReference, SymbolTable, Symbols},
+    Error, InputFile,
+};
+
+/// Relocations of an output section, rewritten for the output file.
+#[derive(Debug)]
+pub(super) struct OutputRelocations {
+    /// Index of the output section the relocations apply to.
+    pub(super) section: usize,
+    /// The relocations, whose offsets are virtual addresses, and whose symbol
+    /// indices point to the output symbol table.
+    pub(super) relocations: Vec<Relocation>,
+}
+
+/// Apply all the relocations of all input files onto the output sections.
+///
+/// A relocation against a symbol having a PLT stub targets this stub.
+///
+/// If `symbol_table` is present, the applied relocations are rewritten for
+/// the output file, and returned.
+pub(super) fn apply<N>(
+    files: &[InputFile],
+    symbols: &Symbols<N>,
+    layout: &mut Layout,
+    plt: &Plt,
+    symbol_table: Option<&SymbolTable>,
+) -> Result<Vec<OutputRelocations>, Error>
+where
+    N: Number,
+{
+    let mut output_relocations = Vec::<OutputRelocations>::new();
+    let thread_pointer = layout.thread_pointer();
+
+    for (file_index, input_file) in files.iter().enumerate() {
+        let file = &input_file.file;
+
+        for section in file
+            .sections
+            .iter()
+            .filter(|section| section.r#type == SectionType::RelocationWithAddends)
+        {
+            // `information` holds the index of the section to which the
+            // relocations apply. Relocations for sections that are not part of
+            // the output are ignored.
+            let Some(placement) = layout.placement(file_index, section.information as usize) else {
+                continue;
+            };
+
+            let relocations = section
+                .data
+                .relocations::<()>()
+                .ok_or_else(|| Error::ParsingRelocation(input_file.path().to_path_buf()))?;
+
+            for relocation in relocations {
+                let relocation = relocation
+                    .map_err(|_| Error::ParsingRelocation(input_file.path().to_path_buf()))?;
+
+                let reference =
+                    SymbolReference { file: file_index, symbol: relocation.symbol_index as usize };
+
+                let Some(symbol) = symbols.get(reference) else {
+                    return Err(Error::ParsingRelocation(input_file.path().to_path_buf()));
+                };
+
+                let resolution = match plt_entry_address(layout, plt, &symbol) {
+                    Some(address) => Resolution::Plt(address),
+                    None => symbols.resolve_reference(reference)?,
+                };
+
+                let (symbol_value, addend) = match resolution {
+                    Resolution::Defined(definition) => {
+                        match merged_strings_section(symbols, layout, definition) {
+                            // A relocation against the section symbol of a
+                            // merged string section targets a string, which
+                            // has moved: the non-negative part of the addend
+                            // is resolved to the new address of the string.
+                            Some(section_index) => {
+                                let offset = relocation.addend.max(0);
+
+                                (
+                                    layout
+                                        .address(definition.file, section_index, offset as u64)
+                                        .ok_or_else(|| {
+                                        Error::ParsingRelocation(input_file.path().to_path_buf())
+                                    })?,
+                                    relocation.addend - offset,
+                                )
+                            }
+                            None => (
+                                symbols.address(definition, layout).ok_or_else(|| {
+                                    Error::UndefinedSymbol(symbol_name(symbols, definition))
+                                })?,
+                                relocation.addend,
+                            ),
+                        }
+                    }
+                    Resolution::UndefinedWeak => (0, relocation.addend),
+                    Resolution::Plt(address) => (address, relocation.addend),
+                };
+
+                let output_section = &mut layout.sections[placement.section];
+                let offset = placement.offset + relocation.offset.0;
+                let place = output_section.virtual_address + offset;
+
+                let field = usize::try_from(offset)
+                    .ok()
+                    .and_then(|offset| output_section.data.get_mut(offset..))
+                    .ok_or_else(|| Error::ParsingRelocation(input_file.path().to_path_buf()))?;
+
+                let relocated =
+                    RelocatedValue { symbol: symbol_value, addend, place, thread_pointer };
+
+                let applied = match file.endianness {
+                    Endianness::Big => {
+                        apply_one::<BigEndian>(file.machine, relocation.r#type, relocated, field)
+                    }
+                    Endianness::Little => {
+                        apply_one::<LittleEndian>(file.machine, relocation.r#type, relocated, field)
+                    }
+                };
+
+                applied.map_err(|failure| {
+                    let name = relocation_type_name(file.machine, relocation.r#type).into_owned();
+
+                    match failure {
+                        Failure::Unsupported => {
+                            Error::UnsupportedRelocation(name, input_file.path().to_path_buf())
+                        }
+                        Failure::Overflow => {
+                            Error::RelocationOverflow(name, symbol_name(symbols, reference))
+                        }
+                    }
+                })?;
+
+                // Rewrite the relocation for the output file if needed.
+                if let Some(symbol_table) = symbol_table {
+                    let (symbol_index, addend) =
+                        output_symbol(symbols, layout, symbol_table, reference, relocation.addend);
+
+                    push_output_relocation(
+                        &mut output_relocations,
+                        placement.section,
+                        Relocation {
+                            offset: Address(place),
+                            symbol_index,
+                            r#type: relocation.r#type,
+                            addend,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    output_relocations.sort_by_key(|output| output.section);
+
+    Ok(output_relocations)
+}
+
+/// Rewrite all the relocations of all input files for a relocatable output,
+/// without applying them, see [`Configuration::relocatable`][crate::Configuration::relocatable].
+///
+/// The output sections have no virtual address, so the offsets of the
+/// rewritten relocations are relative to their output section.
+pub(super) fn rewrite<N>(
+    files: &[InputFile],
+    symbols: &Symbols<N>,
+    layout: &Layout,
+    symbol_table: &SymbolTable,
+) -> Result<Vec<OutputRelocations>, Error>
+where
+    N: Number,
+{
+    let mut output_relocations = Vec::<OutputRelocations>::new();
+
+    for (file_index, input_file) in files.iter().enumerate() {
+        for section in input_file
+            .file
+            .sections
+            .iter()
+            .filter(|section| section.r#type == SectionType::RelocationWithAddends)
+        {
+            let Some(placement) = layout.placement(file_index, section.information as usize) else {
+                continue;
+            };
+
+            let relocations = section
+                .data
+                .relocations::<()>()
+                .ok_or_else(|| Error::ParsingRelocation(input_file.path().to_path_buf()))?;
+
+            for relocation in relocations {
+                let relocation = relocation
+                    .map_err(|_| Error::ParsingRelocation(input_file.path().to_path_buf()))?;
+
+                let reference =
+                    SymbolReference { file: file_index, symbol: relocation.symbol_index as usize };
+
+                if symbols.get(reference).is_none() {
+                    return Err(Error::ParsingRelocation(input_file.path().to_path_buf()));
+                }
+
+                let (symbol_index, addend) =
+                    output_symbol(symbols, layout, symbol_table, reference, relocation.addend);
+
+                push_output_relocation(
+                    &mut output_relocations,
+                    placement.section,
+                    Relocation {
+                        offset: Address(placement.offset + relocation.offset.0),
+                        symbol_index,
+                        r#type: relocation.r#type,
+                        addend,
+                    },
+                );
+            }
+        }
+    }
+
+    output_relocations.sort_by_key(|output| output.section);
+
+    Ok(output_relocations)
+}
+
+/// Push a relocation into the relocations of the output section `section`.
+fn push_output_relocation(
+    output_relocations: &mut Vec<OutputRelocations>,
+    section: usize,
+    relocation: Relocation,
+) {
+    match output_relocations.iter_mut().find(|output| output.section == section) {
+        Some(output) => output.relocations.push(relocation),
+        None => {
+            output_relocations.push(OutputRelocations { section, relocations: vec![relocation] })
+        }
+    }
+}
+
+/// The values needed to compute a relocation.
+#[derive(Debug, Clone, Copy)]
+struct RelocatedValue {
+    /// The value of the symbol, `S`.
+    symbol: u64,
+    /// The addend, `A`.
+    addend: i64,
+    /// The place, i.e. the address of the relocated field, `P`.
+    place: u64,
+    /// The thread pointer, `TP`, see [`Layout::thread_pointer`].
+    thread_pointer: u64,
+}
+
+/// Why a relocation cannot be applied.
+#[derive(Debug)]
+enum Failure {
+    /// The relocation type is not supported.
+    Unsupported,
+    /// The value doesn't fit in the field.
+    Overflow,
+}
+
+/// Apply one relocation onto `field`.
+fn apply_one<N>(
+    machine: Machine,
+    r#type: u32,
+    value: RelocatedValue,
+    field: &mut [u8],
+) -> Result<(), Failure>
+where
+    N: Number,
+{
+    match machine {
+        Machine::X86_64 => apply_x86_64::<N>(r#type, value, field),
+        _ => Err(Failure::Unsupported),
+    }
+}
+
+fn apply_x86_64<N>(r#type: u32, value: RelocatedValue, field: &mut [u8]) -> Result<(), Failure>
+where
+    N: Number,
+{
+    let RelocatedValue { symbol, addend, place, thread_pointer } = value;
+    let absolute = symbol.wrapping_add_signed(addend);
+    let relative = absolute.wrapping_sub(place);
+    let thread_pointer_relative = absolute.wrapping_sub(thread_pointer);
+
+    match r#type {
+        // `R_X86_64_NONE`.
+        0 => Ok(()),
+
+        // `R_X86_64_64`: `S + A`.
+        1 => write(field, &N::write_u64(absolute)),
+
+        // `R_X86_64_PC32` and `R_X86_64_PLT32`: `S + A - P`.
+        //
+        // There is no PLT in a static output, so `R_X86_64_PLT32` is computed
+        // like `R_X86_64_PC32`.
+        2 | 4 => {
+            let relative = i32::try_from(relative as i64).map_err(|_| Failure::Overflow)?;
+
+            write(field, &N::write_u32(relative as u32))
+        }
+
+        // `R_X86_64_32`: `S + A`, zero-extended.
+        10 => {
+            let absolute = u32::try_from(absolute).map_err(|_| Failure::Overflow)?;
+
+            write(field, &N::write_u32(absolute))
+        }
+
+        // `R_X86_64_32S`: `S + A`, sign-extended.
+        11 => {
+            let absolute = i32::try_from(absolute as i64).map_err(|_| Failure::Overflow)?;
+
+            write(field, &N::write_u32(absolute as u32))
+        }
+
+        // `R_X86_64_TPOFF64`: `S + A - TP`.
+        18 => write(field, &N::write_u64(thread_pointer_relative)),
+
+        // `R_X86_64_TPOFF32`: `S + A - TP`, sign-extended.
+        23 => {
+            let thread_pointer_relative =
+                i32::try_from(thread_pointer_relative as i64).map_err(|_| Failure::Overflow)?;
+
+            write(field, &N::write_u32(thread_pointer_relative as u32))
+        }
+
+        // `R_X86_64_PC64`: `S + A - P`.
+        24 => write(field, &N::write_u64(relative)),
+
+        _ => Err(Failure::Unsupported),
+    }
+}
+
+/// Write `bytes` at the beginning of `field`.
+fn write(field: &mut [u8], bytes: &[u8]) -> Result<(), Failure> {
+    field.get_mut(..bytes.len()).ok_or(Failure::Overflow)?.copy_from_slice(bytes);
+
+    Ok(())
+}
+
+/// Find the symbol to use in the output symbol table for a relocation, along
+/// with the new addend.
+fn output_symbol<N>(
+    symbols: &Symbols<N>,
+    layout: &Layout,
+    symbol_table: &SymbolTable,
+    reference: SymbolReference,
+    addend: i64,
+) -> (u32, i64)
+where
+    N: Number,
+{
+    let symbol = symbols.get(reference).expect("The symbol reference must be valid");
+
+    // Relocations against a section symbol are rewritten against the section
+    // symbol of the output section, and the addend is shifted by the offset of
+    // the input section inside the output section.
+    if let SectionIndex::Ok(section_index) = symbol.section_index_where_symbol_is_defined {
+        if symbol.r#type == SymbolType::Section {
+            if layout.is_merged_strings(reference.file, section_index) {
+                let offset = addend.max(0);
+
+                if let Some((section, output_offset)) =
+                    layout.output_offset(reference.file, section_index, offset as u64)
+                {
+                    return (
+                        symbol_table.section_symbols[section],
+                        output_offset as i64 + addend - offset,
+                    );
+                }
+            } else if let Some(placement) = layout.placement(reference.file, section_index) {
+                return (
+                    symbol_table.section_symbols[placement.section],
+                    addend + placement.offset as i64,
+                );
+            }
+        }
+    }
+
+    if let Some(index) = symbol_table.local_symbols.get(&reference) {
+        return (*index, addend);
+    }
+
+    let name = symbol.name.as_deref();
+
+    if let Some(global_index) = name.and_then(|name| symbols.global_index(name)) {
+        if let Some(index) = symbol_table.global_symbols[global_index] {
+            return (index, addend);
+        }
+
+        // The global symbol is dropped from the output symbol table, see
+        // `Configuration::retain_symbols_file`: the relocation is rewritten
+        // against the section symbol of the output section defining it.
+        let (_, definition) = symbols.globals[global_index];
+
+        return match symbols.output_location(definition, layout) {
+            Some((SectionIndex::Ok(section_index), _)) => {
+                // The output section indices are shifted by one because of
+                // the null section. The value of a thread-local symbol is not
+                // its address: use the latter.
+                let section = section_index - 1;
+                let value = symbols.address(definition, layout).unwrap_or_default();
+
+                (
+                    symbol_table.section_symbols[section],
+                    addend + value.wrapping_sub(layout.sections[section].virtual_address) as i64,
+                )
+            }
+            Some((_, value)) => (0, addend.wrapping_add(value as i64)),
+            None => (0, addend),
+        };
+    }
+
+    let index = name.and_then(|name| symbol_table.undefined_symbols.get(name).copied());
+
+    (index.unwrap_or(0), addend)
+}
+
+/// Get the address of the PLT stub of an undefined symbol, if any.
+fn plt_entry_address(layout: &Layout, plt: &Plt, symbol: &Symbol) -> Option<u64> {
+    if symbol.is_defined() {
+        return None;
+    }
+
+    let entry = plt.entry(symbol.name.as_deref()?)?;
+
+    Some(Plt::entry_address(layout.sections[layout.plt?].virtual_address, entry))
+}
+
+/// Get the index of the input section defining a section symbol, if it is a
+/// merged string section.
+fn merged_strings_section<N>(
+    symbols: &Symbols<N>,
+    layout: &Layout,
+    reference: SymbolReference,
+) -> Option<usize>
+where
+    N: Number,
+{
+    let symbol = symbols.get(reference)?;
+
+    match symbol.section_index_where_symbol_is_defined {
+        SectionIndex::Ok(section_index)
+            if symbol.r#type == SymbolType::Section
+                && layout.is_merged_strings(reference.file, section_index) =>
+        {
+            Some(section_index)
+        }
+        _ => None,
+    }
+}
+
+/// Get the name of a symbol, for diagnostics.
+fn symbol_name<N>(symbols: &Symbols<N>, reference: SymbolReference) -> String
+where
+    N: Number,
+{
+    symbols
+        .get(reference)
+        .and_then(|symbol| symbol.name)
+        .map(|name| name.to_str_lossy().into_owned())
+        .unwrap_or_default()
+}
@@ -0,0 +1,796 @@
+use std::{
+    borrow::Cow,
+    collections::{hash_map::Entry, HashMap, HashSet},
+    fs,
+    marker::PhantomData,
+    path::Path,
+};
+
+use bstr::{BStr, BString, ByteSlice};
+use weld_object::{
+    arena::Arena,
+    elf64::{Address, SectionIndex, SectionType, SymbolBinding, SymbolType},
+    prelude::*,
+};
+
+use super::{layout::Layout, merge, Error, InputFile};
+use crate::{Configuration, SymbolValue, Warning, Warnings};
+
+/// A reference to a symbol of an input file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct SymbolReference {
+    /// Index of the input file.
+    pub(super) file: usize,
+    /// Index of the symbol in the symbol table of the input file.
+    pub(super) symbol: usize,
+}
+
+/// The names shared by all the symbol tables, interned once in the arena
+/// owned by the link.
+#[derive(Debug)]
+pub(super) struct Names<'a> {
+    /// The arena the names of the symbols are copied into, when they can't
+    /// borrow the input files.
+    arena: &'a Arena,
+    /// The wrapped symbols and their wrapper, `__wrap_<name>`, see
+    /// [`Configuration::wrap`].
+    wraps: Vec<(&'a BStr, &'a BStr)>,
+}
+
+impl<'a> Names<'a> {
+    /// Intern the names of the wrappers of the wrapped symbols in `arena`.
+    pub(super) fn new(configuration: &'a Configuration, arena: &'a Arena) -> Self {
+        let wraps = configuration
+            .wrap
+            .iter()
+            .map(|name| {
+                (name.as_bytes().as_bstr(), arena.alloc_concat(&[b"__wrap_", name.as_bytes()]))
+            })
+            .collect();
+
+        Self { arena, wraps }
+    }
+
+    /// Get the name an undefined reference resolves to: `__wrap_<name>` if
+    /// `name` is wrapped, `<name>` if the reference is `__real_<name>` and
+    /// `name` is wrapped, otherwise `name` itself.
+    fn wrapped_name(&self, name: Cow<'a, BStr>) -> Cow<'a, BStr> {
+        let wrapped = |name: &[u8]| {
+            self.wraps.iter().find(|(wrapped, _)| wrapped.as_bytes() == name).copied()
+        };
+
+        if let Some((_, wrapper)) = wrapped(&name) {
+            return Cow::Borrowed(wrapper);
+        }
+
+        match name.strip_prefix(b"__real_").and_then(wrapped) {
+            Some((wrapped, _)) => Cow::Borrowed(wrapped),
+            None => name,
+        }
+    }
+}
+
+/// The result of resolving a symbol reference.
+#[derive(Debug)]
+pub(super) enum Resolution {
+    /// The symbol is defined by this symbol.
+    Defined(SymbolReference),
+    /// The symbol is weak and undefined; it resolves to 0.
+    UndefinedWeak,
+    /// The symbol is defined by a shared object, and is reached through the
+    /// PLT stub at this address.
+    Plt(u64),
+}
+
+/// The symbol table of an input file.
+///
+/// Symbols are not collected: they are read from the input file when needed.
+/// They have all been read successfully once, by [`Symbols::resolve`]. All
+/// input files have the same endianness, `N`, so reading a symbol doesn't
+/// branch on it.
+///
+/// The symbols defined by the configuration, see [`Configuration::defsym`],
+/// are gathered in a last symbol table, which has no input file and owns its
+/// symbols.
+#[derive(Debug)]
+pub(super) struct InputSymbols<'a, N>
+where
+    N: Number,
+{
+    /// The symbol table section, if any.
+    symbol_table: Option<&'a Section<'a>>,
+    /// The string table section associated to the symbol table, if any.
+    strings_section: Option<&'a Section<'a>>,
+    /// The number of symbols.
+    len: usize,
+    /// The names shared by all the symbol tables, see [`Names`].
+    names: &'a Names<'a>,
+    /// Whether each input section is discarded, see
+    /// [`InputFile::discarded_sections`].
+    discarded_sections: &'a [bool],
+    /// The names and the values of the absolute symbols defined by the
+    /// configuration, if this symbol table has no input file.
+    defined: Vec<(Cow<'a, BStr>, u64)>,
+    /// The endianness of the input files, `N`.
+    _endianness: PhantomData<N>,
+}
+
+impl<'a, N> InputSymbols<'a, N>
+where
+    N: Number,
+{
+    /// Read a symbol by its index.
+    pub(super) fn get(&self, index: usize) -> Option<Symbol<'a>> {
+        if index >= self.len {
+            return None;
+        }
+
+        let Some(symbol_table) = self.symbol_table else {
+            return self.defined.get(index).map(|(name, value)| Symbol {
+                name: Some(name.clone()),
+                name_offset: Address(0),
+                r#type: SymbolType::NoType,
+                binding: SymbolBinding::Global,
+                section_index_where_symbol_is_defined: SectionIndex::Absolute,
+                value: Address(*value),
+                size: 0,
+            });
+        };
+
+        let input = &symbol_table.data[index * Symbol::SIZE..];
+        let (_, mut symbol) = Symbol::read::<N, ()>(input).ok()?;
+
+        if let Some(strings_section) = self.strings_section {
+            symbol.name = strings_section
+                .data
+                .input_name_at_offset_in(symbol.name_offset.into(), self.names.arena)
+                .map(Cow::Borrowed);
+        }
+
+        // A global symbol defined in a discarded section is defined by the
+        // kept copy of its COMDAT group: it becomes an undefined reference.
+        if !symbol.is_local() {
+            if let SectionIndex::Ok(section_index) = symbol.section_index_where_symbol_is_defined {
+                if self.discarded_sections.get(section_index).copied().unwrap_or(false) {
+                    symbol.section_index_where_symbol_is_defined = SectionIndex::Undefined;
+                }
+            }
+        }
+
+        // Only the undefined references are redirected: the definitions keep
+        // their names.
+        if !symbol.is_local() && symbol.is_undefined() {
+            symbol.name = symbol.name.map(|name| self.names.wrapped_name(name));
+        }
+
+        Some(symbol)
+    }
+
+    /// Iterate over all the symbols.
+    pub(super) fn iter(&self) -> impl Iterator<Item = Symbol<'a>> + '_ {
+        (0..self.len).filter_map(|index| self.get(index))
+    }
+}
+
+/// Symbols of all input files, along with the resolution of global symbols.
+#[derive(Debug)]
+pub(super) struct Symbols<'a, N>
+where
+    N: Number,
+{
+    /// Symbols of all input files, indexed by input file index.
+    pub(super) per_file: Vec<InputSymbols<'a, N>>,
+    /// Definitions of global symbols, in order of appearance.
+    pub(super) globals: Vec<(Cow<'a, BStr>, SymbolReference)>,
+    /// Map a global symbol name to its index in `globals`.
+    globals_index: HashMap<Cow<'a, BStr>, usize>,
+}
+
+impl<'a, N> Symbols<'a, N>
+where
+    N: Number,
+{
+    /// Read the symbols of all input files, and resolve the global symbols.
+    ///
+    /// Symbols are read one at a time: they are not collected. The undefined
+    /// references to the wrapped symbols are redirected, see
+    /// [`Configuration::wrap`]. The symbols defined by the configuration are
+    /// resolved last, see [`Configuration::defsym`]. The names are borrowed,
+    /// either from the input files, the configuration, or `names`.
+    pub(super) fn resolve(
+        files: &'a [InputFile<'a>],
+        configuration: &'a Configuration,
+        names: &'a Names<'a>,
+    ) -> Result<Self, Error> {
+        let mut per_file = Vec::<InputSymbols<'a, N>>::with_capacity(files.len());
+        let mut globals = Vec::<(Cow<'a, BStr>, SymbolReference)>::new();
+        let mut globals_index = HashMap::<Cow<'a, BStr>, usize>::new();
+
+        for (file_index, input_file) in files.iter().enumerate() {
+            let file = &input_file.file;
+            let symbol_table =
+                file.sections.iter().find(|section| section.r#type == SectionType::SymbolTable);
+            let strings_section =
+                symbol_table.and_then(|symbol_table| file.strings_section_of(symbol_table));
+
+            let mut input_symbols = InputSymbols {
+                symbol_table,
+                strings_section,
+                len: 0,
+                names,
+                discarded_sections: &input_file.discarded_sections,
+                defined: Vec::new(),
+                _endianness: PhantomData,
+            };
+
+            let symbols = match symbol_table {
+                Some(symbol_table) => Some(
+                    symbol_table
+                        .data
+                        .symbols::<()>(strings_section)
+                        .ok_or_else(|| Error::ParsingSymbol(input_file.path().to_path_buf()))?,
+                ),
+                None => None,
+            };
+
+            for (symbol_index, symbol) in symbols.into_iter().flatten().enumerate() {
+                let symbol =
+                    symbol.map_err(|_| Error::ParsingSymbol(input_file.path().to_path_buf()))?;
+                input_symbols.len += 1;
+
+                check_name(input_file, strings_section, symbol_index, &symbol)?;
+
+                if !(symbol.is_global() || symbol.is_weak())
+                    || symbol.is_undefined()
+                    || matches!(
+                        symbol.section_index_where_symbol_is_defined,
+                        SectionIndex::Ok(section_index) if input_file.is_discarded(section_index)
+                    )
+                {
+                    continue;
+                }
+
+                let Some(name) = symbol.name else {
+                    continue;
+                };
+
+                let reference = SymbolReference { file: file_index, symbol: symbol_index };
+
+                match globals_index.entry(name.clone()) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(globals.len());
+                        globals.push((name, reference));
+                    }
+
+                    Entry::Occupied(entry) => {
+                        let (_, defined) = &mut globals[*entry.get()];
+                        let defined_binding = if defined.file == file_index {
+                            input_symbols.get(defined.symbol)
+                        } else {
+                            per_file[defined.file].get(defined.symbol)
+                        }
+                        .map(|defined_symbol| defined_symbol.binding);
+
+                        match (defined_binding, symbol.binding) {
+                            // A global symbol overrides a weak symbol.
+                            (Some(SymbolBinding::Weak), SymbolBinding::Global) => {
+                                *defined = reference
+                            }
+
+                            // Two global symbols conflict.
+                            (Some(SymbolBinding::Global), SymbolBinding::Global) => {
+                                return Err(Error::DuplicateSymbol(
+                                    name.to_str_lossy().into_owned(),
+                                    files[defined.file].path().to_path_buf(),
+                                    files[file_index].path().to_path_buf(),
+                                ));
+                            }
+
+                            // The first definition is kept.
+                            _ => {}
+                        }
+                    }
+                }
+            }
+
+            per_file.push(input_symbols);
+        }
+
+        // The symbols defined by the configuration have their own symbol
+        // table, after the ones of the input files. An alias shares the
+        // definition of its symbol.
+        let defined_file = files.len();
+        let mut defined_symbols = InputSymbols {
+            symbol_table: None,
+            strings_section: None,
+            len: 0,
+            names,
+            discarded_sections: &[],
+            defined: Vec::new(),
+            _endianness: PhantomData,
+        };
+
+        for (name, value) in &configuration.defined_symbols {
+            let reference = match value {
+                SymbolValue::Absolute(value) => {
+                    defined_symbols
+                        .defined
+                        .push((Cow::Borrowed(name.as_bytes().as_bstr()), *value));
+                    defined_symbols.len += 1;
+
+                    SymbolReference { file: defined_file, symbol: defined_symbols.len - 1 }
+                }
+
+                SymbolValue::Symbol(other) => globals_index
+                    .get(other.as_bytes().as_bstr())
+                    .map(|index| globals[*index].1)
+                    .ok_or_else(|| Error::UndefinedSymbol(other.clone()))?,
+            };
+
+            let name = Cow::<BStr>::Owned(name.as_str().into());
+
+            match globals_index.entry(name.clone()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(globals.len());
+                    globals.push((name, reference));
+                }
+
+                Entry::Occupied(entry) => {
+                    let (_, defined) = &mut globals[*entry.get()];
+
+                    // The last definition by the configuration wins, and it
+                    // overrides a weak symbol.
+                    if defined.file == defined_file
+                        || per_file[defined.file].get(defined.symbol).map(|symbol| symbol.binding)
+                            == Some(SymbolBinding::Weak)
+                    {
+                        *defined = reference;
+                    } else {
+                        return Err(Error::DefinedSymbolConflict(
+                            name.to_str_lossy().into_owned(),
+                            files[defined.file].path().to_path_buf(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        per_file.push(defined_symbols);
+
+        Ok(Self { per_file, globals, globals_index })
+    }
+
+    /// Get a symbol of an input file.
+    pub(super) fn get(&self, reference: SymbolReference) -> Option<Symbol<'a>> {
+        self.per_file.get(reference.file)?.get(reference.symbol)
+    }
+
+    /// Find the definition of a global symbol by its name.
+    pub(super) fn global(&self, name: &BStr) -> Option<SymbolReference> {
+        self.global_index(name).map(|index| self.globals[index].1)
+    }
+
+    /// Find the index of a global symbol in [`Self::globals`] by its name.
+    pub(super) fn global_index(&self, name: &BStr) -> Option<usize> {
+        self.globals_index.get(name).copied()
+    }
+
+    /// Resolve a symbol reference to its definition.
+    ///
+    /// Local symbols resolve to themselves. Global and weak symbols resolve to
+    /// their global definition. The reference must be valid, see
+    /// [`Self::get`].
+    pub(super) fn resolve_reference(
+        &self,
+        reference: SymbolReference,
+    ) -> Result<Resolution, Error> {
+        let symbol = self.get(reference).expect("The symbol reference must be valid");
+
+        if symbol.is_local() || symbol.r#type == SymbolType::Section {
+            return Ok(Resolution::Defined(reference));
+        }
+
+        let name = symbol.name.as_deref().unwrap_or_default();
+
+        match self.global(name) {
+            Some(definition) => Ok(Resolution::Defined(definition)),
+            None if symbol.is_weak() => Ok(Resolution::UndefinedWeak),
+            None => Err(Error::UndefinedSymbol(name.to_str_lossy().into_owned())),
+        }
+    }
+
+    /// Report the input files defining or referencing the traced global
+    /// symbols, and the definition each one resolves to, see
+    /// [`Configuration::trace_symbol`].
+    pub(super) fn trace(&self, files: &[InputFile], names: &[String], warnings: &mut Warnings) {
+        for name in names {
+            let name = name.as_bytes().as_bstr();
+
+            for (input_file, input_symbols) in files.iter().zip(&self.per_file) {
+                let path = input_file.path().to_path_buf();
+
+                for symbol in input_symbols.iter() {
+                    if symbol.is_local() || symbol.name.as_deref() != Some(name) {
+                        continue;
+                    }
+
+                    let name = name.to_str_lossy().into_owned();
+
+                    warnings.push(if symbol.is_undefined() {
+                        Warning::TracedSymbolReference(name, path.clone())
+                    } else {
+                        Warning::TracedSymbolDefinition(name, path.clone())
+                    });
+                }
+            }
+
+            // The symbols defined by the configuration have no input file.
+            if let Some(input_file) =
+                self.global(name).and_then(|reference| files.get(reference.file))
+            {
+                warnings.push(Warning::TracedSymbolResolution(
+                    name.to_str_lossy().into_owned(),
+                    input_file.path().to_path_buf(),
+                ));
+            }
+        }
+    }
+
+    /// Check that every non-weak global symbol used by an input file is
+    /// defined.
+    pub(super) fn check_undefined(&self) -> Result<(), Error> {
+        for symbol in self.per_file.iter().flat_map(InputSymbols::iter) {
+            if !symbol.is_global() || symbol.is_defined() {
+                continue;
+            }
+
+            let Some(name) = symbol.name.as_deref() else {
+                continue;
+            };
+
+            if self.global(name).is_none() {
+                return Err(Error::UndefinedSymbol(name.to_str_lossy().into_owned()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Compute the final address of a defined symbol.
+    ///
+    /// The value of an absolute symbol is kept as is: it doesn't depend on
+    /// the layout.
+    pub(super) fn address(&self, reference: SymbolReference, layout: &Layout) -> Option<u64> {
+        let symbol = self.get(reference)?;
+
+        match symbol.section_index_where_symbol_is_defined {
+            SectionIndex::Ok(section_index) => {
+                layout.address(reference.file, section_index, symbol.value.0)
+            }
+            SectionIndex::Absolute => Some(symbol.value.0),
+            _ => None,
+        }
+    }
+
+    /// Build the symbol table of the output file.
+    ///
+    /// If `retained` is given, the global symbols that are not listed are
+    /// dropped, see [`Configuration::retain_symbols_file`]. If `relocatable`
+    /// is true, the undefined global symbols are kept, to be resolved when
+    /// the output file is linked again, see [`Configuration::relocatable`].
+    pub(super) fn output_table(
+        &self,
+        layout: &Layout,
+        retained: Option<&HashSet<BString>>,
+        relocatable: bool,
+    ) -> SymbolTable<'a> {
+        let mut table = SymbolTable {
+            symbols: vec![Symbol {
+                name: None,
+                name_offset: Address(0),
+                r#type: SymbolType::NoType,
+                binding: SymbolBinding::Local,
+                section_index_where_symbol_is_defined: SectionIndex::Undefined,
+                value: Address(0),
+                size: 0,
+            }],
+            strings: vec![0],
+            first_global: 0,
+            section_symbols: Vec::with_capacity(layout.sections.len()),
+            local_symbols: HashMap::new(),
+            global_symbols: Vec::with_capacity(self.globals.len()),
+            undefined_symbols: HashMap::new(),
+        };
+
+        // One section symbol per output section.
+        for (section_index, section) in layout.sections.iter().enumerate() {
+            table.section_symbols.push(table.symbols.len() as u32);
+            table.symbols.push(Symbol {
+                name: None,
+                name_offset: Address(0),
+                r#type: SymbolType::Section,
+                binding: SymbolBinding::Local,
+                section_index_where_symbol_is_defined: SectionIndex::Ok(section_index + 1),
+                value: Address(section.virtual_address),
+                size: 0,
+            });
+        }
+
+        // Local symbols.
+        for (file_index, symbols) in self.per_file.iter().enumerate() {
+            for (symbol_index, symbol) in symbols.iter().enumerate() {
+                if !symbol.is_local()
+                    || matches!(symbol.r#type, SymbolType::Section)
+                    || symbol.name.is_none()
+                    || symbol_index == 0
+                {
+                    continue;
+                }
+
+                let reference = SymbolReference { file: file_index, symbol: symbol_index };
+                let Some((section_index, value)) = self.output_location(reference, layout) else {
+                    continue;
+                };
+
+                table.local_symbols.insert(reference, table.symbols.len() as u32);
+                table.push(&symbol, section_index, value);
+            }
+        }
+
+        table.first_global = table.symbols.len() as u32;
+
+        // Global symbols.
+        for (name, reference) in &self.globals {
+            // An alias, see [`Configuration::defsym`], has the name of the
+            // global symbol, not the one of its definition.
+            let symbol = Symbol {
+                name: Some(name.clone()),
+                ..self.get(*reference).expect("The symbol reference must be valid")
+            };
+            if !is_retained(retained, name) {
+                table.global_symbols.push(None);
+
+                continue;
+            }
+
+            let (section_index, value) =
+                self.output_location(*reference, layout).unwrap_or((SectionIndex::Undefined, 0));
+
+            table.global_symbols.push(Some(table.symbols.len() as u32));
+            table.push(&symbol, section_index, value);
+        }
+
+        // Undefined weak symbols, which have no definition, but can still be
+        // the target of relocations, and all undefined symbols of a
+        // relocatable output.
+        for symbol in self.per_file.iter().flat_map(InputSymbols::iter) {
+            if !(symbol.is_weak() || (relocatable && symbol.is_global())) || symbol.is_defined() {
+                continue;
+            }
+
+            let Some(name) = &symbol.name else {
+                continue;
+            };
+
+            if self.globals_index.contains_key(name)
+                || table.undefined_symbols.contains_key(name)
+                || !is_retained(retained, name)
+            {
+                continue;
+            }
+
+            table.undefined_symbols.insert(name.clone(), table.symbols.len() as u32);
+            table.push(&symbol, SectionIndex::Undefined, 0);
+        }
+
+        table.merge_strings();
+
+        table
+    }
+
+    /// Check that the value of each defined symbol of the output symbol table
+    /// is consistent with the layout: the input section defining the symbol
+    /// is placed at an address aligned as it requires, and the value of the
+    /// symbol is this address plus the offset of the symbol in the section.
+    ///
+    /// A mismatch is a layout bug: the name of the first inconsistent symbol
+    /// is returned.
+    pub(super) fn check_addresses(
+        &self,
+        files: &[InputFile],
+        layout: &Layout,
+        table: &SymbolTable,
+    ) -> Result<(), String> {
+        let locals = table.local_symbols.iter().map(|(reference, index)| (*reference, *index));
+        let globals = self
+            .globals
+            .iter()
+            .zip(&table.global_symbols)
+            .filter_map(|((_, reference), index)| Some((*reference, (*index)?)));
+
+        for (reference, index) in locals.chain(globals) {
+            let Some(symbol) = self.get(reference) else {
+                continue;
+            };
+
+            // Absolute symbols don't depend on the layout, and the strings of
+            // merged string sections have moved individually.
+            let SectionIndex::Ok(section_index) = symbol.section_index_where_symbol_is_defined
+            else {
+                continue;
+            };
+
+            if layout.is_merged_strings(reference.file, section_index) {
+                continue;
+            }
+
+            let (Some(placement), Some(section)) = (
+                layout.placement(reference.file, section_index),
+                files.get(reference.file).and_then(|file| file.file.sections.get(section_index)),
+            ) else {
+                continue;
+            };
+
+            let section_address =
+                layout.sections[placement.section].virtual_address + placement.offset;
+            let base = if symbol.r#type == SymbolType::ThreadLocalStorage {
+                layout.tls_address()
+            } else {
+                0
+            };
+
+            if !section.alignment.is_aligned(section_address)
+                || table.symbols[index as usize].value.0 != section_address + symbol.value.0 - base
+            {
+                return Err(symbol.name.as_deref().unwrap_or_default().to_str_lossy().into_owned());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the output section index and the final value of a symbol.
+    ///
+    /// The value of a thread-local symbol is its offset in the thread-local
+    /// storage template, not its address.
+    pub(super) fn output_location(
+        &self,
+        reference: SymbolReference,
+        layout: &Layout,
+    ) -> Option<(SectionIndex, u64)> {
+        let symbol = self.get(reference)?;
+
+        match symbol.section_index_where_symbol_is_defined {
+            SectionIndex::Ok(section_index) => {
+                let placement = layout.placement(reference.file, section_index)?;
+
+                let mut value = self.address(reference, layout)?;
+
+                if symbol.r#type == SymbolType::ThreadLocalStorage {
+                    value -= layout.tls_address();
+                }
+
+                Some((
+                    // The output section indices are shifted by one because of the
+                    // null section.
+                    SectionIndex::Ok(placement.section + 1),
+                    value,
+                ))
+            }
+            SectionIndex::Absolute => Some((SectionIndex::Absolute, symbol.value.0)),
+            _ => None,
+        }
+    }
+}
+
+/// Read the names of the global symbols to keep in the output symbol table,
+/// one per line, see [`Configuration::retain_symbols_file`]. Blank lines are
+/// ignored.
+pub(super) fn read_retained_symbols(path: &Path) -> Result<HashSet<BString>, Error> {
+    let bytes = fs::read(path).map_err(|error| Error::ReadingFile(path.to_path_buf(), error))?;
+
+    Ok(bytes
+        .lines()
+        .map(<[u8]>::trim_ascii)
+        .filter(|name| !name.is_empty())
+        .map(BString::from)
+        .collect())
+}
+
+/// Check whether the global symbol `name` is kept in the output symbol
+/// table.
+fn is_retained(retained: Option<&HashSet<BString>>, name: &BStr) -> bool {
+    retained.is_none_or(|retained| retained.contains(name))
+}
+
+/// Check that the name of a symbol is well-formed: its name offset must point
+/// to a null-terminated string of the string table, and a global symbol must
+/// have a non-empty name. Otherwise, the symbol would be resolved by a garbage
+/// name.
+fn check_name(
+    input_file: &InputFile,
+    strings_section: Option<&Section>,
+    symbol_index: usize,
+    symbol: &Symbol,
+) -> Result<(), Error> {
+    if strings_section.is_none() {
+        return Ok(());
+    }
+
+    let is_malformed = match symbol.name.as_deref() {
+        // The name is out of bounds.
+        None if symbol.name_offset.0 != 0 => true,
+
+        // A global or weak symbol must be named.
+        name => {
+            name.is_none_or(|name| name.is_empty())
+                && matches!(symbol.binding, SymbolBinding::Global | SymbolBinding::Weak)
+        }
+    };
+
+    if is_malformed {
+        return Err(Error::MalformedSymbolName(
+            input_file.path().to_path_buf(),
+            symbol_index,
+            symbol.name_offset.0,
+        ));
+    }
+
+    Ok(())
+}
+
+/// The symbol table of the output file.
+#[derive(Debug)]
+pub(super) struct SymbolTable<'a> {
+    /// All the symbols, locals first, as required by the ELF format: local
+    /// symbols precede the global and weak symbols.
+    pub(super) symbols: Vec<Symbol<'a>>,
+    /// The string table associated to the symbols.
+    pub(super) strings: Vec<u8>,
+    /// Index of the first non-local symbol.
+    pub(super) first_global: u32,
+    /// Index of the section symbol of each output section.
+    pub(super) section_symbols: Vec<u32>,
+    /// Index of each input local symbol.
+    pub(super) local_symbols: HashMap<SymbolReference, u32>,
+    /// Index of each global symbol, in the same order as [`Symbols::globals`],
+    /// if it is not dropped, see [`Configuration::retain_symbols_file`].
+    pub(super) global_symbols: Vec<Option<u32>>,
+    /// Index of each undefined symbol, by name: the weak ones, and the
+    /// global ones of a relocatable output.
+    pub(super) undefined_symbols: HashMap<Cow<'a, BStr>, u32>,
+}
+
+impl<'a> SymbolTable<'a> {
+    /// Merge the names of the symbols: identical names, e.g. local symbols
+    /// of several input files, are stored once, and the name offsets of the
+    /// symbols are rewritten accordingly.
+    fn merge_strings(&mut self) {
+        let (strings, offset) = merge::merge_string_table(&self.strings);
+
+        for symbol in &mut self.symbols {
+            symbol.name_offset = Address(offset(symbol.name_offset.0));
+        }
+
+        self.strings = strings;
+    }
+
+    fn push(&mut self, symbol: &Symbol<'a>, section_index: SectionIndex, value: u64) {
+        let name_offset = Address(self.strings.len() as u64);
+
+        if let Some(name) = &symbol.name {
+            self.strings.extend_from_slice(name);
+            self.strings.push(0);
+        }
+
+        self.symbols.push(Symbol {
+            name: symbol.name.clone(),
+            name_offset: if symbol.name.is_some() { name_offset } else { Address(0) },
+            r#type: symbol.r#type,
+            binding: symbol.binding,
+            section_index_where_symbol_is_defined: section_index,
+            value: Address(value),
+            size: symbol.size,
+        });
+    }
+}
@@ -5,11 +5,25 @@ mod configuration;
 #[cfg(feature = "elf64")]
 mod elf64;
 mod linker;
+mod warning;
 
 pub use configuration::*;
 #[cfg(feature = "elf64")]
 pub use elf64::Error as Elf64Error;
 pub use linker::*;
+pub use warning::*;
+
+/// The features `weld-linker` has been compiled with, including the ones of its
+/// file reader.
+pub fn features() -> Vec<&'static str> {
+    let mut features = vec![
+        #[cfg(feature = "elf64")]
+        "elf64",
+    ];
+    features.extend(weld_file::FEATURES);
+
+    features
+}
 
 /// This module contains all types to work with target tiple.
 pub mod target {
@@ -2,7 +2,7 @@ use weld_errors::{error, Result};
 
 use crate::{
     target::{self, Triple},
-    Configuration,
+    Configuration, Warnings,
 };
 
 /// The linker itself.
@@ -30,28 +30,108 @@ error! {
         #[help = "Maybe try another target with `weld --target <target>`?"]
         UnsupportedBinaryFormat(Triple),
 
+        #[code = E015]
+        #[message = "I understand the given target triple, but I unfortunately don't support its architecture."]
+        #[formatted_message("I understand the `{0}` target triple, but I unfortunately don't support its architecture, `{}`.", .0.architecture)]
+        #[help = "See the supported targets with `weld --print-targets`."]
+        UnsupportedArchitecture(Triple),
+
         #[cfg(feature = "elf64")]
         #[transparent]
         Elf64(#[from] crate::elf64::Error),
     }
 }
 
+/// Target triples that `weld` may support, filtered by [`Linker::supports`].
+const CANDIDATE_TARGETS: &[&str] = &[
+    "x86_64-unknown-linux-gnu",
+    "x86_64-unknown-linux-musl",
+    "x86_64-unknown-freebsd",
+    "x86_64-unknown-netbsd",
+    "x86_64-unknown-openbsd",
+    "aarch64-unknown-linux-gnu",
+    "aarch64-unknown-linux-musl",
+    "riscv64gc-unknown-linux-gnu",
+    "x86_64-apple-darwin",
+    "x86_64-pc-windows-msvc",
+];
+
 impl Linker {
     pub(crate) fn with_configuration(configuration: Configuration) -> Self {
         Self { configuration }
     }
 
-    /// Let's weld things!
-    pub fn link(self) -> Result<(), Error> {
-        if self.configuration.input_files.is_empty() {
-            return Err(Error::NoInputFile);
+    /// Check whether a target can be linked by `weld`.
+    pub fn supports(target: &Triple) -> bool {
+        match target.binary_format {
+            #[cfg(feature = "elf64")]
+            target::BinaryFormat::Elf => crate::elf64::machine(target.architecture).is_some(),
+
+            _ => false,
         }
+    }
 
-        Ok(match self.configuration.target.binary_format {
+    /// List the targets that can be linked by `weld`.
+    pub fn supported_targets() -> Vec<Triple> {
+        CANDIDATE_TARGETS
+            .iter()
+            .filter_map(|target| target.parse::<Triple>().ok())
+            .filter(Self::supports)
+            .collect()
+    }
+
+    /// Let's weld things!
+    ///
+    /// The output file is written, and the warnings collected during the link
+    /// are returned.
+    pub fn link(self) -> Result<Warnings, Error> {
+        self.check()?;
+
+        let warnings = match self.configuration.target.binary_format {
             #[cfg(feature = "elf64")]
             target::BinaryFormat::Elf => crate::elf64::link(self.configuration)?,
 
             _ => return Err(Error::UnsupportedBinaryFormat(self.configuration.target)),
-        })
+        };
+
+        Ok(warnings)
+    }
+
+    /// Let's weld things, in memory!
+    ///
+    /// Like [`Self::link`], except that the output file isn't written: its
+    /// bytes are returned along with the warnings collected during the link.
+    pub fn link_to_vec(mut self) -> Result<(Vec<u8>, Warnings), Error> {
+        self.check()?;
+
+        let output = match self.configuration.target.binary_format {
+            #[cfg(feature = "elf64")]
+            target::BinaryFormat::Elf => {
+                let (output, warnings) = crate::elf64::link_to_vec(&self.configuration)?;
+
+                (crate::elf64::post_process(&mut self.configuration, output)?, warnings)
+            }
+
+            _ => return Err(Error::UnsupportedBinaryFormat(self.configuration.target)),
+        };
+
+        Ok(output)
+    }
+
+    /// Check the configuration can be linked at all.
+    fn check(&self) -> Result<(), Error> {
+        if self.configuration.input_files.is_empty() {
+            return Err(Error::NoInputFile);
+        }
+
+        // A binary format that isn't supported at all is reported by the caller.
+        #[cfg(feature = "elf64")]
+        if self.configuration.target.binary_format == target::BinaryFormat::Elf
+            && !Self::supports(&self.configuration.target)
+        {
+            return Err(Error::UnsupportedArchitecture(self.configuration.target.clone()));
+        }
+
+        Ok(())
     }
 }
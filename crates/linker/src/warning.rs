@@ -0,0 +1,132 @@
+use std::{path::PathBuf, vec};
+
+use miette::{Diagnostic, Severity};
+use weld_errors::error;
+
+error! {
+    #[doc = "Linker warnings."]
+    #[doc = "\n"]
+    #[doc = "A warning reports a non-fatal condition: the linker proceeds anyway. A warning"]
+    #[doc = "of severity `Advice` is a report requested by the user, see [`Warning::is_report`]."]
+    pub enum Warning {
+        #[severity = Warning]
+        #[message = "An input file is not a relocatable object file."]
+        #[formatted_message("The input file `{}` is not a relocatable object file, I'm linking it anyway.", .0.display())]
+        #[help = "Only relocatable object files, e.g. `.o` files, are expected to be linked."]
+        NonRelocatableInputFile(PathBuf),
+
+        #[severity = Warning]
+        #[message = "The `_start` symbol is missing, another entry point is used."]
+        #[formatted_message("The `_start` symbol is missing, I'm using `{0}` as the entry point.")]
+        #[help = "Define a `_start` symbol to choose the entry point explicitly."]
+        EntryPointFallback(String),
+
+        #[severity = Warning]
+        #[message = "The name of the shared object is ignored, the output is not a shared object."]
+        #[formatted_message("The name of the shared object, `{0}`, is ignored because the output is not a shared object.")]
+        #[help = "Use `--shared` to produce a shared object, or remove `--soname`."]
+        IgnoredSoname(String),
+
+        #[severity = Warning]
+        #[message = "A loadable segment is both writable and executable."]
+        #[formatted_message("The loadable segment containing `{0}` is both writable and executable.")]
+        #[help = "Keep the writable data and the executable code in different sections, e.g. `.data` and `.text`."]
+        WritableExecutableSegment(String),
+
+        #[severity = Advice]
+        #[message = "An unused section has been removed."]
+        #[formatted_message("The unused section `{0}` of the input file `{}` has been removed.", .1.display())]
+        #[help = "The section is not reachable from the entry point, see `--gc-sections`."]
+        RemovedSection(String, PathBuf),
+
+        #[severity = Advice]
+        #[message = "A traced symbol is defined by an input file."]
+        #[formatted_message("The traced symbol `{0}` is defined by the input file `{}`.", .1.display())]
+        #[help = "The symbol is traced, see `--trace-symbol`."]
+        TracedSymbolDefinition(String, PathBuf),
+
+        #[severity = Advice]
+        #[message = "A traced symbol is referenced by an input file."]
+        #[formatted_message("The traced symbol `{0}` is referenced by the input file `{}`.", .1.display())]
+        #[help = "The symbol is traced, see `--trace-symbol`."]
+        TracedSymbolReference(String, PathBuf),
+
+        #[severity = Advice]
+        #[message = "A traced symbol resolves to a definition."]
+        #[formatted_message("The traced symbol `{0}` resolves to the definition of the input file `{}`.", .1.display())]
+        #[help = "The symbol is traced, see `--trace-symbol`."]
+        TracedSymbolResolution(String, PathBuf),
+    }
+}
+
+impl Warning {
+    /// Check whether the warning is a report requested by the user, e.g. with
+    /// [`Configuration::print_gc_sections`][crate::Configuration::print_gc_sections],
+    /// rather than a problem. A report is never ignored, nor treated as an
+    /// error.
+    pub fn is_report(&self) -> bool {
+        self.severity() == Some(Severity::Advice)
+    }
+}
+
+/// A collector of [`Warning`]s.
+///
+/// It is filled during the link. It can be disabled, see
+/// [`Configuration::no_warnings`][crate::Configuration::no_warnings], in which
+/// case it collects the reports only, see [`Warning::is_report`].
+#[derive(Debug, Default)]
+pub struct Warnings {
+    warnings: Vec<Warning>,
+    disabled: bool,
+}
+
+impl Warnings {
+    /// Create a new collector, which collects the reports only if `disabled`
+    /// is true.
+    pub(crate) fn new(disabled: bool) -> Self {
+        Self { warnings: Vec::new(), disabled }
+    }
+
+    /// Collect a warning.
+    pub(crate) fn push(&mut self, warning: Warning) {
+        if !self.disabled || warning.is_report() {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Get an iterator over all the collected warnings.
+    pub fn iter(&self) -> impl Iterator<Item = &Warning> {
+        self.warnings.iter()
+    }
+
+    /// Get the number of collected warnings.
+    pub fn len(&self) -> usize {
+        self.warnings.len()
+    }
+
+    /// Check whether no warning has been collected.
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Get the collected warnings which are not reports, see
+    /// [`Warning::is_report`], to treat them as errors.
+    pub(crate) fn fatal(self) -> Vec<Warning> {
+        self.warnings.into_iter().filter(|warning| !warning.is_report()).collect()
+    }
+}
+
+impl IntoIterator for Warnings {
+    type Item = Warning;
+    type IntoIter = vec::IntoIter<Warning>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.warnings.into_iter()
+    }
+}
+
+impl From<Warnings> for Vec<Warning> {
+    fn from(warnings: Warnings) -> Self {
+        warnings.warnings
+    }
+}
@@ -0,0 +1,2 @@
+        .globl _end
+        .set _end, 0x1000           # An absolute symbol.
@@ -0,0 +1,15 @@
+        .data
+        .quad 1, 2                  # 16 bytes
+
+        .bss
+buffer:
+        .zero 48                    # 48 bytes
+
+        .text
+        .globl _start
+        .type _start, @function
+_start:
+        movzbl buffer+47(%rip), %edi  # `.bss` is zeroed
+        addl $42, %edi
+        movl $60, %eax              # `exit`
+        syscall
@@ -0,0 +1,9 @@
+        # Call `foo`, which is defined by `libfoo.so`.
+        .text
+        .globl _start
+        .type _start, @function
+_start:
+        call foo                    # `R_X86_64_PLT32` against `foo`
+        movl %eax, %edi
+        movl $60, %eax              # `exit`
+        syscall
@@ -0,0 +1,16 @@
+        .section .text.inline_fn, "axG", @progbits, inline_fn, comdat
+        .globl inline_fn
+        .type inline_fn, @function
+inline_fn:
+        movl $42, %eax              # return code 42
+        ret
+
+        .text
+        .globl _start
+        .type _start, @function
+_start:
+        call other
+        call inline_fn
+        movl %eax, %edi
+        movl $60, %eax              # `_exit` syscall
+        syscall
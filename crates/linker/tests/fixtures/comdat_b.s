@@ -0,0 +1,13 @@
+        .section .text.inline_fn, "axG", @progbits, inline_fn, comdat
+        .globl inline_fn
+        .type inline_fn, @function
+inline_fn:
+        movl $43, %eax              # a different copy, to tell them apart
+        ret
+
+        .text
+        .globl other
+        .type other, @function
+other:
+        call inline_fn
+        ret
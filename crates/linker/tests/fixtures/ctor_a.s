@@ -0,0 +1,8 @@
+        .text
+        .type ctor_a, @function
+ctor_a:
+        ret
+
+        .section .init_array, "aw", @init_array
+        .balign 8
+        .quad ctor_a
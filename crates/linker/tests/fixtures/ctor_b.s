@@ -0,0 +1,8 @@
+        .text
+        .type ctor_b, @function
+ctor_b:
+        ret
+
+        .section .init_array, "aw", @init_array
+        .balign 8
+        .quad ctor_b
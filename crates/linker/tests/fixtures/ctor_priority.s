@@ -0,0 +1,17 @@
+        .text
+        .type ctor_priority, @function
+ctor_priority:
+        ret
+
+        .section .init_array.00100, "aw", @init_array
+        .balign 8
+        .quad ctor_priority
+
+        .text
+        .type dtor, @function
+dtor:
+        ret
+
+        .section .fini_array, "aw", @fini_array
+        .balign 8
+        .quad dtor
@@ -0,0 +1,9 @@
+        # Use `origin`, defined with `--defsym origin=0x1000`.
+        .text
+        .globl _start
+        .type _start, @function
+_start:
+        movl $origin, %edi          # `R_X86_64_32` against `origin`
+        subl $0xfd6, %edi           # 0x1000 - 0xfd6 = 42
+        movl $60, %eax              # `exit`
+        syscall
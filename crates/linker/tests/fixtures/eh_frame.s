@@ -0,0 +1,17 @@
+        .section .text.late, "ax", @progbits
+        .type late, @function
+late:
+        .cfi_startproc
+        movl $42, %edi              # return code 42
+        ret
+        .cfi_endproc
+
+        .text
+        .globl _start
+        .type _start, @function
+_start:
+        .cfi_startproc
+        call late
+        movl $60, %eax              # `_exit` syscall
+        syscall
+        .cfi_endproc
@@ -0,0 +1,6 @@
+        .text
+        .globl foo
+        .type foo, @function
+foo:
+        movl $42, %eax
+        ret
@@ -0,0 +1,20 @@
+        .section .text._start, "ax", @progbits
+        .globl _start
+        .type _start, @function
+_start:
+        call used
+        movl $60, %eax              # `exit`
+        syscall
+
+        .section .text.used, "ax", @progbits
+        .type used, @function
+used:
+        movl $42, %edi
+        ret
+
+        .section .text.unused, "ax", @progbits
+        .globl unused
+        .type unused, @function
+unused:
+        movl $7, %edi
+        ret
@@ -0,0 +1,8 @@
+        .text
+        .globl ordered
+        .type ordered, @function
+ordered:
+        ret
+
+        .section .ordered, "ao", @progbits, .text
+        .quad 0
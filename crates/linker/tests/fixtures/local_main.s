@@ -0,0 +1,4 @@
+        .text
+        .type main, @function
+main:                               # a local `main`, not the global one
+        ret
@@ -0,0 +1,7 @@
+        .text
+        .globl main
+        .type main, @function
+main:
+        movl $42, %edi
+        movl $60, %eax              # `exit`
+        syscall
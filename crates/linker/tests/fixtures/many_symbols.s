@@ -0,0 +1,20 @@
+        # Many local symbols, to stress the symbol handling.
+        .altmacro
+        .macro symbol nth
+symbol_\nth:
+        .byte 0
+        .endm
+
+        .text
+        .set nth, 0
+        .rept 4096
+        symbol %nth
+        .set nth, nth + 1
+        .endr
+
+        .globl _start
+        .type _start, @function
+_start:
+        movl $42, %edi
+        movl $60, %eax              # `exit`
+        syscall
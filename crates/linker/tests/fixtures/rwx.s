@@ -0,0 +1,6 @@
+        # `.patchable` is both writable and executable, and so is its segment.
+        .section .patchable, "awx", @progbits
+        .globl patchable
+        .type patchable, @function
+patchable:
+        ret
@@ -0,0 +1,16 @@
+        .section .text.c, "ax", @progbits
+        .ascii "c"
+
+        .section .text.a, "ax", @progbits
+        .ascii "a"
+
+        .section .text.b, "ax", @progbits
+        .ascii "b"
+
+        .text
+        .globl _start
+        .type _start, @function
+_start:
+        movl $60, %eax              # `exit`
+        movl $42, %edi
+        syscall
@@ -0,0 +1,11 @@
+        .section .rodata
+message:
+        .ascii "weld\n"
+
+        .text
+        .globl _start
+        .type _start, @function
+_start:
+        leaq message(%rip), %rsi    # `R_X86_64_PC32` against `.rodata`
+        movl answer(%rip), %edi     # `R_X86_64_PC32` against `answer`
+        call do_exit                # `R_X86_64_PLT32` against `do_exit`
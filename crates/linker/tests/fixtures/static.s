@@ -0,0 +1,10 @@
+        .text
+        .globl _start
+        .type _start, @function
+_start:
+        movl $42, %edi
+        movl $60, %eax              # `exit`
+        syscall
+
+        # Declared global, but never defined nor relocated.
+        .globl missing
@@ -0,0 +1,29 @@
+        .section .tdata, "awT", @progbits
+        .globl counter
+        .type counter, @tls_object
+        .balign 4
+counter:
+        .long 42                    # 4 bytes
+
+        .section .tbss, "awT", @nobits
+        .globl buffer
+        .type buffer, @tls_object
+        .balign 16
+buffer:
+        .zero 24                    # 24 bytes
+
+        .text
+        .globl _start
+        .type _start, @function
+_start:
+        # `movl %fs:counter@tpoff, %edi`, without the reference to
+        # `_GLOBAL_OFFSET_TABLE_` that `@tpoff` implies.
+        .byte 0x64, 0x8b, 0x3c, 0x25
+        .reloc ., R_X86_64_TPOFF32, counter
+        .long 0
+        # `movq $buffer@tpoff, %rax`.
+        .byte 0x48, 0xc7, 0xc0
+        .reloc ., R_X86_64_TPOFF32, buffer
+        .long 0
+        movl $60, %eax              # `exit`
+        syscall
@@ -0,0 +1,10 @@
+        .weak undefined_weak
+
+        .text
+        .globl _start
+        .type _start, @function
+_start:
+        movq $undefined_weak, %rdi  # `R_X86_64_32S` against `undefined_weak`
+        addq $42, %rdi
+        movl $60, %eax              # `exit`
+        syscall
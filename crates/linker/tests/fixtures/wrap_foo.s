@@ -0,0 +1,8 @@
+        # Wrap `foo`, when linked with `--wrap foo`.
+        .text
+        .globl __wrap_foo
+        .type __wrap_foo, @function
+__wrap_foo:
+        call __real_foo             # `R_X86_64_PLT32` against the original `foo`
+        addl $1, %eax
+        ret
@@ -0,0 +1,309 @@
+//! End-to-end tests: link relocatable objects into executables, and check the
+//! result.
+
+use std::{
+    env, fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use weld_linker::{target::Triple, Configuration, LinkPhase, SymbolValue};
+use weld_object::elf64::{File, FileType, ProgramType};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(name)
+}
+
+/// Link `input_files` into `output_file_name`, and return the path to the
+/// output file.
+fn link(input_files: Vec<PathBuf>, output_file_name: &str) -> PathBuf {
+    let output_file = env::temp_dir().join(output_file_name);
+
+    Configuration::new(
+        "x86_64-unknown-linux-gnu".parse::<Triple>().unwrap(),
+        input_files,
+        output_file.clone(),
+    )
+    .linker()
+    .link()
+    .unwrap();
+
+    output_file
+}
+
+/// Check the output is a loadable executable, and run it if possible.
+fn assert_executable(output_file: PathBuf, expected_exit_code: i32) {
+    let output = fs::read(&output_file).unwrap();
+    let (_, file) = File::read::<()>(&output).unwrap();
+
+    assert_eq!(&output[..4], b"\x7fELF");
+    assert_eq!(file.r#type, FileType::ExecutableFile);
+    assert!(file.programs.iter().any(|program| program.r#type == ProgramType::Load));
+    assert!(file.entry_point.is_some_and(|entry_point| entry_point.0 != 0));
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    {
+        let status = std::process::Command::new(&output_file).status().unwrap();
+
+        assert_eq!(status.code(), Some(expected_exit_code));
+    }
+
+    #[cfg(not(all(target_os = "linux", target_arch = "x86_64")))]
+    let _ = expected_exit_code;
+
+    fs::remove_file(&output_file).unwrap();
+}
+
+#[test]
+fn test_link_single_object() {
+    let output_file = link(
+        vec![fixture("../object/tests/fixtures/exit_elf_amd64.o")],
+        "weld_test_link_single_object",
+    );
+
+    assert_executable(output_file, 42);
+}
+
+#[test]
+fn test_link_several_objects() {
+    let output_file = link(
+        vec![
+            fixture("tests/fixtures/start_elf_amd64.o"),
+            fixture("tests/fixtures/exit_elf_amd64.o"),
+        ],
+        "weld_test_link_several_objects",
+    );
+
+    assert_executable(output_file, 42);
+}
+
+#[test]
+fn test_link_absolute_symbol() {
+    let output_file = link(
+        vec![
+            fixture("tests/fixtures/absolute_use_elf_amd64.o"),
+            fixture("tests/fixtures/absolute_elf_amd64.o"),
+        ],
+        "weld_test_link_absolute_symbol",
+    );
+
+    assert_executable(output_file, 42);
+}
+
+#[test]
+fn test_link_bss() {
+    let output_file = link(vec![fixture("tests/fixtures/bss_elf_amd64.o")], "weld_test_link_bss");
+
+    assert_executable(output_file, 42);
+}
+
+#[test]
+fn test_link_progress() {
+    let input_files = vec![
+        fixture("tests/fixtures/start_elf_amd64.o"),
+        fixture("tests/fixtures/exit_elf_amd64.o"),
+    ];
+    let output_file = env::temp_dir().join("weld_test_link_progress");
+    let phases = Arc::new(Mutex::new(Vec::new()));
+
+    Configuration::new(
+        "x86_64-unknown-linux-gnu".parse::<Triple>().unwrap(),
+        input_files.clone(),
+        output_file.clone(),
+    )
+    .progress({
+        let phases = phases.clone();
+
+        move |phase| {
+            phases.lock().unwrap().push(match phase {
+                LinkPhase::ParsingInput(path) => format!("parsing {}", path.display()),
+                phase => format!("{phase:?}"),
+            })
+        }
+    })
+    .linker()
+    .link()
+    .unwrap();
+
+    assert_eq!(
+        *phases.lock().unwrap(),
+        [
+            format!("parsing {}", input_files[0].display()),
+            format!("parsing {}", input_files[1].display()),
+            "ResolvingSymbols".to_owned(),
+            "ApplyingRelocations".to_owned(),
+            "WritingOutput".to_owned(),
+        ]
+    );
+
+    assert_executable(output_file, 42);
+}
+
+#[test]
+fn test_link_to_vec() {
+    let output_file = env::temp_dir().join("weld_test_link_to_vec");
+
+    let (output, warnings) = Configuration::new(
+        "x86_64-unknown-linux-gnu".parse::<Triple>().unwrap(),
+        vec![fixture("../object/tests/fixtures/exit_elf_amd64.o")],
+        output_file.clone(),
+    )
+    .linker()
+    .link_to_vec()
+    .unwrap();
+
+    let (_, file) = File::read::<()>(&output).unwrap();
+
+    assert!(warnings.is_empty());
+    assert_eq!(file.r#type, FileType::ExecutableFile);
+    assert!(file.entry_point.is_some_and(|entry_point| entry_point.0 != 0));
+    assert!(!output_file.exists());
+}
+
+#[test]
+fn test_link_shared_library_call() {
+    let output_file = env::temp_dir().join("weld_test_link_shared_library_call");
+
+    // `foo` is defined by `libfoo.so`, found at runtime thanks to the runtime
+    // search paths.
+    Configuration::new(
+        "x86_64-unknown-linux-gnu".parse::<Triple>().unwrap(),
+        vec![fixture("tests/fixtures/call_foo_elf_amd64.o")],
+        output_file.clone(),
+    )
+    .library("foo")
+    .library_path(fixture("tests/fixtures"))
+    .rpath(fixture("tests/fixtures").to_string_lossy())
+    .linker()
+    .link()
+    .unwrap();
+
+    assert_executable(output_file, 42);
+}
+
+#[test]
+fn test_link_wrap() {
+    let output_file = env::temp_dir().join("weld_test_link_wrap");
+
+    // `__wrap_foo` calls the original `foo`, and adds 1 to its result.
+    Configuration::new(
+        "x86_64-unknown-linux-gnu".parse::<Triple>().unwrap(),
+        vec![
+            fixture("tests/fixtures/call_foo_elf_amd64.o"),
+            fixture("tests/fixtures/foo_elf_amd64.o"),
+            fixture("tests/fixtures/wrap_foo_elf_amd64.o"),
+        ],
+        output_file.clone(),
+    )
+    .wrap("foo")
+    .linker()
+    .link()
+    .unwrap();
+
+    assert_executable(output_file, 43);
+}
+
+#[test]
+fn test_link_defsym() {
+    let output_file = env::temp_dir().join("weld_test_link_defsym");
+
+    Configuration::new(
+        "x86_64-unknown-linux-gnu".parse::<Triple>().unwrap(),
+        vec![fixture("tests/fixtures/defsym_elf_amd64.o")],
+        output_file.clone(),
+    )
+    .defsym("origin", SymbolValue::Absolute(0x1000))
+    .linker()
+    .link()
+    .unwrap();
+
+    assert_executable(output_file, 42);
+}
+
+#[test]
+fn test_link_eh_frame() {
+    let output_file = env::temp_dir().join("weld_test_link_eh_frame");
+
+    Configuration::new(
+        "x86_64-unknown-linux-gnu".parse::<Triple>().unwrap(),
+        vec![fixture("tests/fixtures/eh_frame_elf_amd64.o")],
+        output_file.clone(),
+    )
+    .linker()
+    .link()
+    .unwrap();
+
+    assert_executable(output_file, 42);
+}
+
+#[test]
+fn test_link_post_process() {
+    let output_file = env::temp_dir().join("weld_test_link_post_process");
+
+    Configuration::new(
+        "x86_64-unknown-linux-gnu".parse::<Triple>().unwrap(),
+        vec![
+            fixture("tests/fixtures/start_elf_amd64.o"),
+            fixture("tests/fixtures/exit_elf_amd64.o"),
+        ],
+        output_file.clone(),
+    )
+    .post_process(|mut output| {
+        output.extend_from_slice(b"WELD-TRAILER");
+
+        Ok(output)
+    })
+    .linker()
+    .link()
+    .unwrap();
+
+    // The trailer is written after the section headers, which are still
+    // valid.
+    let output = fs::read(&output_file).unwrap();
+
+    assert!(output.ends_with(b"WELD-TRAILER"));
+    assert_executable(output_file, 42);
+}
+
+#[test]
+fn test_link_comdat_groups() {
+    let output_file = link(
+        vec![
+            fixture("tests/fixtures/comdat_a_elf_amd64.o"),
+            fixture("tests/fixtures/comdat_b_elf_amd64.o"),
+        ],
+        "weld_test_link_comdat_groups",
+    );
+
+    assert_executable(output_file, 42);
+}
+
+#[test]
+fn test_link_relocatable() {
+    let object_file = env::temp_dir().join("weld_test_link_relocatable.o");
+
+    Configuration::new(
+        "x86_64-unknown-linux-gnu".parse::<Triple>().unwrap(),
+        vec![
+            fixture("tests/fixtures/start_elf_amd64.o"),
+            fixture("tests/fixtures/exit_elf_amd64.o"),
+        ],
+        object_file.clone(),
+    )
+    .relocatable(true)
+    .linker()
+    .link()
+    .unwrap();
+
+    let output = fs::read(&object_file).unwrap();
+    let (_, file) = File::read::<()>(&output).unwrap();
+
+    assert_eq!(file.r#type, FileType::RelocatableFile);
+    assert!(file.programs.is_empty());
+
+    // The relocatable object file can be linked again.
+    let output_file = link(vec![object_file.clone()], "weld_test_link_relocatable");
+    fs::remove_file(&object_file).unwrap();
+
+    assert_executable(output_file, 42);
+}
@@ -0,0 +1,34 @@
+//! Memory tests: link objects while counting the allocated memory.
+
+use std::{env, fs, mem::size_of, path::PathBuf};
+
+use weld_counting_allocator::{peak_allocated, CountingAllocator};
+use weld_linker::{target::Triple, Configuration};
+use weld_object::elf64::Symbol;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn test_link_many_symbols() {
+    // Number of symbols in the input file.
+    const SYMBOLS: usize = 4096;
+
+    let output_file = env::temp_dir().join("weld_test_link_many_symbols");
+    let configuration = Configuration::new(
+        "x86_64-unknown-linux-gnu".parse::<Triple>().unwrap(),
+        vec![PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/many_symbols_elf_amd64.o")],
+        output_file.clone(),
+    );
+
+    let (_, peak) = peak_allocated(|| configuration.linker().link().unwrap());
+    fs::remove_file(&output_file).unwrap();
+
+    // The output symbol table is built in memory, but the input symbols must
+    // not be collected on top of it.
+    assert!(
+        peak < 5 * SYMBOLS * size_of::<Symbol>(),
+        "peak allocation is {peak} bytes for {SYMBOLS} symbols",
+    );
+}
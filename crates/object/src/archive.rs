@@ -0,0 +1,214 @@
+//! Static archives, i.e. `.a` files, as produced by `ar`.
+
+use bstr::{BStr, ByteSlice};
+
+use crate::{combinators::*, Input, Result};
+
+/// A static archive, i.e. a `.a` file: a collection of members, which are
+/// usually object files.
+///
+/// The GNU and System V format is supported, where the long member names are
+/// stored in the `//` member, along with the BSD format, where the long member
+/// names prefix their data.
+#[derive(Debug)]
+pub struct Archive<'a> {
+    /// The members, in order. The special members, i.e. the symbol index and
+    /// the long names table, are not part of them.
+    pub members: Vec<Member<'a>>,
+}
+
+/// A member of an [`Archive`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct Member<'a> {
+    /// The name of the member, e.g. `exit.o`.
+    pub name: &'a BStr,
+    /// The data of the member, e.g. the bytes of an object file.
+    pub data: Input<'a>,
+}
+
+impl<'a> Archive<'a> {
+    /// The magic number of an archive.
+    pub const MAGIC: &'static [u8] = b"!<arch>\n";
+
+    /// Size, in bytes, of a member header.
+    pub const MEMBER_HEADER_SIZE: usize = 60;
+
+    /// Check whether `input` starts like an archive.
+    pub fn is_archive(input: Input) -> bool {
+        input.starts_with(Self::MAGIC)
+    }
+
+    /// Read an archive, and all its members.
+    pub fn read<E>(input: Input<'a>) -> Result<'a, Self, E>
+    where
+        E: ParseError<Input<'a>>,
+    {
+        let (mut input, _) = tag(Self::MAGIC)(input)?;
+        let mut members = Vec::new();
+        let mut long_names = None;
+
+        while !input.is_empty() {
+            let member_input = input;
+            let (next_input, (name, data)) = Self::read_member(input)?;
+
+            // The data of a member is aligned to 2 bytes, with a `\n`, but the
+            // padding of the last member may be missing.
+            input = match data.len() % 2 {
+                1 => next_input.get(1..).unwrap_or(next_input),
+                _ => next_input,
+            };
+
+            let name = match name.trim_end_with(|character| character == ' ') {
+                // The symbol index, in the 32-bit or the 64-bit format.
+                b"/" | b"/SYM64/" => continue,
+
+                // The long names table.
+                b"//" => {
+                    long_names = Some(data);
+
+                    continue;
+                }
+
+                // A long name, in the BSD format: `#1/<length>`, and the name
+                // prefixes the data.
+                name if name.starts_with(b"#1/") => {
+                    let length = parse_decimal(&name[3..])
+                        .filter(|length| *length <= data.len())
+                        .ok_or_else(|| {
+                        Err::Error(E::from_error_kind(member_input, ErrorKind::Digit))
+                    })?;
+                    let (name, data) = data.split_at(length);
+
+                    members.push(Member {
+                        name: name.trim_end_with(|character| character == '\0').as_bstr(),
+                        data,
+                    });
+
+                    continue;
+                }
+
+                // A long name, in the GNU format: `/<offset>` in the long
+                // names table, where it ends with `/\n`.
+                name if name.len() > 1 && name.starts_with(b"/") => {
+                    let long_name = parse_decimal(&name[1..])
+                        .and_then(|offset| long_names?.get(offset..))
+                        .ok_or_else(|| {
+                            Err::Error(E::from_error_kind(member_input, ErrorKind::Digit))
+                        })?;
+                    let end = long_name.find_byte(b'\n').unwrap_or(long_name.len());
+
+                    long_name[..end].strip_suffix(b"/").unwrap_or(&long_name[..end])
+                }
+
+                // A short name, ending with `/` in the GNU format.
+                name => name.strip_suffix(b"/").unwrap_or(name),
+            };
+
+            members.push(Member { name: name.as_bstr(), data });
+        }
+
+        Ok((input, Self { members }))
+    }
+
+    /// Read a member header and its data. The name is returned as is.
+    fn read_member<E>(input: Input<'a>) -> Result<'a, (Input<'a>, Input<'a>), E>
+    where
+        E: ParseError<Input<'a>>,
+    {
+        let header = input
+            .get(..Self::MEMBER_HEADER_SIZE)
+            .ok_or_else(|| Err::Error(E::from_error_kind(input, ErrorKind::Eof)))?;
+
+        // The header ends with a magic number.
+        if &header[58..60] != b"`\n" {
+            return Err(Err::Error(E::from_error_kind(input, ErrorKind::Tag)));
+        }
+
+        let size = parse_decimal(&header[48..58])
+            .ok_or_else(|| Err::Error(E::from_error_kind(input, ErrorKind::Digit)))?;
+        let data = input[Self::MEMBER_HEADER_SIZE..]
+            .get(..size)
+            .ok_or_else(|| Err::Error(E::from_error_kind(input, ErrorKind::Eof)))?;
+
+        Ok((&input[Self::MEMBER_HEADER_SIZE + size..], (&header[..16], data)))
+    }
+}
+
+/// Parse a decimal number, padded with spaces on the right, as found in the
+/// member headers.
+fn parse_decimal(input: &[u8]) -> Option<usize> {
+    input.trim_end_with(|character| character == ' ').to_str().ok()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a member header.
+    fn header(name: &str, size: usize) -> Vec<u8> {
+        format!("{name:<16}{:<12}{:<6}{:<6}{:<8}{size:<10}`\n", 0, 0, 0, 644).into_bytes()
+    }
+
+    #[test]
+    fn test_archive() {
+        let long_names = b"a_very_long_member_name.o/\n";
+        let mut input = Archive::MAGIC.to_vec();
+
+        // The symbol index, which is ignored.
+        input.extend(header("/", 4));
+        input.extend(b"\0\0\0\0");
+        // The long names table.
+        input.extend(header("//", long_names.len()));
+        input.extend(long_names);
+        input.push(b'\n');
+        // A member with a short name, whose data is padded.
+        input.extend(header("short.o/", 3));
+        input.extend(b"abc\n");
+        // A member with a long name, in the GNU format.
+        input.extend(header("/0", 2));
+        input.extend(b"de");
+        // A member with a long name, in the BSD format.
+        input.extend(header("#1/24", 24 + 1));
+        input.extend(b"another_long_name.o\0\0\0\0\0f");
+
+        let (rest, archive) = Archive::read::<()>(&input).unwrap();
+
+        assert!(rest.is_empty());
+        assert_eq!(
+            archive.members,
+            [
+                Member { name: b"short.o".as_bstr(), data: b"abc" },
+                Member { name: b"a_very_long_member_name.o".as_bstr(), data: b"de" },
+                Member { name: b"another_long_name.o".as_bstr(), data: b"f" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_archive() {
+        assert!(Archive::is_archive(b"!<arch>\n"));
+        assert!(!Archive::is_archive(b"\x7fELF"));
+    }
+
+    #[test]
+    fn test_malformed_archive() {
+        let mut input = Archive::MAGIC.to_vec();
+        input.extend(header("short.o/", 8));
+        input.extend(b"abc");
+
+        // The data is truncated.
+        assert_eq!(
+            Archive::read::<(Input, ErrorKind)>(&input).map(|_| ()),
+            Err(Err::Error((&input[8..], ErrorKind::Eof)))
+        );
+
+        // The long names table is missing.
+        let mut input = Archive::MAGIC.to_vec();
+        input.extend(header("/0", 0));
+
+        assert!(Archive::read::<()>(&input).is_err());
+
+        // Not an archive.
+        assert!(Archive::read::<()>(b"\x7fELF").is_err());
+    }
+}
@@ -0,0 +1,54 @@
+//! An arena for the names of the sections and of the symbols.
+
+use bstr::{BStr, ByteSlice};
+use bumpalo::Bump;
+
+/// An arena owning names, e.g. the names of the sections or of the symbols.
+///
+/// A name is copied once into the arena, and then borrowed for as long as the
+/// arena lives: a name borrowed from an arena is never cloned, nor copied
+/// again. The arena is usually owned by a link, and outlives all the files it
+/// parses, see
+/// [`File::fetch_section_names_in`][crate::elf64::File::fetch_section_names_in].
+///
+/// ```rust
+/// use weld_object::arena::Arena;
+///
+/// let arena = Arena::new();
+/// let name = arena.alloc_concat(&[b"__wrap_", b"foo"]);
+///
+/// assert_eq!(name, "__wrap_foo");
+/// assert!(arena.allocated_bytes() >= name.len());
+/// ```
+#[derive(Debug, Default)]
+pub struct Arena {
+    bump: Bump,
+}
+
+impl Arena {
+    /// Create a new empty arena. It doesn't allocate until a name is
+    /// allocated in it.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy `name` into the arena.
+    pub fn alloc(&self, name: &[u8]) -> &BStr {
+        self.bump.alloc_slice_copy(name).as_bstr()
+    }
+
+    /// Copy the concatenation of `parts` into the arena, without allocating
+    /// anything else.
+    pub fn alloc_concat(&self, parts: &[&[u8]]) -> &BStr {
+        let mut bytes = parts.iter().flat_map(|part| part.iter().copied());
+        let length = parts.iter().map(|part| part.len()).sum();
+
+        self.bump.alloc_slice_fill_with(length, |_| bytes.next().unwrap_or_default()).as_bstr()
+    }
+
+    /// Get the number of bytes allocated by the arena, including its unused
+    /// capacity.
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+}
@@ -1,14 +1,17 @@
+use nom::ToUsize;
 pub use nom::{
     bytes::complete::tag,
-    error::{ErrorKind, ParseError},
+    error::{ErrorKind, FromExternalError, ParseError},
     sequence::tuple,
     Err,
 };
-use nom::{InputIter, ToUsize};
 
 use crate::{Input, Result};
 
 /// Like `take` but it “skips” the parsed value.
+///
+/// It is bounds-safe: if `count` is larger than the input, it returns an
+/// error of kind [`ErrorKind::Eof`], and never panics.
 pub fn skip<'a, C, E>(count: C) -> impl Fn(Input<'a>) -> Result<'a, Input<'a>, E>
 where
     C: ToUsize,
@@ -16,9 +19,9 @@ where
 {
     let count = count.to_usize();
 
-    move |input: Input| match input.slice_index(count) {
-        Err(_needed) => Err(Err::Error(E::from_error_kind(input, ErrorKind::Eof))),
-        Ok(index) => Ok((&input[index..], &[])),
+    move |input: Input| match input.get(count..) {
+        Some(rest) => Ok((rest, &[])),
+        None => Err(Err::Error(E::from_error_kind(input, ErrorKind::Eof))),
     }
 }
 
@@ -31,5 +34,10 @@ mod tests {
         let input: &[u8] = &[1, 2, 3, 4, 5];
 
         assert_eq!(skip::<_, ()>(2usize)(input), Ok((&[3, 4, 5][..], &[] as &[u8])));
+        assert_eq!(skip::<_, ()>(5usize)(input), Ok((&[][..], &[] as &[u8])));
+        assert_eq!(
+            skip::<_, nom::error::Error<Input>>(6usize)(input),
+            Err(Err::Error(nom::error::Error::new(input, ErrorKind::Eof)))
+        );
     }
 }
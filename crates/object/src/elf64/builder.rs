@@ -0,0 +1,723 @@
+use std::{
+    borrow::Cow,
+    io::{self, Read as _, Write as _},
+    mem,
+};
+
+use bstr::BStr;
+
+use super::{
+    Address, Alignment, Data, DataType, Endianness, File, FileType, Machine, OsAbi, Program,
+    Section, SectionFlag, SectionFlags, SectionIndex, SectionType,
+};
+use crate::{BigEndian, LittleEndian, Number, Write};
+
+/// Build an Elf64 file.
+///
+/// The content of the file is made of the data of the sections; the data of
+/// the programs is not written. Programs and allocable sections must already
+/// have their offset, and their virtual address: the builder writes them as
+/// is. Non-allocable sections are placed after them. The section names table,
+/// `.shstrtab`, is generated from the names of the sections.
+///
+/// The index of a section in the output is its insertion order plus one,
+/// because of the null section.
+///
+/// # Example
+///
+/// ```rust
+/// use std::borrow::Cow;
+///
+/// use bstr::BStr;
+/// use weld_object::elf64::*;
+///
+/// # fn main() {
+/// // `mov edi, 42; mov eax, 60; syscall`, i.e. `exit(42)`.
+/// let code: &[u8] = &[
+///     0xbf, 0x2a, 0x00, 0x00, 0x00, 0xb8, 0x3c, 0x00, 0x00, 0x00, 0x0f, 0x05,
+/// ];
+/// let code_offset = 0x1000;
+/// let code_address = 0x40_1000;
+///
+/// let mut builder = Builder::new(Endianness::Little, Machine::X86_64, OsAbi::SystemV);
+///
+/// builder.add_program(Program {
+///     r#type: ProgramType::Load,
+///     segment_flags: ProgramFlag::Read | ProgramFlag::Execute,
+///     offset: Address(code_offset),
+///     virtual_address: Address(code_address),
+///     physical_address: Some(Address(code_address)),
+///     segment_size_in_file_image: Address(code.len() as u64),
+///     segment_size_in_memory: Address(code.len() as u64),
+///     alignment: Alignment::new(0x1000).unwrap(),
+///     data: Data::new(
+///         Cow::Borrowed(code),
+///         DataType::ProgramData,
+///         weld_object::Endianness::Little,
+///         Machine::X86_64,
+///         None,
+///     ),
+/// });
+///
+/// builder.add_section(Section {
+///     name: Some(Cow::Borrowed(BStr::new(".text"))),
+///     name_offset: Address(0),
+///     r#type: SectionType::ProgramData,
+///     flags: SectionFlag::Allocable | SectionFlag::Executable,
+///     virtual_address: Address(code_address),
+///     offset: Address(code_offset),
+///     segment_size_in_file_image: Address(code.len() as u64),
+///     link: SectionIndex::Undefined,
+///     information: 0,
+///     alignment: Alignment::new(1).unwrap(),
+///     entity_size: None,
+///     data: Data::new(
+///         Cow::Borrowed(code),
+///         DataType::ProgramData,
+///         weld_object::Endianness::Little,
+///         Machine::X86_64,
+///         None,
+///     ),
+/// });
+///
+/// builder.set_entry(Address(code_address));
+///
+/// let bytes = builder.build().unwrap();
+///
+/// // Let's parse the file we've just built.
+/// let (_, mut file) = File::read::<()>(&bytes).unwrap();
+/// file.fetch_section_names();
+///
+/// assert_eq!(file.r#type, FileType::ExecutableFile);
+/// assert_eq!(file.machine, Machine::X86_64);
+/// assert_eq!(file.entry_point, Some(Address(code_address)));
+/// assert_eq!(file.programs.len(), 1);
+///
+/// // The null section, `.text`, and `.shstrtab`.
+/// assert_eq!(file.sections.len(), 3);
+/// assert_eq!(file.sections[1].name, Some(Cow::Borrowed(BStr::new(".text"))));
+/// assert_eq!(&*file.sections[1].data, &Cow::Borrowed(code));
+/// # }
+/// ```
+pub struct Builder<'a> {
+    endianness: Endianness,
+    machine: Machine,
+    os_abi: OsAbi,
+    r#type: FileType,
+    entry_point: Option<Address>,
+    programs: Vec<Program<'a>>,
+    sections: Vec<Section<'a>>,
+}
+
+impl<'a> Builder<'a> {
+    /// Create a new builder for an executable file.
+    pub fn new(endianness: Endianness, machine: Machine, os_abi: OsAbi) -> Self {
+        Self {
+            endianness,
+            machine,
+            os_abi,
+            r#type: FileType::ExecutableFile,
+            entry_point: None,
+            programs: Vec::new(),
+            sections: Vec::new(),
+        }
+    }
+
+    /// Add a program, i.e. a program header.
+    pub fn add_program(&mut self, program: Program<'a>) {
+        self.programs.push(program);
+    }
+
+    /// Add a section. Its name is read from [`Section::name`], and
+    /// [`Section::name_offset`] is ignored.
+    ///
+    /// If the section is not allocable (see [`SectionFlag::Allocable`]), its
+    /// offset is computed by the builder, and it doesn't belong to any
+    /// program: it can be anything, like a `.comment` or a `.note` section.
+    pub fn add_section(&mut self, section: Section<'a>) {
+        self.sections.push(section);
+    }
+
+    /// Set the type of the file. By default, it is
+    /// [`FileType::ExecutableFile`].
+    pub fn set_type(&mut self, r#type: FileType) {
+        self.r#type = r#type;
+    }
+
+    /// Set the entry point virtual address.
+    pub fn set_entry(&mut self, entry_point: Address) {
+        self.entry_point = Some(entry_point);
+    }
+
+    /// Build the file.
+    ///
+    /// Up to `0xfffe` programs are supported, otherwise an
+    /// [`io::ErrorKind::InvalidInput`] error is returned. The number of
+    /// sections isn't limited by the file header: from `0xff00` sections, it
+    /// is stored in the null section.
+    pub fn build(mut self) -> io::Result<Vec<u8>> {
+        let file_layout = self.lay_out();
+
+        // The section headers are the last bytes of the file, so the total
+        // size is known upfront and the buffer is allocated once.
+        let mut buffer = Vec::with_capacity(file_layout.file_size as usize);
+        self.write(&file_layout, &mut buffer)?;
+
+        debug_assert_eq!(buffer.len() as u64, file_layout.file_size);
+
+        Ok(buffer)
+    }
+
+    /// Build the file, and write it incrementally into `writer`, e.g. an
+    /// [`io::BufWriter`] over a file, so that the whole file never has to be
+    /// in memory at once. The bytes are the same as with [`Self::build`].
+    ///
+    /// The size of the file is returned.
+    pub fn build_into<W>(mut self, writer: &mut W) -> io::Result<u64>
+    where
+        W: io::Write,
+    {
+        let file_layout = self.lay_out();
+        self.write(&file_layout, writer)?;
+
+        Ok(file_layout.file_size)
+    }
+
+    /// Generate the section names table, and place the non-allocable
+    /// sections: after this, the offset of everything is known.
+    fn lay_out(&mut self) -> FileLayout {
+        // Generate the section names table, as the last section.
+        let mut section_names = vec![0];
+
+        for section in &mut self.sections {
+            section.name_offset = match &section.name {
+                Some(name) => {
+                    let name_offset = Address(section_names.len() as u64);
+                    section_names.extend_from_slice(name);
+                    section_names.push(0);
+
+                    name_offset
+                }
+                None => Address(0),
+            };
+        }
+
+        let name_offset = Address(section_names.len() as u64);
+        section_names.extend_from_slice(b".shstrtab\0");
+
+        self.sections.push(Section {
+            name: Some(Cow::Borrowed(BStr::new(".shstrtab"))),
+            name_offset,
+            r#type: SectionType::StringTable,
+            flags: SectionFlags::empty(),
+            virtual_address: Address(0),
+            offset: Address(0),
+            segment_size_in_file_image: Address(section_names.len() as u64),
+            link: SectionIndex::Undefined,
+            information: 0,
+            alignment: Alignment::new(1).unwrap(),
+            entity_size: None,
+            data: Data::new(
+                Cow::Owned(mem::take(&mut section_names)),
+                DataType::StringTable,
+                crate::Endianness::Little,
+                self.machine,
+                None,
+            ),
+        });
+
+        // Place the non-allocable sections after all the programs and the
+        // allocable sections.
+        let headers_size = (File::HEADER_SIZE + self.programs.len() * Program::SIZE) as u64;
+        let programs_end = self
+            .programs
+            .iter()
+            .map(|program| program.offset.0 + program.segment_size_in_file_image.0)
+            .max()
+            .unwrap_or(0);
+        let mut offset = self
+            .sections
+            .iter()
+            .filter(|section| section.flags.contains(SectionFlag::Allocable))
+            .map(|section| {
+                // `NoBits` sections occupy no space in the file.
+                if section.r#type == SectionType::NoBits {
+                    section.offset.0
+                } else {
+                    section.offset.0 + section.segment_size_in_file_image.0
+                }
+            })
+            .max()
+            .unwrap_or(0)
+            .max(programs_end)
+            .max(headers_size);
+
+        for section in self
+            .sections
+            .iter_mut()
+            .filter(|section| !section.flags.contains(SectionFlag::Allocable))
+        {
+            offset = section.alignment.align_up(offset);
+            section.offset = Address(offset);
+            offset += section.segment_size_in_file_image.0;
+        }
+
+        let section_headers_offset = offset.next_multiple_of(8);
+
+        FileLayout {
+            programs_end,
+            section_headers_offset,
+            // The section headers are the last bytes of the file, after the
+            // null section.
+            file_size: section_headers_offset + ((self.sections.len() + 1) * Section::SIZE) as u64,
+        }
+    }
+
+    /// Write the file, once laid out, see [`Self::lay_out`].
+    fn write<W>(&self, file_layout: &FileLayout, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        let mut writer = PositionedWriter { inner: writer, position: 0 };
+
+        match self.endianness {
+            Endianness::Big => self.write_with_endianness::<BigEndian, _>(file_layout, &mut writer),
+            Endianness::Little => {
+                self.write_with_endianness::<LittleEndian, _>(file_layout, &mut writer)
+            }
+        }?;
+
+        debug_assert_eq!(writer.position, file_layout.file_size);
+
+        writer.inner.flush()
+    }
+
+    fn write_with_endianness<N, W>(
+        &self,
+        file_layout: &FileLayout,
+        writer: &mut PositionedWriter<W>,
+    ) -> io::Result<()>
+    where
+        N: Number,
+        W: io::Write,
+    {
+        // The section names table is the last section, and section indices are
+        // shifted by one because of the null section.
+        let section_names_index = self.sections.len();
+
+        // `0xffff` programs means that the actual number is stored elsewhere,
+        // which is not supported.
+        let program_count = u16::try_from(self.programs.len())
+            .ok()
+            .filter(|program_count| *program_count != u16::MAX)
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "Too many programs for the file header")
+            })?;
+
+        // The number of sections, and the index of the section names, which
+        // don't fit in the file header are stored in the null section: the
+        // number of sections in `sh_size`, the index of the section names in
+        // `sh_link`.
+        let section_count = self.sections.len() + 1;
+        let (header_section_count, null_section_size) = match u16::try_from(section_count) {
+            Ok(section_count) if usize::from(section_count) < LOW_RESERVED_SECTION_INDEX => {
+                (section_count, 0)
+            }
+            _ => (0, section_count as u64),
+        };
+        let (header_section_names_index, null_section_link) =
+            match u16::try_from(section_names_index) {
+                Ok(index) if usize::from(index) < LOW_RESERVED_SECTION_INDEX => (index, 0),
+                _ => (
+                    EXTENDED_SECTION_INDEX,
+                    u32::try_from(section_names_index).map_err(|_| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "Too many sections")
+                    })?,
+                ),
+            };
+
+        // File header.
+        writer.write_all(File::MAGIC)?;
+        writer.write_all(File::ELF64)?;
+        self.endianness.write::<N, _>(writer)?;
+        writer.write_all(&[0x01])?;
+        self.os_abi.write::<N, _>(writer)?;
+        writer.write_all(&[0; 8])?;
+        self.r#type.write::<N, _>(writer)?;
+        self.machine.write::<N, _>(writer)?;
+        writer.write_all(&N::write_u32(1))?;
+        <Option<Address> as Write<u64>>::write::<N, _>(&self.entry_point, writer)?;
+        writer.write_all(&N::write_u64(if self.programs.is_empty() {
+            0
+        } else {
+            File::HEADER_SIZE as u64
+        }))?;
+        writer.write_all(&N::write_u64(file_layout.section_headers_offset))?;
+        writer.write_all(&N::write_u32(0))?;
+        writer.write_all(&N::write_u16(File::HEADER_SIZE as u16))?;
+        writer.write_all(&N::write_u16(Program::SIZE as u16))?;
+        writer.write_all(&N::write_u16(program_count))?;
+        writer.write_all(&N::write_u16(Section::SIZE as u16))?;
+        writer.write_all(&N::write_u16(header_section_count))?;
+        writer.write_all(&N::write_u16(header_section_names_index))?;
+
+        // Program headers.
+        for program in &self.programs {
+            program.write::<N, _>(writer)?;
+        }
+
+        // Section data, in file order: sections can be added in any order.
+        let mut sections_in_file_order = self.sections.iter().collect::<Vec<_>>();
+        sections_in_file_order.sort_by_key(|section| section.offset.0);
+
+        for section in sections_in_file_order {
+            if writer.position > section.offset.0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Sections are overlapping in the output file",
+                ));
+            }
+
+            writer.pad_to(section.offset.0)?;
+            writer.write_all(&section.data)?;
+        }
+
+        // Program data that are not covered by sections, e.g. the padding.
+        writer.pad_to(writer.position.max(file_layout.programs_end))?;
+
+        // Section headers, starting with the null section: everything is zero,
+        // except the extended values, i.e. `sh_size` and `sh_link`.
+        writer.pad_to(file_layout.section_headers_offset)?;
+        writer.write_all(&[0; 32])?;
+        writer.write_all(&N::write_u64(null_section_size))?;
+        writer.write_all(&N::write_u32(null_section_link))?;
+        writer.write_all(&[0; 20])?;
+
+        for section in &self.sections {
+            section.write::<N, _>(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The first reserved section index, i.e. `SHN_LORESERVE`: the number of
+/// sections, or a section index, from this one doesn't fit in the file header.
+const LOW_RESERVED_SECTION_INDEX: usize = 0xff00;
+
+/// The section index announcing that the actual index is stored elsewhere,
+/// i.e. `SHN_XINDEX`, see [`SectionIndex::Extended`].
+const EXTENDED_SECTION_INDEX: u16 = 0xffff;
+
+/// The offsets of a file, computed by [`Builder::lay_out`].
+struct FileLayout {
+    /// The first offset after the data of all programs.
+    programs_end: u64,
+    /// The offset of the section headers.
+    section_headers_offset: u64,
+    /// The size of the file.
+    file_size: u64,
+}
+
+/// A writer keeping track of the number of bytes written so far, i.e. the
+/// offset in the file.
+struct PositionedWriter<'w, W> {
+    inner: &'w mut W,
+    position: u64,
+}
+
+impl<W> PositionedWriter<'_, W>
+where
+    W: io::Write,
+{
+    /// Write zero bytes up to `offset`, if it is after the current position.
+    fn pad_to(&mut self, offset: u64) -> io::Result<()> {
+        let padding = offset.saturating_sub(self.position);
+
+        io::copy(&mut io::repeat(0).take(padding), self)?;
+
+        Ok(())
+    }
+}
+
+impl<W> io::Write for PositionedWriter<'_, W>
+where
+    W: io::Write,
+{
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buffer)?;
+        self.position += written as u64;
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{ProgramFlag, ProgramType},
+        *,
+    };
+
+    #[test]
+    fn test_non_allocable_section() {
+        let comment: &[u8] = b"weld\0";
+
+        let mut builder = Builder::new(Endianness::Little, Machine::X86_64, OsAbi::SystemV);
+
+        // The non-allocable section is added first on purpose.
+        builder.add_section(Section {
+            name: Some(Cow::Borrowed(BStr::new(".comment"))),
+            name_offset: Address(0),
+            r#type: SectionType::ProgramData,
+            flags: SectionFlags::empty(),
+            virtual_address: Address(0),
+            offset: Address(0),
+            segment_size_in_file_image: Address(comment.len() as u64),
+            link: SectionIndex::Undefined,
+            information: 0,
+            alignment: Alignment::new(1).unwrap(),
+            entity_size: None,
+            data: Data::new(
+                Cow::Borrowed(comment),
+                DataType::ProgramData,
+                crate::Endianness::Little,
+                Machine::X86_64,
+                None,
+            ),
+        });
+        builder.add_program(Program {
+            r#type: ProgramType::Load,
+            segment_flags: ProgramFlag::Read.into(),
+            offset: Address(0),
+            virtual_address: Address(0x40_0000),
+            physical_address: None,
+            segment_size_in_file_image: Address(0x100),
+            segment_size_in_memory: Address(0x100),
+            alignment: Alignment::new(0x1000).unwrap(),
+            data: Data::new(
+                Cow::Borrowed(&[]),
+                DataType::ProgramData,
+                crate::Endianness::Little,
+                Machine::X86_64,
+                None,
+            ),
+        });
+
+        let bytes = builder.build().unwrap();
+        let (_, mut file) = File::read::<()>(&bytes).unwrap();
+        file.fetch_section_names();
+
+        let section = file
+            .sections
+            .iter()
+            .find(|section| section.name.as_deref().is_some_and(|name| name == ".comment"))
+            .unwrap();
+
+        assert_eq!(&**section.data, comment);
+
+        // The section doesn't belong to any loadable program.
+        for program in file.programs.iter().filter(|program| program.r#type == ProgramType::Load) {
+            let program_range =
+                program.offset.0..program.offset.0 + program.segment_size_in_file_image.0;
+
+            assert!(!program_range.contains(&section.offset.0));
+        }
+    }
+
+    #[test]
+    fn test_extended_section_number_and_index() {
+        let mut builder = Builder::new(Endianness::Little, Machine::X86_64, OsAbi::SystemV);
+
+        // With the null section and the section names, the number of sections,
+        // and the index of the section names, don't fit in the file header.
+        for _ in 0..LOW_RESERVED_SECTION_INDEX {
+            builder.add_section(Section {
+                name: Some(Cow::Borrowed(BStr::new(".note"))),
+                name_offset: Address(0),
+                r#type: SectionType::Note,
+                flags: SectionFlags::empty(),
+                virtual_address: Address(0),
+                offset: Address(0),
+                segment_size_in_file_image: Address(0),
+                link: SectionIndex::Undefined,
+                information: 0,
+                alignment: Alignment::new(1).unwrap(),
+                entity_size: None,
+                data: Data::new(
+                    Cow::Borrowed(&[]),
+                    DataType::ProgramData,
+                    crate::Endianness::Little,
+                    Machine::X86_64,
+                    None,
+                ),
+            });
+        }
+
+        let bytes = builder.build().unwrap();
+
+        // `e_shnum` and `e_shstrndx`.
+        assert_eq!(&bytes[60..62], &[0x00, 0x00]);
+        assert_eq!(&bytes[62..64], &[0xff, 0xff]);
+
+        let (_, mut file) = File::read::<()>(&bytes).unwrap();
+        file.fetch_section_names();
+
+        let section_names_index = LOW_RESERVED_SECTION_INDEX + 1;
+
+        assert_eq!(file.sections.len(), section_names_index + 1);
+        assert_eq!(file.section_index_for_section_names, SectionIndex::Ok(section_names_index));
+        assert_eq!(file.sections[section_names_index].r#type, SectionType::StringTable);
+        assert_eq!(file.sections[1].name, Some(Cow::Borrowed(BStr::new(".note"))));
+    }
+
+    #[test]
+    fn test_too_many_programs() {
+        let mut builder = Builder::new(Endianness::Little, Machine::X86_64, OsAbi::SystemV);
+
+        for _ in 0..=u16::MAX {
+            builder.add_program(Program {
+                r#type: ProgramType::Note,
+                segment_flags: ProgramFlag::Read.into(),
+                offset: Address(0),
+                virtual_address: Address(0),
+                physical_address: None,
+                segment_size_in_file_image: Address(0),
+                segment_size_in_memory: Address(0),
+                alignment: Alignment::new(1).unwrap(),
+                data: Data::new(
+                    Cow::Borrowed(&[]),
+                    DataType::ProgramData,
+                    crate::Endianness::Little,
+                    Machine::X86_64,
+                    None,
+                ),
+            });
+        }
+
+        assert_eq!(builder.build().unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_single_allocation() {
+        use weld_counting_allocator::count_allocations;
+
+        let data = (0..0x10_0000u32).map(|nth| nth as u8).collect::<Vec<_>>();
+        let builder = || {
+            let mut builder = Builder::new(Endianness::Little, Machine::X86_64, OsAbi::SystemV);
+
+            builder.add_section(Section {
+                name: Some(Cow::Borrowed(BStr::new(".data"))),
+                name_offset: Address(0),
+                r#type: SectionType::ProgramData,
+                flags: SectionFlag::Allocable | SectionFlag::Writable,
+                virtual_address: Address(0x40_1000),
+                offset: Address(0x1000),
+                segment_size_in_file_image: Address(data.len() as u64),
+                link: SectionIndex::Undefined,
+                information: 0,
+                alignment: Alignment::new(0x1000).unwrap(),
+                entity_size: None,
+                data: Data::new(
+                    Cow::Borrowed(&data),
+                    DataType::ProgramData,
+                    crate::Endianness::Little,
+                    Machine::X86_64,
+                    None,
+                ),
+            });
+
+            builder
+        };
+
+        // Laying out the file allocates, but writing it into a sink doesn't:
+        // the only other allocation of `build` is the output buffer, which is
+        // never grown.
+        let (size, allocations_without_buffer) =
+            count_allocations(|| builder().build_into(&mut io::sink()).unwrap());
+        let (bytes, allocations) = count_allocations(|| builder().build().unwrap());
+
+        assert_eq!(allocations, allocations_without_buffer + 1);
+        assert_eq!(bytes.len() as u64, size);
+
+        // The bytes are the same as the ones written incrementally.
+        let mut written = Vec::new();
+        builder().build_into(&mut written).unwrap();
+
+        assert_eq!(bytes, written);
+
+        let (_, mut file) = File::read::<()>(&bytes).unwrap();
+        file.fetch_section_names();
+
+        let section = file
+            .sections
+            .iter()
+            .find(|section| section.name.as_deref().is_some_and(|name| name == ".data"))
+            .unwrap();
+
+        assert_eq!(&**section.data, data.as_slice());
+    }
+
+    #[test]
+    fn test_build_into() {
+        let code: &[u8] = &[0xbf, 0x2a, 0x00, 0x00, 0x00, 0xb8, 0x3c, 0x00, 0x00, 0x00, 0x0f, 0x05];
+        let builder = || {
+            let mut builder = Builder::new(Endianness::Little, Machine::X86_64, OsAbi::SystemV);
+
+            builder.set_entry(Address(0x40_1000));
+            builder.add_program(Program {
+                r#type: ProgramType::Load,
+                segment_flags: ProgramFlag::Read | ProgramFlag::Execute,
+                offset: Address(0x1000),
+                virtual_address: Address(0x40_1000),
+                physical_address: None,
+                segment_size_in_file_image: Address(code.len() as u64),
+                segment_size_in_memory: Address(code.len() as u64),
+                alignment: Alignment::new(0x1000).unwrap(),
+                data: Data::new(
+                    Cow::Borrowed(&[]),
+                    DataType::ProgramData,
+                    crate::Endianness::Little,
+                    Machine::X86_64,
+                    None,
+                ),
+            });
+            builder.add_section(Section {
+                name: Some(Cow::Borrowed(BStr::new(".text"))),
+                name_offset: Address(0),
+                r#type: SectionType::ProgramData,
+                flags: SectionFlag::Allocable | SectionFlag::Executable,
+                virtual_address: Address(0x40_1000),
+                offset: Address(0x1000),
+                segment_size_in_file_image: Address(code.len() as u64),
+                link: SectionIndex::Undefined,
+                information: 0,
+                alignment: Alignment::new(16).unwrap(),
+                entity_size: None,
+                data: Data::new(
+                    Cow::Borrowed(code),
+                    DataType::ProgramData,
+                    crate::Endianness::Little,
+                    Machine::X86_64,
+                    None,
+                ),
+            });
+
+            builder
+        };
+
+        let bytes = builder().build().unwrap();
+
+        // Writing incrementally produces the same bytes.
+        let mut writer = io::BufWriter::with_capacity(64, Vec::new());
+        let size = builder().build_into(&mut writer).unwrap();
+        let streamed = writer.into_inner().unwrap();
+
+        assert_eq!(size, bytes.len() as u64);
+        assert_eq!(streamed, bytes);
+    }
+}
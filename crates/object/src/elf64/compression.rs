@@ -0,0 +1,97 @@
+use std::io;
+
+use weld_object_macros::ReadWrite;
+
+use super::Alignment;
+use crate::{combinators::*, Input, Number, Read, Result, Write};
+
+/// A compression header, i.e. the `Elf64_Chdr` at the beginning of the data
+/// of a compressed section.
+///
+/// The section has the [`SectionFlag::Compressed`][super::SectionFlag::Compressed]
+/// flag. The compressed data follows the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionHeader {
+    /// The compression algorithm.
+    pub r#type: CompressionType,
+    /// The size, in bytes, of the uncompressed data.
+    pub size: u64,
+    /// The alignment of the uncompressed data.
+    pub alignment: Alignment,
+}
+
+impl CompressionHeader {
+    /// Size, in bytes, of a compression header.
+    pub const SIZE: usize = 24;
+}
+
+impl Read for CompressionHeader {
+    fn read<'a, N, E>(input: Input<'a>) -> Result<'a, Self, E>
+    where
+        N: Number,
+        E: ParseError<Input<'a>>,
+    {
+        let (input, (r#type, _reserved, size, alignment)) = tuple((
+            CompressionType::read::<N, _>,
+            N::read_u32,
+            N::read_u64,
+            Alignment::read::<N, _>,
+        ))(input)?;
+
+        Ok((input, Self { r#type, size, alignment }))
+    }
+}
+
+impl Write for CompressionHeader {
+    fn write<N, B>(&self, buffer: &mut B) -> io::Result<()>
+    where
+        N: Number,
+        B: io::Write,
+    {
+        self.r#type.write::<N, _>(buffer)?;
+        buffer.write_all(&N::write_u32(0))?;
+        buffer.write_all(&N::write_u64(self.size))?;
+        self.alignment.write::<N, _>(buffer)
+    }
+}
+
+/// Compression algorithm of a compressed section.
+#[derive(ReadWrite, Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum CompressionType {
+    /// The data is compressed with zlib.
+    Zlib = 0x01,
+    /// The data is compressed with Zstandard.
+    Zstd = 0x02,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_header() {
+        #[rustfmt::skip]
+        let input: &[u8] = &[
+            // Type.
+            0x00, 0x00, 0x00, 0x02,
+            // Reserved.
+            0x00, 0x00, 0x00, 0x00,
+            // Size.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            // Alignment.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08,
+        ];
+
+        assert_read_write!(
+            CompressionHeader: Read<()> + Write<()> {
+                bytes_value(big_endian) = input,
+                rust_value = CompressionHeader {
+                    r#type: CompressionType::Zstd,
+                    size: 0x100,
+                    alignment: Alignment::new(8).unwrap(),
+                },
+            }
+        );
+    }
+}
@@ -1,18 +1,35 @@
-use std::{borrow::Cow, fmt, num::NonZeroU64};
+use std::{borrow::Cow, fmt, io, num::NonZeroU64, ops::Deref};
 
 use bstr::BStr;
 use nom::error::VerboseError;
 
-use super::{Section, SectionType, Symbol, SymbolIterator};
-use crate::{combinators::*, Endianness, Input};
+#[cfg(feature = "eh_frame")]
+use super::EhFrameIterator;
+use super::{
+    CompressionHeader, DynamicIterator, Group, Machine, RelocationIterator, Section, SectionType,
+    Symbol, SymbolIterator, VersionNeedIterator, VersionSymbolIterator,
+};
+#[cfg(feature = "arena")]
+use crate::arena::Arena;
+use crate::{combinators::*, BigEndian, Endianness, Input, LittleEndian, Read};
 
 /// The type of `Data`.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DataType {
     /// `Data` represents a string table.
     StringTable,
     /// `Data` represents a symbol table.
     SymbolTable,
+    /// `Data` represents a relocation table, with addends.
+    RelocationTable,
+    /// `Data` represents a symbol version table.
+    VersionSymbolTable,
+    /// `Data` represents a version requirement table.
+    VersionNeedTable,
+    /// `Data` represents a dynamic table.
+    DynamicTable,
+    /// `Data` represents a section group.
+    Group,
     /// `Data` represents program data.
     ProgramData,
     /// `Data` has unspecified data.
@@ -23,7 +40,12 @@ impl From<SectionType> for DataType {
     fn from(value: SectionType) -> Self {
         match value {
             SectionType::StringTable => Self::StringTable,
-            SectionType::SymbolTable => Self::SymbolTable,
+            SectionType::SymbolTable | SectionType::DynamicLoaderSymbolTable => Self::SymbolTable,
+            SectionType::RelocationWithAddends => Self::RelocationTable,
+            SectionType::GnuVersionSymbol => Self::VersionSymbolTable,
+            SectionType::GnuVersionNeed => Self::VersionNeedTable,
+            SectionType::DynamicLinkingTable => Self::DynamicTable,
+            SectionType::Group => Self::Group,
             SectionType::ProgramData => Self::ProgramData,
             _ => Self::Unspecified,
         }
@@ -34,7 +56,7 @@ impl From<SectionType> for DataType {
 ///
 /// It represents the data owned by a [`Program`][super::Program] or a
 /// [`Section`][super::Section].
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct Data<'a> {
     /// Inner bytes.
     pub(crate) inner: Cow<'a, [u8]>,
@@ -42,20 +64,72 @@ pub struct Data<'a> {
     pub(crate) r#type: DataType,
     /// The endianness of the data.
     endianness: Endianness,
+    /// The machine of the file owning the data, to interpret program data.
+    machine: Machine,
     /// The size, in bytes, of each “entry”, if the data represents fixed-sized
     /// entries.
     entity_size: Option<NonZeroU64>,
+    /// Whether the bytes are compressed, i.e. the owning section has the
+    /// [`SectionFlag::Compressed`][super::SectionFlag::Compressed] flag.
+    pub(crate) compressed: bool,
+    /// The size of the data in memory, if the owning section is a
+    /// [`SectionType::NoBits`] section: it occupies memory, but has no bytes.
+    pub(crate) no_bits_size: Option<u64>,
+    /// The string table associated to the data, e.g. the `.strtab` section of
+    /// a symbol table, if it has been resolved, see
+    /// [`File::read_resolved`][super::File::read_resolved].
+    pub(crate) strings: Option<Box<Data<'a>>>,
 }
 
 impl<'a> Data<'a> {
     /// Create a new `Data` type, wrapping some bytes.
-    pub(crate) fn new(
+    pub fn new(
         inner: Cow<'a, [u8]>,
         r#type: DataType,
         endianness: Endianness,
+        machine: Machine,
         entity_size: Option<NonZeroU64>,
     ) -> Self {
-        Self { inner, r#type, endianness, entity_size }
+        Self {
+            inner,
+            r#type,
+            endianness,
+            machine,
+            entity_size,
+            compressed: false,
+            no_bits_size: None,
+            strings: None,
+        }
+    }
+
+    /// Create a new `Data` type for a [`SectionType::NoBits`] section, like
+    /// `.bss`: it has no bytes, but occupies `size` bytes in memory.
+    pub fn no_bits(size: u64, endianness: Endianness, machine: Machine) -> Self {
+        Self {
+            no_bits_size: Some(size),
+            ..Self::new(Cow::Borrowed(&[]), SectionType::NoBits.into(), endianness, machine, None)
+        }
+    }
+
+    /// Check whether the data belongs to a [`SectionType::NoBits`] section,
+    /// i.e. occupies memory without having bytes.
+    pub fn is_nobits(&self) -> bool {
+        self.no_bits_size.is_some()
+    }
+
+    /// Get the size of the data in memory, in bytes. It is the number of
+    /// bytes, except for a [`SectionType::NoBits`] section, which has no
+    /// bytes but still occupies memory, see [`Self::is_nobits`].
+    pub fn logical_size(&self) -> u64 {
+        self.no_bits_size.unwrap_or(self.inner.len() as u64)
+    }
+
+    /// Get the bytes as a mutable slice, to edit them in place.
+    ///
+    /// Borrowed bytes are copied on the first mutation, so that the data
+    /// owns them from now on.
+    pub fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        Some(self.inner.to_mut().as_mut_slice())
     }
 
     /// Get the string at a specific offset, if and only if (i) the data type
@@ -64,29 +138,89 @@ impl<'a> Data<'a> {
     ///
     /// The string is not guaranteed to be valid UTF-8. It is a bytes slice,
     /// `&[u8]`.
-    pub fn string_at_offset(&self, offset: usize) -> Option<Cow<BStr>> {
+    pub fn string_at_offset(&self, offset: usize) -> Option<Cow<'_, BStr>> {
         if self.r#type != DataType::StringTable {
             return None;
         }
 
-        if offset >= self.inner.len() {
+        string_at(&self.inner, offset).map(Cow::Borrowed)
+    }
+
+    /// Get the name at a specific offset, like [`Self::string_at_offset`],
+    /// except that the offset 0 means that there is no name: `None` is
+    /// returned instead of the empty string at this offset.
+    ///
+    /// It is used for the names of the symbols and of the sections.
+    pub fn name_at_offset(&self, offset: usize) -> Option<Cow<'_, BStr>> {
+        if offset == 0 {
+            return None;
+        }
+
+        self.string_at_offset(offset)
+    }
+
+    /// Get the name at a specific offset, like [`Self::name_at_offset`], but
+    /// the name is not tied to `self`, see [`Self::input_string_at_offset`].
+    pub fn input_name_at_offset(&self, offset: usize) -> Option<Cow<'a, BStr>> {
+        if offset == 0 {
             return None;
         }
 
-        let name = &self.inner[offset..];
+        self.input_string_at_offset(offset)
+    }
+
+    /// Get the string at a specific offset, like [`Self::string_at_offset`],
+    /// but the string is not tied to `self`: it borrows the input bytes when
+    /// the data is still borrowed from them, otherwise it is copied.
+    pub fn input_string_at_offset(&self, offset: usize) -> Option<Cow<'a, BStr>> {
+        if self.r#type != DataType::StringTable {
+            return None;
+        }
 
-        name.iter()
-            .position(|c| *c == 0x00)
-            .map(|name_end| Cow::Borrowed(BStr::new(&name[..name_end])))
+        match self.inner {
+            Cow::Borrowed(bytes) => string_at(bytes, offset).map(Cow::Borrowed),
+            Cow::Owned(ref bytes) => string_at(bytes, offset).map(|name| Cow::Owned(name.into())),
+        }
+    }
+
+    /// Get the name at a specific offset, like [`Self::input_name_at_offset`],
+    /// but the name is copied into `arena` instead of being cloned, see
+    /// [`Self::input_string_at_offset_in`].
+    #[cfg(feature = "arena")]
+    pub fn input_name_at_offset_in(&self, offset: usize, arena: &'a Arena) -> Option<&'a BStr> {
+        if offset == 0 {
+            return None;
+        }
+
+        self.input_string_at_offset_in(offset, arena)
+    }
+
+    /// Get the string at a specific offset, like
+    /// [`Self::input_string_at_offset`], but the string is copied into `arena`
+    /// when the data is no longer borrowed from the input bytes.
+    #[cfg(feature = "arena")]
+    pub fn input_string_at_offset_in(&self, offset: usize, arena: &'a Arena) -> Option<&'a BStr> {
+        if self.r#type != DataType::StringTable {
+            return None;
+        }
+
+        match self.inner {
+            Cow::Borrowed(bytes) => string_at(bytes, offset),
+            Cow::Owned(ref bytes) => string_at(bytes, offset).map(|name| arena.alloc(name)),
+        }
     }
 
     /// Get an iterator over symbols, if and only if the data type is
     /// [`DataType::SymbolTable`].
     ///
     /// The optional `strings_section` argument is supposed to contain the
-    /// `.strtab` section, see [`File::strings_section`] to get it.
+    /// string table linked to this symbol table, e.g. `.strtab` for `.symtab`
+    /// or `.dynstr` for `.dynsym`, see [`File::strings_section_of`] to get
+    /// it. If it is `None`, the string table associated to the data is used,
+    /// if any, see [`File::read_resolved`].
     ///
-    /// [`File::strings_section`]: super::File::strings_section
+    /// [`File::strings_section_of`]: super::File::strings_section_of
+    /// [`File::read_resolved`]: super::File::read_resolved
     pub fn symbols<E>(
         &'a self,
         strings_section: Option<&'a Section<'a>>,
@@ -102,9 +236,204 @@ impl<'a> Data<'a> {
             self.inner.as_ref(),
             self.endianness,
             self.entity_size,
-            strings_section,
+            strings_section.map(|section| &section.data).or(self.strings.as_deref()),
         ))
     }
+
+    /// Get an iterator over relocations, if and only if the data type is
+    /// [`DataType::RelocationTable`].
+    pub fn relocations<E>(&'a self) -> Option<RelocationIterator<'a, E>>
+    where
+        E: ParseError<Input<'a>>,
+    {
+        if self.r#type != DataType::RelocationTable {
+            return None;
+        }
+
+        Some(RelocationIterator::new(self.inner.as_ref(), self.endianness, self.entity_size))
+    }
+
+    /// Get an iterator over symbol versions, if and only if the data type is
+    /// [`DataType::VersionSymbolTable`].
+    ///
+    /// The n-th symbol version is the version of the n-th symbol of the
+    /// associated dynamic symbol table.
+    pub fn version_symbols<E>(&'a self) -> Option<VersionSymbolIterator<'a, E>>
+    where
+        E: ParseError<Input<'a>>,
+    {
+        if self.r#type != DataType::VersionSymbolTable {
+            return None;
+        }
+
+        Some(VersionSymbolIterator::new(self.inner.as_ref(), self.endianness))
+    }
+
+    /// Get an iterator over version requirements, if and only if the data type
+    /// is [`DataType::VersionNeedTable`].
+    ///
+    /// The optional `strings_section` argument is supposed to contain the
+    /// `.dynstr` section, i.e. the section linked by the `.gnu.version_r`
+    /// section.
+    pub fn version_needs<E>(
+        &'a self,
+        strings_section: Option<&'a Section<'a>>,
+    ) -> Option<VersionNeedIterator<'a, E>>
+    where
+        E: ParseError<Input<'a>>,
+    {
+        if self.r#type != DataType::VersionNeedTable {
+            return None;
+        }
+
+        Some(VersionNeedIterator::new(self.inner.as_ref(), self.endianness, strings_section))
+    }
+
+    /// Get an iterator over dynamic entries, if and only if the data type is
+    /// [`DataType::DynamicTable`].
+    pub fn dynamic_entries<E>(&'a self) -> Option<DynamicIterator<'a, E>>
+    where
+        E: ParseError<Input<'a>>,
+    {
+        if self.r#type != DataType::DynamicTable {
+            return None;
+        }
+
+        Some(DynamicIterator::new(self.inner.as_ref(), self.endianness))
+    }
+
+    /// Get the section group, if and only if the data type is
+    /// [`DataType::Group`] and the group is valid.
+    pub fn group(&self) -> Option<Group> {
+        if self.r#type != DataType::Group {
+            return None;
+        }
+
+        let read = match self.endianness {
+            Endianness::Big => Group::read::<BigEndian, ()>(&self.inner),
+            Endianness::Little => Group::read::<LittleEndian, ()>(&self.inner),
+        };
+
+        read.ok().map(|(_, group)| group)
+    }
+
+    /// Get an iterator over the entries of an `.eh_frame` section, i.e. the
+    /// CIEs and the FDEs used to unwind the stack.
+    ///
+    /// The data is expected to be the content of an `.eh_frame` section: its
+    /// type is not checked, as it depends on the machine.
+    #[cfg(feature = "eh_frame")]
+    pub fn eh_frame_entries<E>(&'a self) -> EhFrameIterator<'a, E>
+    where
+        E: ParseError<Input<'a>>,
+    {
+        EhFrameIterator::new(self.inner.as_ref(), self.endianness)
+    }
+
+    /// Get an iterator over the raw bytes of each entry, if and only if the
+    /// data represents fixed-sized entries, i.e. it has an entity size.
+    ///
+    /// Trailing bytes that don't form a complete entry are ignored.
+    pub fn raw_entries(&self) -> Option<impl Iterator<Item = &[u8]>> {
+        let entity_size = self
+            .entity_size?
+            .get()
+            .try_into()
+            .expect("Failed to cast the entity size from `u64` to `usize`");
+
+        Some(self.inner.chunks_exact(entity_size))
+    }
+
+    /// Copy the data, if and only if the data type is
+    /// [`DataType::StringTable`], to associate it to a symbol table. Borrowed
+    /// bytes are not copied.
+    pub(crate) fn to_strings(&self) -> Option<Data<'a>> {
+        if self.r#type != DataType::StringTable {
+            return None;
+        }
+
+        Some(Self {
+            inner: self.inner.clone(),
+            r#type: DataType::StringTable,
+            endianness: self.endianness,
+            machine: self.machine,
+            entity_size: self.entity_size,
+            compressed: self.compressed,
+            no_bits_size: self.no_bits_size,
+            strings: None,
+        })
+    }
+
+    /// Get the compression header, if and only if the data is compressed
+    /// and the header is valid.
+    ///
+    /// The data is not decompressed: the header only describes the
+    /// algorithm, the size and the alignment of the uncompressed data.
+    pub fn compression_header(&self) -> Option<CompressionHeader> {
+        if !self.compressed {
+            return None;
+        }
+
+        let read = match self.endianness {
+            Endianness::Big => CompressionHeader::read::<BigEndian, ()>(&self.inner),
+            Endianness::Little => CompressionHeader::read::<LittleEndian, ()>(&self.inner),
+        };
+
+        read.ok().map(|(_, header)| header)
+    }
+
+    /// Get a reader over the bytes, e.g. to copy them into a writer with
+    /// [`io::copy`].
+    ///
+    /// The bytes are read as is: compressed bytes are not decompressed.
+    pub fn reader(&self) -> DataReader<'_> {
+        DataReader { bytes: &self.inner }
+    }
+}
+
+/// A reader over the bytes of a [`Data`], see [`Data::reader`].
+///
+/// It implements [`io::Read`] and [`io::BufRead`]: each read consumes the
+/// bytes it returns.
+#[derive(Debug)]
+pub struct DataReader<'d> {
+    /// The bytes that are not read yet.
+    bytes: &'d [u8],
+}
+
+impl io::Read for DataReader<'_> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        io::Read::read(&mut self.bytes, buffer)
+    }
+
+    fn read_to_end(&mut self, buffer: &mut Vec<u8>) -> io::Result<usize> {
+        io::Read::read_to_end(&mut self.bytes, buffer)
+    }
+}
+
+impl io::BufRead for DataReader<'_> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        io::BufRead::fill_buf(&mut self.bytes)
+    }
+
+    fn consume(&mut self, amount: usize) {
+        io::BufRead::consume(&mut self.bytes, amount)
+    }
+}
+
+/// Get the null-terminated string at a specific offset in `bytes`, if any.
+fn string_at(bytes: &[u8], offset: usize) -> Option<&BStr> {
+    let name = bytes.get(offset..)?;
+
+    name.iter().position(|c| *c == 0x00).map(|name_end| BStr::new(&name[..name_end]))
+}
+
+impl<'a> Deref for Data<'a> {
+    type Target = Cow<'a, [u8]>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
 }
 
 impl<'a> fmt::Debug for Data<'a> {
@@ -122,64 +451,88 @@ impl<'a> fmt::Debug for Data<'a> {
                 self.symbols::<VerboseError<Input>>(None).unwrap().collect::<Vec<_>>()
             )),
 
-            #[cfg(feature = "debug")]
-            DataType::ProgramData => {
-                #[cfg(feature = "debug-x86")]
-                {
-                    use iced_x86::{Decoder, DecoderOptions, FastFormatter, Instruction};
+            DataType::RelocationTable => formatter.write_fmt(format_args!(
+                "{:?} Data(..), interpreted: {:#?}",
+                self.r#type,
+                self.relocations::<VerboseError<Input>>().unwrap().collect::<Vec<_>>()
+            )),
 
-                    formatter
-                        .write_fmt(format_args!("{:?} Data(..), interpreted:", self.r#type))?;
+            DataType::VersionSymbolTable => formatter.write_fmt(format_args!(
+                "{:?} Data(..), interpreted: {:#?}",
+                self.r#type,
+                self.version_symbols::<VerboseError<Input>>().unwrap().collect::<Vec<_>>()
+            )),
 
-                    let mut decoder = Decoder::new(64, &self.inner, DecoderOptions::NONE);
-                    let mut x86_formatter = FastFormatter::new();
+            DataType::VersionNeedTable => formatter.write_fmt(format_args!(
+                "{:?} Data(..), interpreted: {:#?}",
+                self.r#type,
+                self.version_needs::<VerboseError<Input>>(None).unwrap().collect::<Vec<_>>()
+            )),
 
-                    {
-                        let options = x86_formatter.options_mut();
-                        options.set_space_after_operand_separator(true);
-                        options.set_rip_relative_addresses(true);
-                        options.set_show_symbol_address(true);
-                        options.set_uppercase_hex(false);
-                        options.set_use_hex_prefix(true);
-                    }
+            DataType::DynamicTable => formatter.write_fmt(format_args!(
+                "{:?} Data(..), interpreted: {:#?}",
+                self.r#type,
+                self.dynamic_entries::<VerboseError<Input>>().unwrap().collect::<Vec<_>>()
+            )),
 
-                    let mut output = String::new();
-                    let mut instruction = Instruction::default();
+            DataType::Group => formatter.write_fmt(format_args!(
+                "{:?} Data(..), interpreted: {:#?}",
+                self.r#type,
+                self.group()
+            )),
 
-                    while decoder.can_decode() {
-                        decoder.decode_out(&mut instruction);
-                        output.clear();
-                        x86_formatter.format(&instruction, &mut output);
-                        formatter.write_fmt(format_args!("\n{:016x} ", instruction.ip()))?;
+            #[cfg(feature = "debug-x86")]
+            DataType::ProgramData if self.machine == Machine::X86_64 => {
+                use iced_x86::{Decoder, DecoderOptions, FastFormatter, Instruction};
 
-                        let start_index = instruction.ip() as usize;
-                        let instr_bytes = &self.inner[start_index..start_index + instruction.len()];
+                formatter.write_fmt(format_args!("{:?} Data(..), interpreted:", self.r#type))?;
 
-                        for bytes in instr_bytes.iter() {
-                            formatter.write_fmt(format_args!("{bytes:02x}"))?;
-                        }
+                let mut decoder = Decoder::new(64, &self.inner, DecoderOptions::NONE);
+                let mut x86_formatter = FastFormatter::new();
 
-                        if instr_bytes.len() < 10 {
-                            for _ in 0..10 - instr_bytes.len() {
-                                formatter.write_fmt(format_args!("  "))?;
-                            }
-                        }
+                {
+                    let options = x86_formatter.options_mut();
+                    options.set_space_after_operand_separator(true);
+                    options.set_rip_relative_addresses(true);
+                    options.set_show_symbol_address(true);
+                    options.set_uppercase_hex(false);
+                    options.set_use_hex_prefix(true);
+                }
+
+                let mut output = String::new();
+                let mut instruction = Instruction::default();
 
-                        formatter.write_fmt(format_args!(" {output}"))?;
+                while decoder.can_decode() {
+                    decoder.decode_out(&mut instruction);
+                    output.clear();
+                    x86_formatter.format(&instruction, &mut output);
+                    formatter.write_fmt(format_args!("\n{:016x} ", instruction.ip()))?;
+
+                    let start_index = instruction.ip() as usize;
+                    let instr_bytes = &self.inner[start_index..start_index + instruction.len()];
+
+                    for bytes in instr_bytes.iter() {
+                        formatter.write_fmt(format_args!("{bytes:02x}"))?;
                     }
-                }
 
-                #[cfg(not(feature = "debug-x86"))]
-                {
-                    formatter.write_fmt(format_args!(
-                        "{:?} Data(..), cannot interpret them",
-                        self.r#type,
-                    ))?;
+                    if instr_bytes.len() < 10 {
+                        for _ in 0..10 - instr_bytes.len() {
+                            formatter.write_fmt(format_args!("  "))?;
+                        }
+                    }
+
+                    formatter.write_fmt(format_args!(" {output}"))?;
                 }
 
                 Ok(())
             }
 
+            #[cfg(feature = "debug")]
+            DataType::ProgramData => formatter.write_fmt(format_args!(
+                "{:?} Data(..), cannot interpret them for {:?}",
+                self.r#type, self.machine,
+            )),
+
             #[cfg_attr(feature = "debug", allow(unreachable_patterns))]
             DataType::ProgramData | DataType::Unspecified => {
                 let len = self.inner.len();
@@ -204,7 +557,48 @@ impl<'a> fmt::Debug for Data<'a> {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use super::{
+        super::{Alignment, CompressionType},
+        *,
+    };
+
+    #[test]
+    fn test_program_data_of_another_machine() {
+        // `mov x0, #42` and `ret` for AArch64.
+        let data = Data::new(
+            Cow::Borrowed(&[0x40, 0x05, 0x80, 0xd2, 0xc0, 0x03, 0x5f, 0xd6]),
+            DataType::ProgramData,
+            Endianness::Little,
+            Machine::Aarch64,
+            None,
+        );
+        let formatted = format!("{data:?}");
+
+        assert!(!formatted.contains("interpreted"));
+        assert!(!formatted.contains("rex"));
+    }
+
+    #[test]
+    fn test_no_bits_data() {
+        let data = Data::no_bits(256, Endianness::Little, Machine::X86_64);
+
+        assert!(data.is_nobits());
+        assert_eq!(data.len(), 0);
+        assert!(data.is_empty());
+        assert_eq!(data.logical_size(), 256);
+
+        let data = Data::new(
+            Cow::Borrowed(&[0x01, 0x02, 0x03]),
+            DataType::ProgramData,
+            Endianness::Little,
+            Machine::X86_64,
+            None,
+        );
+
+        assert!(!data.is_nobits());
+        assert_eq!(data.len(), 3);
+        assert_eq!(data.logical_size(), 3);
+    }
 
     #[test]
     fn test_string_at_offset() {
@@ -212,6 +606,7 @@ mod tests {
             Cow::Borrowed(&[0x0, 0x61, 0x62, 0x63, 0x0, 0x64, 0x65, 0x0, 0x66]),
             DataType::StringTable,
             Endianness::Little,
+            Machine::X86_64,
             None,
         );
 
@@ -226,5 +621,114 @@ mod tests {
         assert_eq!(data.string_at_offset(8), None);
         assert_eq!(data.string_at_offset(9), None);
         assert_eq!(data.string_at_offset(10), None);
+
+        // The offset 0 means there is no name.
+        assert_eq!(data.name_at_offset(0), None);
+        assert_eq!(data.input_name_at_offset(0), None);
+        assert_eq!(data.name_at_offset(1), Some(Cow::Borrowed(BStr::new("abc"))));
+        assert_eq!(data.input_name_at_offset(5), Some(Cow::Borrowed(BStr::new("de"))));
+    }
+
+    #[test]
+    fn test_input_string_at_offset() {
+        let input: &[u8] = b"\0abc\0";
+        let mut data = Data::new(
+            Cow::Borrowed(input),
+            DataType::StringTable,
+            Endianness::Little,
+            Machine::X86_64,
+            None,
+        );
+
+        // The string borrows the input bytes.
+        let string = data.input_string_at_offset(1);
+        assert!(matches!(
+            string,
+            Some(Cow::Borrowed(string)) if string == "abc" && string.as_ptr() == input[1..].as_ptr()
+        ));
+
+        // Once modified, the data no longer borrows the input: strings are copied.
+        data.as_mut_slice().unwrap()[1] = b'x';
+
+        let string = data.input_string_at_offset(1);
+        assert!(matches!(string, Some(Cow::Owned(string)) if string == "xbc"));
+        assert_eq!(data.input_string_at_offset(5), None);
+    }
+
+    #[test]
+    fn test_raw_entries() {
+        let data = Data::new(
+            Cow::Borrowed(&[0x0, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9, 0xa, 0xb]),
+            DataType::ProgramData,
+            Endianness::Little,
+            Machine::X86_64,
+            NonZeroU64::new(4),
+        );
+
+        assert_eq!(
+            data.raw_entries().unwrap().collect::<Vec<_>>(),
+            &[&[0x0, 0x1, 0x2, 0x3], &[0x4, 0x5, 0x6, 0x7], &[0x8, 0x9, 0xa, 0xb]]
+        );
+
+        // No entity size, no entries.
+        let data = Data::new(
+            Cow::Borrowed(&[0x0, 0x1, 0x2, 0x3]),
+            DataType::ProgramData,
+            Endianness::Little,
+            Machine::X86_64,
+            None,
+        );
+
+        assert!(data.raw_entries().is_none());
+    }
+
+    #[test]
+    fn test_compression_header() {
+        #[rustfmt::skip]
+        let bytes: &[u8] = &[
+            // Compression type.
+            0x02, 0x00, 0x00, 0x00,
+            // Reserved.
+            0x00, 0x00, 0x00, 0x00,
+            // Uncompressed size.
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // Uncompressed alignment.
+            0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // Compressed data.
+            0x28, 0xb5, 0x2f, 0xfd,
+        ];
+        let mut data = Data::new(
+            Cow::Borrowed(bytes),
+            DataType::ProgramData,
+            Endianness::Little,
+            Machine::X86_64,
+            None,
+        );
+
+        // Not compressed, no header.
+        assert!(data.compression_header().is_none());
+
+        data.compressed = true;
+
+        assert_eq!(
+            data.compression_header(),
+            Some(CompressionHeader {
+                r#type: CompressionType::Zstd,
+                size: 0x100,
+                alignment: Alignment::new(0x10).unwrap(),
+            })
+        );
+
+        // Truncated header.
+        let mut data = Data::new(
+            Cow::Borrowed(&bytes[..8]),
+            DataType::ProgramData,
+            Endianness::Little,
+            Machine::X86_64,
+            None,
+        );
+        data.compressed = true;
+
+        assert!(data.compression_header().is_none());
     }
 }
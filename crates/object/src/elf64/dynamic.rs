@@ -0,0 +1,256 @@
+use std::{io, marker::PhantomData, result::Result as StdResult};
+
+use crate::{
+    combinators::*, BigEndian, Endianness, Input, LittleEndian, Number, Read, Result, Write,
+};
+
+/// A dynamic entry, i.e. an entry of the `.dynamic` section.
+///
+/// The dynamic entries tell the dynamic linker how to load an executable or a
+/// shared object, e.g. which shared objects are needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dynamic {
+    /// The tag, which controls the interpretation of the value.
+    pub tag: DynamicTag,
+    /// The value, either an integer or a virtual address depending on the tag.
+    pub value: u64,
+}
+
+impl Dynamic {
+    /// Size, in bytes, of a dynamic entry.
+    pub const SIZE: usize = 16;
+}
+
+impl Read for Dynamic {
+    fn read<'r, N, E>(input: Input<'r>) -> Result<'r, Self, E>
+    where
+        N: Number,
+        E: ParseError<Input<'r>>,
+    {
+        let (input, (tag, value)) = tuple((N::read_u64, N::read_u64))(input)?;
+
+        Ok((input, Self { tag: tag.into(), value }))
+    }
+}
+
+impl Write for Dynamic {
+    fn write<N, B>(&self, buffer: &mut B) -> io::Result<()>
+    where
+        N: Number,
+        B: io::Write,
+    {
+        buffer.write_all(&N::write_u64(self.tag.into()))?;
+        buffer.write_all(&N::write_u64(self.value))
+    }
+}
+
+/// Dynamic entry tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DynamicTag {
+    /// Mark the end of the dynamic entries.
+    Null,
+    /// The value is an offset, in the string table, to the name of a needed
+    /// shared object.
+    Needed,
+    /// The value is the size, in bytes, of the relocations of the PLT.
+    PltRelocationsSize,
+    /// The value is the virtual address of the GOT slots used by the PLT,
+    /// i.e. `.got.plt`.
+    PltGot,
+    /// The value is the virtual address of the symbol hash table, i.e.
+    /// `.hash`.
+    Hash,
+    /// The value is the virtual address of the string table, i.e. `.dynstr`.
+    StringTable,
+    /// The value is the virtual address of the dynamic symbol table.
+    SymbolTable,
+    /// The value is the size, in bytes, of the string table.
+    StringTableSize,
+    /// The value is the size, in bytes, of an entry of the dynamic symbol
+    /// table.
+    SymbolEntrySize,
+    /// The value is the virtual address of a relocation table with addends.
+    Rela,
+    /// The value is an offset, in the string table, to the name of the shared
+    /// object itself.
+    SharedObjectName,
+    /// The value is an offset, in the string table, to the search paths of
+    /// the shared objects, separated by `:`. It is superseded by
+    /// [`Self::RunPath`].
+    RPath,
+    /// The value is an offset, in the string table, to the search paths of
+    /// the shared objects, separated by `:`. Unlike [`Self::RPath`], the
+    /// paths are searched after the ones of the `LD_LIBRARY_PATH` environment
+    /// variable.
+    RunPath,
+    /// The value is the type of the relocations of the PLT, i.e.
+    /// [`Self::Rela`].
+    PltRelocationsType,
+    /// The value is the virtual address of the relocations of the PLT, i.e.
+    /// `.rela.plt`.
+    JumpRelocations,
+    /// The value is the virtual address of the array of constructors, i.e.
+    /// `.init_array`.
+    InitArray,
+    /// The value is the virtual address of the array of destructors, i.e.
+    /// `.fini_array`.
+    FiniArray,
+    /// The value is the size, in bytes, of the array of constructors.
+    InitArraySize,
+    /// The value is the size, in bytes, of the array of destructors.
+    FiniArraySize,
+    /// Any other tag, not interpreted by `weld`.
+    Other(u64),
+}
+
+impl From<u64> for DynamicTag {
+    fn from(value: u64) -> Self {
+        match value {
+            0x00 => Self::Null,
+            0x01 => Self::Needed,
+            0x02 => Self::PltRelocationsSize,
+            0x03 => Self::PltGot,
+            0x04 => Self::Hash,
+            0x05 => Self::StringTable,
+            0x06 => Self::SymbolTable,
+            0x07 => Self::Rela,
+            0x0a => Self::StringTableSize,
+            0x0b => Self::SymbolEntrySize,
+            0x0e => Self::SharedObjectName,
+            0x0f => Self::RPath,
+            0x14 => Self::PltRelocationsType,
+            0x17 => Self::JumpRelocations,
+            0x19 => Self::InitArray,
+            0x1a => Self::FiniArray,
+            0x1b => Self::InitArraySize,
+            0x1c => Self::FiniArraySize,
+            0x1d => Self::RunPath,
+            value => Self::Other(value),
+        }
+    }
+}
+
+impl From<DynamicTag> for u64 {
+    fn from(value: DynamicTag) -> Self {
+        match value {
+            DynamicTag::Null => 0x00,
+            DynamicTag::Needed => 0x01,
+            DynamicTag::PltRelocationsSize => 0x02,
+            DynamicTag::PltGot => 0x03,
+            DynamicTag::Hash => 0x04,
+            DynamicTag::StringTable => 0x05,
+            DynamicTag::SymbolTable => 0x06,
+            DynamicTag::Rela => 0x07,
+            DynamicTag::StringTableSize => 0x0a,
+            DynamicTag::SymbolEntrySize => 0x0b,
+            DynamicTag::SharedObjectName => 0x0e,
+            DynamicTag::RPath => 0x0f,
+            DynamicTag::PltRelocationsType => 0x14,
+            DynamicTag::JumpRelocations => 0x17,
+            DynamicTag::InitArray => 0x19,
+            DynamicTag::FiniArray => 0x1a,
+            DynamicTag::InitArraySize => 0x1b,
+            DynamicTag::FiniArraySize => 0x1c,
+            DynamicTag::RunPath => 0x1d,
+            DynamicTag::Other(value) => value,
+        }
+    }
+}
+
+/// An iterator producing [`Dynamic`]s.
+///
+/// It stops after the entry tagged [`DynamicTag::Null`], if any.
+pub struct DynamicIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    input: Input<'a>,
+    endianness: Endianness,
+    _phantom: PhantomData<E>,
+}
+
+impl<'a, E> DynamicIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    pub(super) fn new(input: Input<'a>, endianness: Endianness) -> Self {
+        Self { input, endianness, _phantom: PhantomData }
+    }
+}
+
+impl<'a, E> Iterator for DynamicIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    type Item = StdResult<Dynamic, Err<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.input.is_empty() {
+            return None;
+        }
+
+        let read = match self.endianness {
+            Endianness::Big => Dynamic::read::<BigEndian, E>(self.input),
+            Endianness::Little => Dynamic::read::<LittleEndian, E>(self.input),
+        };
+
+        match read {
+            Ok((next_input, dynamic)) => {
+                self.input = if dynamic.tag == DynamicTag::Null { &[] } else { next_input };
+
+                Some(Ok(dynamic))
+            }
+
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dynamic() {
+        #[rustfmt::skip]
+        let input: &[u8] = &[
+            // Tag.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+            // Value.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2a,
+        ];
+        let dynamic = Dynamic { tag: DynamicTag::Needed, value: 42 };
+
+        let mut buffer = Vec::new();
+        dynamic.write::<BigEndian, _>(&mut buffer).unwrap();
+
+        assert_eq!(buffer, input);
+        assert_eq!(Dynamic::read::<BigEndian, ()>(input), Ok((&[] as &[u8], dynamic)));
+    }
+
+    #[test]
+    fn test_dynamic_iterator() {
+        let mut input = Vec::new();
+
+        for dynamic in [
+            Dynamic { tag: DynamicTag::Needed, value: 1 },
+            Dynamic { tag: DynamicTag::Other(0x6fff_fffb), value: 2 },
+            Dynamic { tag: DynamicTag::Null, value: 0 },
+            // Padding after the end.
+            Dynamic { tag: DynamicTag::Null, value: 0 },
+        ] {
+            dynamic.write::<LittleEndian, _>(&mut input).unwrap();
+        }
+
+        assert_eq!(
+            DynamicIterator::<()>::new(&input, Endianness::Little)
+                .collect::<StdResult<Vec<_>, _>>()
+                .unwrap(),
+            &[
+                Dynamic { tag: DynamicTag::Needed, value: 1 },
+                Dynamic { tag: DynamicTag::Other(0x6fff_fffb), value: 2 },
+                Dynamic { tag: DynamicTag::Null, value: 0 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,525 @@
+use std::{marker::PhantomData, result::Result as StdResult};
+
+use bstr::BStr;
+
+use crate::{combinators::*, BigEndian, Endianness, Input, LittleEndian, Number, Result};
+
+/// An entry of the `.eh_frame` section, used to unwind the stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EhFrameEntry<'a> {
+    /// A Common Information Entry, shared by several FDEs.
+    Cie(CommonInformationEntry<'a>),
+    /// A Frame Description Entry, describing how to unwind a function.
+    Fde(FrameDescriptionEntry<'a>),
+}
+
+/// A Common Information Entry (CIE) of the `.eh_frame` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommonInformationEntry<'a> {
+    /// Offset of the entry in the section.
+    pub offset: u64,
+    /// Length of the entry, excluding the length field itself.
+    pub length: u64,
+    /// The CIE id, always 0 in `.eh_frame`.
+    pub id: u32,
+    /// The version of the frame information, 1 or 3.
+    pub version: u8,
+    /// The augmentation string, e.g. `zR`.
+    pub augmentation: &'a BStr,
+    /// The factor applied to the advance location instructions.
+    pub code_alignment_factor: u64,
+    /// The factor applied to the offset instructions.
+    pub data_alignment_factor: i64,
+    /// The register holding the return address.
+    pub return_address_register: u64,
+    /// The encoding of the addresses of the FDEs using this CIE, given by the
+    /// `R` augmentation, `DW_EH_PE_absptr` (0) otherwise.
+    pub pointer_encoding: u8,
+    /// The initial call frame instructions.
+    pub instructions: &'a [u8],
+}
+
+/// A Frame Description Entry (FDE) of the `.eh_frame` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameDescriptionEntry<'a> {
+    /// Offset of the entry in the section.
+    pub offset: u64,
+    /// Length of the entry, excluding the length field itself.
+    pub length: u64,
+    /// The distance from the CIE pointer field to the CIE of this FDE.
+    pub cie_pointer: u32,
+    /// Offset of the CIE of this FDE in the section.
+    pub cie_offset: u64,
+    /// The pointer encoding of the CIE of this FDE, i.e.
+    /// [`CommonInformationEntry::pointer_encoding`].
+    pub pointer_encoding: u8,
+    /// Offset of the initial location field in the section, e.g. to resolve
+    /// a PC-relative initial location.
+    pub initial_location_offset: u64,
+    /// The address of the first instruction described by this FDE, as encoded
+    /// by the [`CommonInformationEntry::pointer_encoding`]: e.g. if it is
+    /// PC-relative, it is relative to the address of the field itself.
+    pub initial_location: i64,
+    /// The number of bytes of instructions described by this FDE.
+    pub address_range: u64,
+    /// The call frame instructions.
+    pub instructions: &'a [u8],
+}
+
+/// Pointer encoding format: the value is omitted.
+const DW_EH_PE_OMIT: u8 = 0xff;
+
+/// Iterator over entries of an `.eh_frame` section, see
+/// [`Data::eh_frame_entries`][super::Data::eh_frame_entries].
+///
+/// The iterator stops at the end of the section, or at the zero terminator.
+pub struct EhFrameIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    section: Input<'a>,
+    offset: usize,
+    endianness: Endianness,
+    _phantom: PhantomData<E>,
+}
+
+impl<'a, E> EhFrameIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    pub(super) fn new(section: Input<'a>, endianness: Endianness) -> Self {
+        Self { section, offset: 0, endianness, _phantom: PhantomData }
+    }
+}
+
+impl<'a, E> Iterator for EhFrameIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    type Item = StdResult<EhFrameEntry<'a>, Err<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.section.len() {
+            return None;
+        }
+
+        let read = match self.endianness {
+            Endianness::Big => read_entry::<BigEndian, E>(self.section, self.offset),
+            Endianness::Little => read_entry::<LittleEndian, E>(self.section, self.offset),
+        };
+
+        match read {
+            Ok(Some((next_offset, entry))) => {
+                self.offset = next_offset;
+
+                Some(Ok(entry))
+            }
+
+            // The zero terminator.
+            Ok(None) => {
+                self.offset = self.section.len();
+
+                None
+            }
+
+            Err(err) => {
+                self.offset = self.section.len();
+
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Read the entry at `offset` in the section, and return the offset of the
+/// next entry, or `None` if it is the zero terminator.
+fn read_entry<'a, N, E>(
+    section: Input<'a>,
+    offset: usize,
+) -> StdResult<Option<(usize, EhFrameEntry<'a>)>, Err<E>>
+where
+    N: Number,
+    E: ParseError<Input<'a>>,
+{
+    let input = section.get(offset..).unwrap_or_default();
+    let (input, length) = N::read_u32(input)?;
+
+    // A length of `0xffff_ffff` announces a 64-bit extended length.
+    let (input, length, length_size) = match length {
+        0 => return Ok(None),
+        0xffff_ffff => {
+            let (input, length) = N::read_u64(input)?;
+
+            (input, length, 12)
+        }
+        length => (input, length as u64, 4),
+    };
+
+    let body = usize::try_from(length)
+        .ok()
+        .and_then(|length| input.get(..length))
+        .ok_or_else(|| Err::Failure(E::from_error_kind(input, ErrorKind::Eof)))?;
+    let next_offset = offset + length_size + body.len();
+    let (body_rest, id) = N::read_u32(body)?;
+
+    let entry = if id == 0 {
+        EhFrameEntry::Cie(read_cie::<N, E>(offset as u64, length, body_rest)?)
+    } else {
+        // The CIE pointer is relative to its own field.
+        let cie = (offset + length_size)
+            .checked_sub(id as usize)
+            .and_then(|cie_offset| match read_entry::<N, E>(section, cie_offset) {
+                Ok(Some((_, EhFrameEntry::Cie(cie)))) => Some(cie),
+                _ => None,
+            })
+            .ok_or_else(|| Err::Failure(E::from_error_kind(body, ErrorKind::Verify)))?;
+
+        EhFrameEntry::Fde(read_fde::<N, E>(
+            offset as u64,
+            length,
+            (offset + length_size + 4) as u64,
+            id,
+            &cie,
+            body_rest,
+        )?)
+    };
+
+    Ok(Some((next_offset, entry)))
+}
+
+/// Read a CIE, after its id.
+fn read_cie<'a, N, E>(
+    offset: u64,
+    length: u64,
+    input: Input<'a>,
+) -> StdResult<CommonInformationEntry<'a>, Err<E>>
+where
+    N: Number,
+    E: ParseError<Input<'a>>,
+{
+    let (input, version) = N::read_u8(input)?;
+
+    let augmentation_end = input
+        .iter()
+        .position(|byte| *byte == 0x00)
+        .ok_or_else(|| Err::Failure(E::from_error_kind(input, ErrorKind::Eof)))?;
+    let augmentation = BStr::new(&input[..augmentation_end]);
+    let input = &input[augmentation_end + 1..];
+
+    // The legacy `eh` augmentation is followed by a pointer.
+    let (input, _) =
+        if augmentation.starts_with(b"eh") { skip(8usize)(input)? } else { (input, input) };
+
+    let (input, code_alignment_factor) = read_uleb128(input)?;
+    let (input, data_alignment_factor) = read_sleb128(input)?;
+    let (input, return_address_register) = if version == 1 {
+        N::read_u8(input).map(|(input, register)| (input, register as u64))?
+    } else {
+        read_uleb128(input)?
+    };
+
+    let mut pointer_encoding = 0;
+
+    // The `z` augmentation announces the length of the augmentation data.
+    let instructions = match augmentation.strip_prefix(b"z") {
+        Some(augmentation) => {
+            let (input, augmentation_length) = read_uleb128(input)?;
+            let (mut augmentation_data, instructions) = usize::try_from(augmentation_length)
+                .ok()
+                .filter(|length| *length <= input.len())
+                .map(|length| input.split_at(length))
+                .ok_or_else(|| Err::Failure(E::from_error_kind(input, ErrorKind::Eof)))?;
+
+            for character in augmentation.iter().copied() {
+                match character {
+                    b'R' => {
+                        (augmentation_data, pointer_encoding) = N::read_u8(augmentation_data)?;
+                    }
+                    b'P' => {
+                        let (data, encoding) = N::read_u8(augmentation_data)?;
+                        (augmentation_data, _) = read_encoded::<N, E>(data, encoding)?;
+                    }
+                    b'L' => {
+                        (augmentation_data, _) = N::read_u8(augmentation_data)?;
+                    }
+                    _ => {}
+                }
+            }
+
+            instructions
+        }
+        None => input,
+    };
+
+    Ok(CommonInformationEntry {
+        offset,
+        length,
+        id: 0,
+        version,
+        augmentation,
+        code_alignment_factor,
+        data_alignment_factor,
+        return_address_register,
+        pointer_encoding,
+        instructions,
+    })
+}
+
+/// Read a FDE, after its CIE pointer.
+fn read_fde<'a, N, E>(
+    offset: u64,
+    length: u64,
+    initial_location_offset: u64,
+    cie_pointer: u32,
+    cie: &CommonInformationEntry,
+    input: Input<'a>,
+) -> StdResult<FrameDescriptionEntry<'a>, Err<E>>
+where
+    N: Number,
+    E: ParseError<Input<'a>>,
+{
+    let (input, initial_location) = read_encoded::<N, E>(input, cie.pointer_encoding)?;
+    // The range is not an address: only the format of the encoding applies.
+    let (input, address_range) = read_encoded::<N, E>(input, cie.pointer_encoding & 0x0f)?;
+
+    let (input, _) = if cie.augmentation.starts_with(b"z") {
+        let (input, augmentation_length) = read_uleb128(input)?;
+
+        skip(augmentation_length as usize)(input)?
+    } else {
+        (input, input)
+    };
+
+    Ok(FrameDescriptionEntry {
+        offset,
+        length,
+        cie_pointer,
+        cie_offset: cie.offset,
+        pointer_encoding: cie.pointer_encoding,
+        initial_location_offset,
+        initial_location,
+        address_range: address_range as u64,
+        instructions: input,
+    })
+}
+
+/// Read a value encoded with a `DW_EH_PE_*` pointer encoding. The
+/// application, e.g. PC-relative, is not applied.
+fn read_encoded<'a, N, E>(input: Input<'a>, encoding: u8) -> Result<'a, i64, E>
+where
+    N: Number,
+    E: ParseError<Input<'a>>,
+{
+    if encoding == DW_EH_PE_OMIT {
+        return Ok((input, 0));
+    }
+
+    match encoding & 0x0f {
+        // `DW_EH_PE_absptr`.
+        0x00 => N::read_u64(input).map(|(input, value)| (input, value as i64)),
+        // `DW_EH_PE_uleb128`.
+        0x01 => read_uleb128(input).map(|(input, value)| (input, value as i64)),
+        // `DW_EH_PE_udata2`.
+        0x02 => N::read_u16(input).map(|(input, value)| (input, value as i64)),
+        // `DW_EH_PE_udata4`.
+        0x03 => N::read_u32(input).map(|(input, value)| (input, value as i64)),
+        // `DW_EH_PE_udata8`.
+        0x04 => N::read_u64(input).map(|(input, value)| (input, value as i64)),
+        // `DW_EH_PE_sleb128`.
+        0x09 => read_sleb128(input),
+        // `DW_EH_PE_sdata2`.
+        0x0a => N::read_u16(input).map(|(input, value)| (input, value as i16 as i64)),
+        // `DW_EH_PE_sdata4`.
+        0x0b => N::read_u32(input).map(|(input, value)| (input, value as i32 as i64)),
+        // `DW_EH_PE_sdata8`.
+        0x0c => N::read_u64(input).map(|(input, value)| (input, value as i64)),
+        _ => Err(Err::Failure(E::from_error_kind(input, ErrorKind::Switch))),
+    }
+}
+
+/// Read an unsigned LEB128 number. Bits beyond 64 bits are dropped.
+fn read_uleb128<'a, E>(input: Input<'a>) -> Result<'a, u64, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    let (input, value, _) = read_leb128(input)?;
+
+    Ok((input, value))
+}
+
+/// Read a signed LEB128 number. Bits beyond 64 bits are dropped.
+fn read_sleb128<'a, E>(input: Input<'a>) -> Result<'a, i64, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    let (input, value, shift) = read_leb128(input)?;
+
+    // Extend the sign bit, i.e. the 7th bit of the last byte.
+    let value = if shift < 64 && value & (1 << (shift - 1)) != 0 {
+        (value | (u64::MAX << shift)) as i64
+    } else {
+        value as i64
+    };
+
+    Ok((input, value))
+}
+
+/// Read the 7-bit groups of a LEB128 number, and return the raw value and the
+/// number of bits read.
+fn read_leb128<'a, E>(input: Input<'a>) -> StdResult<(Input<'a>, u64, u32), Err<E>>
+where
+    E: ParseError<Input<'a>>,
+{
+    let mut value = 0u64;
+    let mut shift = 0u32;
+
+    for (index, byte) in input.iter().enumerate() {
+        if shift < 64 {
+            value |= u64::from(byte & 0x7f) << shift;
+        }
+
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Ok((&input[index + 1..], value, shift));
+        }
+    }
+
+    Err(Err::Error(E::from_error_kind(input, ErrorKind::Eof)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leb128() {
+        assert_eq!(read_uleb128::<()>(&[0x02]), Ok((&[][..], 2)));
+        assert_eq!(read_uleb128::<()>(&[0xe5, 0x8e, 0x26, 0xff]), Ok((&[0xff][..], 624_485)));
+        assert_eq!(read_sleb128::<()>(&[0x7f]), Ok((&[][..], -1)));
+        assert_eq!(read_sleb128::<()>(&[0x78]), Ok((&[][..], -8)));
+        assert_eq!(read_sleb128::<()>(&[0xc0, 0xbb, 0x78]), Ok((&[][..], -123_456)));
+        assert!(read_uleb128::<()>(&[0x80]).is_err());
+    }
+
+    #[test]
+    fn test_eh_frame_entries() {
+        #[rustfmt::skip]
+        let input: &[u8] = &[
+            // CIE.
+            // Length.
+            0x14, 0x00, 0x00, 0x00,
+            // CIE id.
+            0x00, 0x00, 0x00, 0x00,
+            // Version.
+            0x01,
+            // Augmentation, `zR`.
+            0x7a, 0x52, 0x00,
+            // Code alignment factor, 1.
+            0x01,
+            // Data alignment factor, -8.
+            0x78,
+            // Return address register, 16.
+            0x10,
+            // Augmentation length.
+            0x01,
+            // Pointer encoding, `DW_EH_PE_pcrel | DW_EH_PE_sdata4`.
+            0x1b,
+            // Instructions: `DW_CFA_def_cfa: r7 ofs 8`, `DW_CFA_offset: r16 at cfa-8`.
+            0x0c, 0x07, 0x08, 0x90, 0x01, 0x00, 0x00,
+
+            // FDE.
+            // Length.
+            0x10, 0x00, 0x00, 0x00,
+            // CIE pointer, to offset 0.
+            0x1c, 0x00, 0x00, 0x00,
+            // Initial location, -0x20.
+            0xe0, 0xff, 0xff, 0xff,
+            // Address range.
+            0x10, 0x00, 0x00, 0x00,
+            // Augmentation length.
+            0x00,
+            // Instructions.
+            0x00, 0x00, 0x00,
+
+            // Terminator.
+            0x00, 0x00, 0x00, 0x00,
+            // Ignored.
+            0xff,
+        ];
+
+        let entries = EhFrameIterator::<()>::new(input, Endianness::Little)
+            .collect::<StdResult<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            entries,
+            [
+                EhFrameEntry::Cie(CommonInformationEntry {
+                    offset: 0,
+                    length: 0x14,
+                    id: 0,
+                    version: 1,
+                    augmentation: BStr::new("zR"),
+                    code_alignment_factor: 1,
+                    data_alignment_factor: -8,
+                    return_address_register: 16,
+                    pointer_encoding: 0x1b,
+                    instructions: &[0x0c, 0x07, 0x08, 0x90, 0x01, 0x00, 0x00],
+                }),
+                EhFrameEntry::Fde(FrameDescriptionEntry {
+                    offset: 0x18,
+                    length: 0x10,
+                    cie_pointer: 0x1c,
+                    cie_offset: 0,
+                    pointer_encoding: 0x1b,
+                    initial_location_offset: 0x20,
+                    initial_location: -0x20,
+                    address_range: 0x10,
+                    instructions: &[0x00, 0x00, 0x00],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_eh_frame_extended_length() {
+        #[rustfmt::skip]
+        let input: &[u8] = &[
+            // Extended length.
+            0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a,
+            // CIE id.
+            0x00, 0x00, 0x00, 0x00,
+            // Version.
+            0x03,
+            // Augmentation.
+            0x00,
+            // Code alignment factor, data alignment factor, return address
+            // register.
+            0x01, 0x78, 0x10,
+            // Instructions.
+            0x00,
+        ];
+
+        let mut entries = EhFrameIterator::<()>::new(input, Endianness::Big);
+
+        assert!(matches!(
+            entries.next(),
+            Some(Ok(EhFrameEntry::Cie(CommonInformationEntry {
+                length: 0x0a,
+                version: 3,
+                pointer_encoding: 0,
+                instructions: &[0x00],
+                ..
+            })))
+        ));
+        assert!(entries.next().is_none());
+
+        // The length goes beyond the section.
+        let mut entries = EhFrameIterator::<()>::new(&input[..20], Endianness::Big);
+
+        assert!(matches!(entries.next(), Some(Err(Err::Failure(())))));
+        assert!(entries.next().is_none());
+    }
+}
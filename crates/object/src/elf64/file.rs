@@ -1,10 +1,20 @@
+use std::{borrow::Cow, path::Path, result::Result as StdResult};
+
+use bstr::BStr;
 use weld_object_macros::ReadWrite;
 
-use super::{Address, Program, Section, SectionIndex, SectionType};
-use crate::{combinators::*, BigEndian, Input, LittleEndian, Number, Read, Result};
+use super::{
+    Address, Alignment, DynamicTag, Program, ProgramType, Section, SectionIndex, SectionType,
+    Symbol, SymbolType,
+};
+#[cfg(feature = "arena")]
+use crate::arena::Arena;
+use crate::{
+    combinators::*, errors::InvalidHeader, BigEndian, Input, LittleEndian, Number, Read, Result,
+};
 
 /// Object file.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct File<'a> {
     /// Endianess of the object file.
     pub endianness: Endianness,
@@ -26,18 +36,63 @@ pub struct File<'a> {
     pub sections: Vec<Section<'a>>,
     /// Section index of the section names.
     pub section_index_for_section_names: SectionIndex,
+    /// Path of the object file, if it has been read with
+    /// [`Self::read_named`]. It is useful for diagnostics.
+    pub path: Option<&'a Path>,
 }
 
 impl<'a> File<'a> {
-    const MAGIC: &'static [u8; 4] = &[0x7f, b'E', b'L', b'F'];
-    const ELF64: &'static [u8; 1] = &[0x2];
+    pub(super) const MAGIC: &'static [u8; 4] = &[0x7f, b'E', b'L', b'F'];
+    pub(super) const ELF64: &'static [u8; 1] = &[0x2];
+
+    /// Size, in bytes, of the file header.
+    pub const HEADER_SIZE: usize = 64;
 
+    /// Read a file.
+    ///
+    /// If the file doesn't start with the ELF magic number, or if its class
+    /// isn't 64-bit, it fails with an error of kind [`ErrorKind::Tag`] built
+    /// from an [`InvalidHeader`]. If the file header is truncated, or if the program headers or the
+    /// section headers don't fit in the file, it fails with an error of kind
+    /// [`ErrorKind::Eof`]. If the program headers or the section headers
+    /// overlap the file header, it fails with an error of kind
+    /// [`ErrorKind::Verify`]. If the alignment of a program header or of a
+    /// section header isn't a power of two, it fails with an error of kind
+    /// [`ErrorKind::Verify`] built from an [`InvalidHeader`].
     pub fn read<E>(input: Input<'a>) -> Result<'a, Self, E>
     where
-        E: ParseError<Input<'a>>,
+        E: ParseError<Input<'a>> + FromExternalError<Input<'a>, InvalidHeader>,
     {
         let file = input;
 
+        // The magic number and the class are checked first, so that a file
+        // which isn't an Elf64 file is reported as such, even if it is short.
+        let length = input.len().min(Self::MAGIC.len());
+
+        if input[..length] != Self::MAGIC[..length] {
+            let mut first_bytes = [0; 4];
+            first_bytes[..length].copy_from_slice(&input[..length]);
+
+            return Err(Err::Failure(E::from_external_error(
+                file,
+                ErrorKind::Tag,
+                InvalidHeader::NotAnElfFile { first_bytes, length },
+            )));
+        }
+
+        if let Some(&class) = input.get(Self::MAGIC.len()).filter(|class| **class != Self::ELF64[0])
+        {
+            return Err(Err::Failure(E::from_external_error(
+                file,
+                ErrorKind::Tag,
+                InvalidHeader::UnsupportedClass { class },
+            )));
+        }
+
+        // The file header must be complete.
+        skip(Self::HEADER_SIZE)(file)
+            .map_err(|_: Err<E>| Err::Failure(E::from_error_kind(file, ErrorKind::Eof)))?;
+
         let (input, (_magic, _class, endianness)) =
             tuple((tag(Self::MAGIC), tag(Self::ELF64), Endianness::read::<LittleEndian, _>))(
                 input,
@@ -51,6 +106,49 @@ impl<'a> File<'a> {
         }
     }
 
+    /// Read a file, like [`Self::read`], and remember its path, see
+    /// [`Self::path`].
+    pub fn read_named<E>(path: &'a Path, input: Input<'a>) -> Result<'a, Self, E>
+    where
+        E: ParseError<Input<'a>> + FromExternalError<Input<'a>, InvalidHeader>,
+    {
+        let (input, mut file) = Self::read(input)?;
+        file.path = Some(path);
+
+        Ok((input, file))
+    }
+
+    /// Read a file, like [`Self::read`], then fetch the section names, see
+    /// [`Self::fetch_section_names`], and associate each symbol table to its
+    /// string table, so that the symbols are named, see [`Self::symbols`].
+    pub fn read_resolved<E>(input: Input<'a>) -> Result<'a, Self, E>
+    where
+        E: ParseError<Input<'a>> + FromExternalError<Input<'a>, InvalidHeader>,
+    {
+        let (input, mut file) = Self::read(input)?;
+        file.fetch_section_names();
+
+        for index in 0..file.sections.len() {
+            let section = &file.sections[index];
+
+            if !matches!(
+                section.r#type,
+                SectionType::SymbolTable | SectionType::DynamicLoaderSymbolTable
+            ) {
+                continue;
+            }
+
+            let strings = section
+                .link
+                .resolve(&file.sections)
+                .and_then(|strings_section| strings_section.data.to_strings());
+
+            file.sections[index].data.strings = strings.map(Box::new);
+        }
+
+        Ok((input, file))
+    }
+
     fn read_with_endianness<N, E>(
         file: Input<'a>,
         input: Input<'a>,
@@ -58,7 +156,7 @@ impl<'a> File<'a> {
     ) -> Result<'a, Self, E>
     where
         N: Number,
-        E: ParseError<Input<'a>>,
+        E: ParseError<Input<'a>> + FromExternalError<Input<'a>, InvalidHeader>,
     {
         // `fh` stands for `file_header`.
         // `ph` stands for `program_header`.
@@ -82,7 +180,7 @@ impl<'a> File<'a> {
                 ph_number,
                 sh_entry_size,
                 sh_number,
-                section_index_for_section_names,
+                mut section_index_for_section_names,
             ),
         ) = tuple((
             Version::read::<N, _>,
@@ -103,28 +201,76 @@ impl<'a> File<'a> {
             <SectionIndex as Read<u16>>::read::<N, _>,
         ))(input)?;
 
+        Self::check_headers_table(file, ph_offset, ph_entry_size, ph_number.into())?;
+
         let mut programs = Vec::with_capacity(ph_number as usize);
 
         // Parse program headers.
         if ph_entry_size > 0 {
-            for ph_slice in file[ph_offset.into()..]
+            for (index, ph_slice) in file[ph_offset.into()..]
                 .chunks_exact(ph_entry_size as usize)
                 .take(ph_number as usize)
+                .enumerate()
             {
-                let (_, ph) = Program::read::<N, _>(ph_slice, file)?;
+                Self::check_alignment::<N, _>(ph_slice, Program::ALIGNMENT_OFFSET, |alignment| {
+                    InvalidHeader::ProgramAlignment { index, alignment }
+                })?;
+
+                let (_, ph) = Program::read::<N, _>(ph_slice, file, machine)?;
                 programs.push(ph);
             }
         }
 
-        let mut sections = Vec::with_capacity(sh_number as usize);
+        // When the number of sections, or the index of the section names, doesn't
+        // fit in the file header, the actual value is stored in the first
+        // section header, which is otherwise a null section: the number of
+        // sections in `sh_size`, and the index of the section names in
+        // `sh_link`.
+        let mut sh_number = usize::from(sh_number);
+
+        // A file without section headers, e.g. a stripped executable, has no
+        // offset for them: the other values are meaningless.
+        if sh_offset == Address(0) {
+            sh_number = 0;
+            section_index_for_section_names = SectionIndex::Undefined;
+        }
+
+        if sh_offset != Address(0)
+            && sh_entry_size > 0
+            && (sh_number == 0 || section_index_for_section_names == SectionIndex::Extended)
+        {
+            let (_, (_, first_sh_size, first_sh_link)) =
+                tuple((skip(32usize), N::read_u64, <SectionIndex as Read<u32>>::read::<N, _>))(
+                    file.get(sh_offset.into()..).unwrap_or_default(),
+                )?;
+
+            if sh_number == 0 {
+                sh_number = first_sh_size
+                    .try_into()
+                    .expect("Failed to cast the number of sections from `u64` to `usize`");
+            }
+
+            if section_index_for_section_names == SectionIndex::Extended {
+                section_index_for_section_names = first_sh_link;
+            }
+        }
+
+        Self::check_headers_table(file, sh_offset, sh_entry_size, sh_number)?;
+
+        let mut sections = Vec::with_capacity(sh_number);
 
         // Parse section headers.
         if sh_entry_size > 0 {
-            for sh_slice in file[sh_offset.into()..]
+            for (index, sh_slice) in file[sh_offset.into()..]
                 .chunks_exact(sh_entry_size as usize)
-                .take(sh_number as usize)
+                .take(sh_number)
+                .enumerate()
             {
-                let (_, sh) = Section::read::<N, _>(sh_slice, file)?;
+                Self::check_alignment::<N, _>(sh_slice, Section::ALIGNMENT_OFFSET, |alignment| {
+                    InvalidHeader::SectionAlignment { index, alignment }
+                })?;
+
+                let (_, sh) = Section::read::<N, _>(sh_slice, file, machine)?;
                 sections.push(sh);
             }
         }
@@ -140,23 +286,115 @@ impl<'a> File<'a> {
             programs,
             sections,
             section_index_for_section_names,
+            path: None,
         };
 
         Ok((&[], file))
     }
 
+    /// Check that a table of `number` headers of `entry_size` bytes each,
+    /// starting at `offset`, fits in the file and doesn't overlap the file
+    /// header.
+    fn check_headers_table<E>(
+        file: Input<'a>,
+        offset: Address,
+        entry_size: u16,
+        number: usize,
+    ) -> StdResult<(), Err<E>>
+    where
+        E: ParseError<Input<'a>>,
+    {
+        if entry_size == 0 || number == 0 {
+            return Ok(());
+        }
+
+        let end = usize::try_from(offset.0)
+            .ok()
+            .and_then(|offset| usize::from(entry_size).checked_mul(number)?.checked_add(offset));
+
+        match end {
+            Some(end) if end <= file.len() => {
+                if offset.0 < Self::HEADER_SIZE as u64 {
+                    Err(Err::Failure(E::from_error_kind(&file[offset.into()..], ErrorKind::Verify)))
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(Err::Failure(E::from_error_kind(
+                file.get(offset.into()..).unwrap_or(&file[file.len()..]),
+                ErrorKind::Eof,
+            ))),
+        }
+    }
+
+    /// Check the alignment of a program header or of a section header, found
+    /// at `offset` in the header, so that an invalid alignment is reported
+    /// with the index of the header. A truncated header is left to the
+    /// header parser.
+    fn check_alignment<N, E>(
+        header: Input<'a>,
+        offset: usize,
+        invalid_header: impl FnOnce(u64) -> InvalidHeader,
+    ) -> StdResult<(), Err<E>>
+    where
+        N: Number,
+        E: ParseError<Input<'a>> + FromExternalError<Input<'a>, InvalidHeader>,
+    {
+        let Ok((_, alignment)) = N::read_u64::<()>(header.get(offset..).unwrap_or_default()) else {
+            return Ok(());
+        };
+
+        match Alignment::new(alignment) {
+            Some(_) => Ok(()),
+            None => Err(Err::Failure(E::from_external_error(
+                header,
+                ErrorKind::Verify,
+                invalid_header(alignment),
+            ))),
+        }
+    }
+
+    /// Check whether the file has no section headers, e.g. a stripped
+    /// executable, which only has program headers.
+    pub fn is_section_headerless(&self) -> bool {
+        self.sections.is_empty()
+    }
+
     /// Fetch all known section names.
     ///
     /// For each section, this method will find its name in the appropriate
-    /// section[^1], and will **copy** the bytes representing its name.
+    /// section[^1]. Names borrow the input bytes; they are copied only if the
+    /// section holding them has been modified. It does nothing if the file
+    /// has no section headers, see [`Self::is_section_headerless`].
     ///
     /// [^1]: See [`Self::section_index_for_section_names`].
     pub fn fetch_section_names(&mut self) {
+        self.fetch_section_names_with(|section_names, offset| {
+            section_names.data.input_name_at_offset(offset)
+        });
+    }
+
+    /// Fetch all known section names, like [`Self::fetch_section_names`], but
+    /// the names of a modified section are copied into `arena` instead of
+    /// being cloned for each section: names are always borrowed, either from
+    /// the input bytes or from `arena`.
+    #[cfg(feature = "arena")]
+    pub fn fetch_section_names_in(&mut self, arena: &'a Arena) {
+        self.fetch_section_names_with(|section_names, offset| {
+            section_names.data.input_name_at_offset_in(offset, arena).map(Cow::Borrowed)
+        });
+    }
+
+    fn fetch_section_names_with<F>(&mut self, name_at_offset: F)
+    where
+        F: Fn(&Section<'a>, usize) -> Option<Cow<'a, BStr>>,
+    {
         if let SectionIndex::Ok(index) = self.section_index_for_section_names {
             // Validate the `index`.
-            if self.sections.is_empty()
-                || index >= self.sections.len()
-                || self.sections[index].r#type != SectionType::StringTable
+            if self
+                .section_index_for_section_names
+                .resolve(&self.sections)
+                .is_none_or(|section| section.r#type != SectionType::StringTable)
             {
                 return;
             }
@@ -167,10 +405,7 @@ impl<'a> File<'a> {
                 .expect("The section for section names must be present");
 
             for section in left_sections.iter_mut().chain(right_sections.iter_mut()) {
-                section.name = section_names
-                    .data
-                    .string_at_offset(section.name_offset.into())
-                    .map(|name| name.into_owned());
+                section.name = name_at_offset(section_names, section.name_offset.into());
             }
         }
     }
@@ -187,10 +422,167 @@ impl<'a> File<'a> {
                     r#type: SectionType::StringTable,
                     name: Some(section_name),
                     ..
-                } if *section_name == ".strtab"
+                } if **section_name == ".strtab"
             )
         })
     }
+
+    /// Get the string table of a symbol table, i.e. the section at the index
+    /// [`Section::link`] of the symbol table, e.g. `.strtab` for `.symtab`, or
+    /// `.dynstr` for `.dynsym`.
+    ///
+    /// If the link is missing, [`Self::strings_section`] is used for a
+    /// [`SectionType::SymbolTable`] only.
+    pub fn strings_section_of(&'a self, symbol_table: &Section<'a>) -> Option<&'a Section<'a>> {
+        match symbol_table.link {
+            SectionIndex::Ok(_) => symbol_table
+                .link
+                .resolve(&self.sections)
+                .filter(|section| section.r#type == SectionType::StringTable),
+            _ if symbol_table.r#type == SectionType::SymbolTable => self.strings_section(),
+            _ => None,
+        }
+    }
+
+    /// Get a section by its name.
+    ///
+    /// Section names must have been fetched, see
+    /// [`Self::fetch_section_names`].
+    pub fn section_by_name(&self, name: &str) -> Option<&Section<'a>> {
+        self.sections.iter().find(|section| {
+            section.name.as_deref().is_some_and(|section_name| section_name == name)
+        })
+    }
+
+    /// Get the raw bytes of a section by its name.
+    ///
+    /// Section names must have been fetched, see
+    /// [`Self::fetch_section_names`].
+    pub fn section_data(&self, name: &str) -> Option<&[u8]> {
+        self.section_by_name(name).map(|section| &**section.data)
+    }
+
+    /// Get the symbol of the entry point.
+    ///
+    /// It is the symbol whose value is the entry point if any, otherwise the
+    /// symbol named `_start`, e.g. for relocatable files.
+    pub fn entry_symbol(&'a self) -> Option<Symbol<'a>> {
+        let symbol_table =
+            self.sections.iter().find(|section| section.r#type == SectionType::SymbolTable)?;
+        let strings_section = self.strings_section_of(symbol_table);
+        let symbols = || {
+            symbol_table
+                .data
+                .symbols::<()>(strings_section)
+                .into_iter()
+                .flatten()
+                .filter_map(|symbol| symbol.ok())
+                .filter(|symbol| {
+                    symbol.name.is_some()
+                        && !matches!(symbol.r#type, SymbolType::Section | SymbolType::File)
+                })
+        };
+
+        self.entry_point
+            .and_then(|entry_point| symbols().find(|symbol| symbol.value == entry_point))
+            .or_else(|| {
+                symbols().find(|symbol| symbol.name.as_deref() == Some(BStr::new("_start")))
+            })
+    }
+
+    /// Get the symbols of the symbol table.
+    ///
+    /// The symbols are named only if the symbol table has been associated to
+    /// its string table, see [`Self::read_resolved`]. The symbols stop at the
+    /// first one that can't be parsed.
+    pub fn symbols(&'a self) -> impl Iterator<Item = Symbol<'a>> {
+        self.sections
+            .iter()
+            .find(|section| section.r#type == SectionType::SymbolTable)
+            .and_then(|symbol_table| symbol_table.data.symbols::<()>(None))
+            .into_iter()
+            .flatten()
+            .filter_map(|symbol| symbol.ok())
+    }
+
+    /// Get the symbols of the dynamic symbol table, i.e. `.dynsym`.
+    ///
+    /// The symbols are named through the string table of the dynamic symbol
+    /// table, see [`Self::strings_section_of`]. The symbols stop at the first
+    /// one that can't be parsed.
+    pub fn dynamic_symbols(&'a self) -> impl Iterator<Item = Symbol<'a>> {
+        self.sections
+            .iter()
+            .find(|section| section.r#type == SectionType::DynamicLoaderSymbolTable)
+            .and_then(|symbol_table| {
+                symbol_table.data.symbols::<()>(self.strings_section_of(symbol_table))
+            })
+            .into_iter()
+            .flatten()
+            .filter_map(|symbol| symbol.ok())
+    }
+
+    /// Get the symbols of the symbol table, paired with the section in which
+    /// they are defined.
+    ///
+    /// The section is `None` if the symbol is undefined, absolute or common.
+    /// The symbols stop at the first one that can't be parsed.
+    pub fn symbols_with_sections(
+        &'a self,
+    ) -> impl Iterator<Item = (Symbol<'a>, Option<&'a Section<'a>>)> {
+        let symbol_table =
+            self.sections.iter().find(|section| section.r#type == SectionType::SymbolTable);
+        let strings_section =
+            symbol_table.and_then(|symbol_table| self.strings_section_of(symbol_table));
+
+        symbol_table
+            .and_then(|symbol_table| symbol_table.data.symbols::<()>(strings_section))
+            .into_iter()
+            .flatten()
+            .filter_map(|symbol| symbol.ok())
+            .map(|symbol| {
+                let section = symbol.section_index_where_symbol_is_defined.resolve(&self.sections);
+
+                (symbol, section)
+            })
+    }
+
+    /// Get the path of the interpreter, i.e. the dynamic loader, like
+    /// `/lib64/ld-linux-x86-64.so.2`.
+    ///
+    /// The path is held by the program of type [`ProgramType::Interpreter`],
+    /// and is null-terminated. Only dynamically-linked executables have one.
+    pub fn interpreter(&self) -> Option<Cow<'_, BStr>> {
+        let program =
+            self.programs.iter().find(|program| program.r#type == ProgramType::Interpreter)?;
+        let data: &[u8] = &program.data;
+        let path = data.iter().position(|byte| *byte == 0x00).map_or(data, |end| &data[..end]);
+
+        Some(Cow::Borrowed(BStr::new(path)))
+    }
+
+    /// Get the name of a shared object, i.e. its `SONAME`, like `libc.so.6`.
+    ///
+    /// The name is held by the dynamic entry tagged
+    /// [`DynamicTag::SharedObjectName`], in the section of type
+    /// [`SectionType::DynamicLinkingTable`]. Only shared objects can have one.
+    pub fn shared_object_name(&self) -> Option<Cow<'_, BStr>> {
+        let dynamic_section = self
+            .sections
+            .iter()
+            .find(|section| section.r#type == SectionType::DynamicLinkingTable)?;
+        let SectionIndex::Ok(strings_index) = dynamic_section.link else {
+            return None;
+        };
+        let strings_section = self.sections.get(strings_index)?;
+
+        dynamic_section
+            .data
+            .dynamic_entries::<()>()?
+            .map_while(StdResult::ok)
+            .find(|dynamic| dynamic.tag == DynamicTag::SharedObjectName)
+            .and_then(|dynamic| strings_section.data.string_at_offset(dynamic.value as usize))
+    }
 }
 
 /// Byte order of the file.
@@ -418,3 +810,28 @@ pub enum Machine {
     /// [Berkeley Packet Filter](https://en.wikipedia.org/wiki/Berkeley_Packet_Filter).
     Bpf = 0xf7,
 }
+
+#[cfg(feature = "target-lexicon")]
+impl From<Machine> for Option<target_lexicon::Architecture> {
+    /// Get the architecture of a machine, if it is a 64-bit machine known by
+    /// `target-lexicon`.
+    ///
+    /// The endianness is not part of the machine: the architecture with the
+    /// canonical endianness is returned, e.g. `aarch64` and not `aarch64_be`.
+    fn from(machine: Machine) -> Self {
+        use target_lexicon::{
+            Aarch64Architecture, Architecture, Mips64Architecture, Riscv64Architecture,
+        };
+
+        Some(match machine {
+            Machine::X86_64 => Architecture::X86_64,
+            Machine::Aarch64 => Architecture::Aarch64(Aarch64Architecture::Aarch64),
+            Machine::RiscV => Architecture::Riscv64(Riscv64Architecture::Riscv64),
+            Machine::PowerPc64 => Architecture::Powerpc64,
+            Machine::IbmS390 => Architecture::S390x,
+            Machine::SparcV9 => Architecture::Sparc64,
+            Machine::Mips => Architecture::Mips64(Mips64Architecture::Mips64),
+            _ => return None,
+        })
+    }
+}
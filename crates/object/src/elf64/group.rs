@@ -0,0 +1,123 @@
+use std::io;
+
+use enumflags2::{bitflags, BitFlags};
+
+use crate::{combinators::*, Input, Number, Read, Result, Write};
+
+/// A section group, i.e. the content of a section of type
+/// [`SectionType::Group`][super::SectionType::Group].
+///
+/// The member sections of a group are kept or discarded together. The
+/// signature of the group is the name of the symbol at the index
+/// [`Section::information`][super::Section::information] of the symbol table
+/// at the index [`Section::link`][super::Section::link].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    /// The flags of the group.
+    pub flags: GroupFlags,
+    /// Indices of the member sections.
+    pub sections: Vec<u32>,
+}
+
+impl Read for Group {
+    fn read<'a, N, E>(input: Input<'a>) -> Result<'a, Self, E>
+    where
+        N: Number,
+        E: ParseError<Input<'a>>,
+    {
+        let (mut input, flags) = GroupFlags::read::<N, _>(input)?;
+        let mut sections = Vec::with_capacity(input.len() / 4);
+
+        while !input.is_empty() {
+            let (next_input, section_index) = N::read_u32(input)?;
+            input = next_input;
+            sections.push(section_index);
+        }
+
+        Ok((input, Self { flags, sections }))
+    }
+}
+
+impl Write for Group {
+    fn write<N, B>(&self, buffer: &mut B) -> io::Result<()>
+    where
+        N: Number,
+        B: io::Write,
+    {
+        self.flags.write::<N, _>(buffer)?;
+
+        for section_index in &self.sections {
+            buffer.write_all(&N::write_u32(*section_index))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Group flag.
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum GroupFlag {
+    /// The group is a COMDAT group: only one group with the same signature is
+    /// kept when linking.
+    Comdat = 0x01,
+}
+
+/// Group flags.
+pub type GroupFlags = BitFlags<GroupFlag>;
+
+impl Read for GroupFlags {
+    fn read<'a, N, E>(input: Input<'a>) -> Result<'a, Self, E>
+    where
+        N: Number,
+        E: ParseError<Input<'a>>,
+    {
+        let (input, flags) = N::read_u32(input)?;
+        let flags = Self::from_bits(flags)
+            .map_err(|_| Err::Error(E::from_error_kind(input, ErrorKind::Alt)))?;
+
+        Ok((input, flags))
+    }
+}
+
+impl Write for GroupFlags {
+    fn write<N, B>(&self, buffer: &mut B) -> io::Result<()>
+    where
+        N: Number,
+        B: io::Write,
+    {
+        buffer.write_all(&N::write_u32(self.bits()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group() {
+        #[rustfmt::skip]
+        let input: &[u8] = &[
+            // Flags.
+            0x00, 0x00, 0x00, 0x01,
+            // Sections.
+            0x00, 0x00, 0x00, 0x04,
+            0x00, 0x00, 0x00, 0x05,
+        ];
+
+        assert_read_write!(
+            Group: Read<()> + Write<()> {
+                bytes_value(big_endian) = input,
+                rust_value = Group { flags: GroupFlag::Comdat.into(), sections: vec![4, 5] },
+            }
+        );
+    }
+
+    #[test]
+    fn test_group_with_unknown_flags() {
+        let input: &[u8] = &[0x00, 0x00, 0x00, 0x02];
+
+        assert!(Group::read::<crate::BigEndian, ()>(input).is_err());
+    }
+}
@@ -0,0 +1,26 @@
+/// Compute the SysV hash of a symbol name, as used by the `.hash` section.
+///
+/// The buckets of the hash table are indexed by this hash, modulo the number
+/// of buckets.
+pub fn elf_hash(name: &[u8]) -> u32 {
+    name.iter().fold(0u32, |hash, byte| {
+        let hash = (hash << 4).wrapping_add(u32::from(*byte));
+        let high = hash & 0xf000_0000;
+
+        (hash ^ (high >> 24)) & !high
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elf_hash() {
+        assert_eq!(elf_hash(b""), 0);
+        assert_eq!(elf_hash(b"foo"), 0x6d5f);
+        assert_eq!(elf_hash(b"exit"), 0x6cf04);
+        assert_eq!(elf_hash(b"printf"), 0x77905a6);
+        assert_eq!(elf_hash(b"_start"), 0x66aa894);
+    }
+}
@@ -6,17 +6,35 @@ use nom::Err::Error;
 
 use crate::{combinators::*, Input, Number, Read, Result, Write};
 
+mod builder;
+mod compression;
 mod data;
+mod dynamic;
+#[cfg(feature = "eh_frame")]
+mod eh_frame;
 mod file;
+mod group;
+mod hash;
 mod program;
+mod relocation;
 mod section;
 mod symbol;
+mod version;
 
+pub use builder::*;
+pub use compression::*;
 pub use data::*;
+pub use dynamic::*;
+#[cfg(feature = "eh_frame")]
+pub use eh_frame::*;
 pub use file::*;
+pub use group::*;
+pub use hash::*;
 pub use program::*;
+pub use relocation::*;
 pub use section::*;
 pub use symbol::*;
+pub use version::*;
 
 /// An address within the file.
 #[repr(transparent)]
@@ -131,12 +149,89 @@ impl Add for Address {
     }
 }
 
+/// A signed address or offset within the file, e.g. a relocation addend.
+///
+/// It's encoded as a two's complement 64-bit integer.
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct SignedAddress(pub i64);
+
+impl Read<u64> for SignedAddress {
+    fn read<'a, N, E>(input: Input<'a>) -> Result<'a, Self, E>
+    where
+        N: Number,
+        E: ParseError<Input<'a>>,
+    {
+        let (input, address) = N::read_u64(input)?;
+
+        Ok((input, SignedAddress(i64::from_ne_bytes(address.to_ne_bytes()))))
+    }
+}
+
+impl Write<u64> for SignedAddress {
+    fn write<N, B>(&self, buffer: &mut B) -> io::Result<()>
+    where
+        N: Number,
+        B: io::Write,
+    {
+        buffer.write_all(&N::write_u64(u64::from_ne_bytes(self.0.to_ne_bytes())))
+    }
+}
+
+impl fmt::Debug for SignedAddress {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.0 < 0 {
+            write!(formatter, "-0x{:08x}", self.0.unsigned_abs())
+        } else {
+            write!(formatter, "0x{:08x}", self.0)
+        }
+    }
+}
+
+impl fmt::Display for SignedAddress {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, formatter)
+    }
+}
+
 /// An alignment value.
 ///
 /// It's guaranteed to be a non-zero power of two, encoded in a `u64`.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
-pub struct Alignment(pub Option<NonZeroU64>);
+pub struct Alignment(Option<NonZeroU64>);
+
+impl Alignment {
+    /// Create a new alignment. `0` means there is no alignment constraint;
+    /// any other value must be a power of two, otherwise `None` is returned.
+    pub fn new(value: u64) -> Option<Self> {
+        match NonZeroU64::new(value) {
+            None => Some(Self(None)),
+            Some(alignment) if alignment.is_power_of_two() => Some(Self(Some(alignment))),
+            Some(_) => None,
+        }
+    }
+
+    /// Get the alignment, if there is an alignment constraint.
+    pub fn get(&self) -> Option<NonZeroU64> {
+        self.0
+    }
+
+    /// Round `value` up to the next multiple of the alignment. Without an
+    /// alignment constraint, `value` is returned as is.
+    pub fn align_up(&self, value: u64) -> u64 {
+        match self.0 {
+            Some(alignment) => value.next_multiple_of(alignment.get()),
+            None => value,
+        }
+    }
+
+    /// Check whether `value` is a multiple of the alignment. Without an
+    /// alignment constraint, any value is aligned.
+    pub fn is_aligned(&self, value: u64) -> bool {
+        self.0.is_none_or(|alignment| value.is_multiple_of(alignment.get()))
+    }
+}
 
 impl Read for Alignment {
     fn read<'a, N, E>(input: Input<'a>) -> Result<'a, Self, E>
@@ -145,19 +240,10 @@ impl Read for Alignment {
         E: ParseError<Input<'a>>,
     {
         let (next_input, alignment) = N::read_u64(input)?;
+        let alignment =
+            Self::new(alignment).ok_or(Error(E::from_error_kind(input, ErrorKind::Digit)))?;
 
-        let alignment = if alignment != 0 {
-            if !alignment.is_power_of_two() {
-                return Err(Error(E::from_error_kind(input, ErrorKind::Digit)));
-            }
-
-            // SAFETY: We just checked that there's no `0`.
-            Some(unsafe { NonZeroU64::new_unchecked(alignment) })
-        } else {
-            None
-        };
-
-        Ok((next_input, Self(alignment)))
+        Ok((next_input, alignment))
     }
 }
 
@@ -173,12 +259,46 @@ impl Write for Alignment {
 
 #[cfg(test)]
 mod tests {
+    use std::borrow::Cow;
+
+    use bstr::BStr;
     use nom::error::VerboseError;
 
     use super::*;
     use crate::BigEndian;
 
-    const EXIT_FILE: &'static [u8] = include_bytes!("../../tests/fixtures/exit_elf_amd64.o");
+    const EXIT_FILE: &[u8] = include_bytes!("../../tests/fixtures/exit_elf_amd64.o");
+    const EXIT_EXECUTABLE_FILE: &[u8] = include_bytes!("../../tests/fixtures/exit_elf_amd64");
+    const EXIT_DYNAMIC_FILE: &[u8] = include_bytes!("../../tests/fixtures/exit_dynamic_elf_amd64");
+    const EXIT_SHARED_FILE: &[u8] = include_bytes!("../../tests/fixtures/libexit_elf_amd64.so");
+    const EXIT_HEADERLESS_FILE: &[u8] =
+        include_bytes!("../../tests/fixtures/exit_headerless_elf_amd64");
+
+    #[test]
+    fn test_alignment_new() {
+        assert_eq!(Alignment::new(0), Some(Alignment(None)));
+        assert_eq!(Alignment::new(1), Some(Alignment(NonZeroU64::new(1))));
+        assert_eq!(Alignment::new(512), Some(Alignment(NonZeroU64::new(512))));
+        assert_eq!(Alignment::new(3), None);
+        assert_eq!(Alignment::new(0x1001), None);
+    }
+
+    #[test]
+    fn test_alignment_align_up() {
+        assert_eq!(Alignment::new(8).unwrap().align_up(13), 16);
+        assert_eq!(Alignment::new(8).unwrap().align_up(16), 16);
+        assert_eq!(Alignment::new(8).unwrap().align_up(0), 0);
+        assert_eq!(Alignment::new(1).unwrap().align_up(13), 13);
+        assert_eq!(Alignment::new(0).unwrap().align_up(13), 13);
+    }
+
+    #[test]
+    fn test_alignment_is_aligned() {
+        assert!(Alignment::new(8).unwrap().is_aligned(16));
+        assert!(!Alignment::new(8).unwrap().is_aligned(13));
+        assert!(Alignment::new(1).unwrap().is_aligned(13));
+        assert!(Alignment::new(0).unwrap().is_aligned(13));
+    }
 
     #[test]
     fn test_address() {
@@ -215,13 +335,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_signed_address() {
+        // Negative.
+        assert_read_write!(
+            SignedAddress: Read<u64> + Write<u64> {
+                bytes_value(auto_endian) = -16i64,
+                rust_value = SignedAddress(-16),
+            }
+        );
+
+        // Positive.
+        assert_read_write!(
+            SignedAddress: Read<u64> + Write<u64> {
+                bytes_value(auto_endian) = 42i64,
+                rust_value = SignedAddress(42),
+            }
+        );
+
+        assert_eq!(format!("{}", SignedAddress(-16)), "-0x00000010");
+    }
+
     #[test]
     fn test_alignment() {
         // No alignment.
         assert_read_write!(
             Alignment: Read<()> + Write<()> {
                 bytes_value(auto_endian) = 0u64,
-                rust_value = Alignment(None),
+                rust_value = Alignment::new(0).unwrap(),
             }
         );
 
@@ -229,7 +370,7 @@ mod tests {
         assert_read_write!(
             Alignment: Read<()> + Write<()> {
                 bytes_value(auto_endian) = 512u64,
-                rust_value = Alignment(Some(NonZeroU64::new(512).unwrap())),
+                rust_value = Alignment::new(512).unwrap(),
             }
         );
 
@@ -240,6 +381,527 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_interpreter() {
+        let (_, file) = File::read::<()>(EXIT_DYNAMIC_FILE).unwrap();
+
+        assert_eq!(file.interpreter().as_deref(), Some(BStr::new("/lib64/ld-linux-x86-64.so.2")));
+
+        // A relocatable object has no interpreter.
+        let (_, file) = File::read::<()>(EXIT_FILE).unwrap();
+
+        assert_eq!(file.interpreter(), None);
+    }
+
+    #[test]
+    fn test_shared_object_name() {
+        let (_, file) = File::read::<()>(EXIT_SHARED_FILE).unwrap();
+
+        assert_eq!(file.r#type, FileType::SharedObject);
+        assert_eq!(file.shared_object_name().as_deref(), Some(BStr::new("libexit.so")));
+
+        // An executable has a dynamic table, but no name.
+        let (_, file) = File::read::<()>(EXIT_DYNAMIC_FILE).unwrap();
+
+        assert_eq!(file.shared_object_name(), None);
+    }
+
+    #[test]
+    fn test_section_data_and_entry_symbol() {
+        let (_, mut file) = File::read::<()>(EXIT_FILE).unwrap();
+        file.fetch_section_names();
+
+        // `mov edi, 42; mov eax, 60; syscall`.
+        assert_eq!(
+            file.section_data(".text"),
+            Some(&[0xbf, 0x2a, 0x00, 0x00, 0x00, 0xb8, 0x3c, 0x00, 0x00, 0x00, 0x0f, 0x05][..])
+        );
+        assert_eq!(file.section_data(".unknown"), None);
+
+        // A relocatable file has no entry point: the symbol is found by name.
+        assert_eq!(file.entry_point, None);
+        assert_eq!(
+            file.entry_symbol().and_then(|symbol| symbol.name.map(|name| name.into_owned())),
+            Some("_start".into())
+        );
+
+        // An executable file has an entry point.
+        let (_, mut file) = File::read::<()>(EXIT_EXECUTABLE_FILE).unwrap();
+        file.fetch_section_names();
+
+        let entry_symbol = file.entry_symbol().unwrap();
+
+        assert_eq!(Some(entry_symbol.value), file.entry_point);
+        assert_eq!(entry_symbol.name.as_deref(), Some(BStr::new("_start")));
+    }
+
+    #[test]
+    fn test_symbols_with_sections() {
+        let (_, mut file) = File::read::<()>(EXIT_FILE).unwrap();
+        file.fetch_section_names();
+
+        let symbols = file.symbols_with_sections().collect::<Vec<_>>();
+
+        // `_start` is defined in `.text`.
+        let (_, section) = symbols
+            .iter()
+            .find(|(symbol, _)| symbol.name.as_deref() == Some(BStr::new("_start")))
+            .unwrap();
+
+        assert_eq!(section.and_then(|section| section.name.as_deref()), Some(BStr::new(".text")));
+
+        // The null symbol is undefined.
+        assert!(symbols[0].1.is_none());
+    }
+
+    #[test]
+    fn test_strings_section_of() {
+        let (_, mut file) = File::read::<()>(EXIT_SHARED_FILE).unwrap();
+        file.fetch_section_names();
+
+        let symbol_table = file.section_by_name(".symtab").unwrap();
+        let dynamic_symbol_table = file.section_by_name(".dynsym").unwrap();
+
+        // Each symbol table has its own string table.
+        assert_eq!(
+            file.strings_section_of(symbol_table).and_then(|section| section.name.as_deref()),
+            Some(BStr::new(".strtab"))
+        );
+        assert_eq!(
+            file.strings_section_of(dynamic_symbol_table)
+                .and_then(|section| section.name.as_deref()),
+            Some(BStr::new(".dynstr"))
+        );
+
+        // `.dynsym` names are resolved via `.dynstr`.
+        let names = |strings_section| {
+            dynamic_symbol_table
+                .data
+                .symbols::<()>(strings_section)
+                .unwrap()
+                .map(|symbol| symbol.unwrap().name.map(|name| name.into_owned()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            names(file.strings_section_of(dynamic_symbol_table)),
+            [None, Some("_start".into())]
+        );
+
+        // `.strtab` gives the wrong names.
+        assert_ne!(
+            names(file.strings_section()),
+            names(file.strings_section_of(dynamic_symbol_table))
+        );
+
+        assert_eq!(
+            file.dynamic_symbols()
+                .map(|symbol| symbol.name.map(|name| name.into_owned()))
+                .collect::<Vec<_>>(),
+            [None, Some("_start".into())]
+        );
+    }
+
+    #[cfg(feature = "target-lexicon")]
+    #[test]
+    fn test_machine_architecture() {
+        use target_lexicon::{Aarch64Architecture, Architecture};
+
+        assert_eq!(Option::<Architecture>::from(Machine::X86_64), Some(Architecture::X86_64));
+        assert_eq!(
+            Option::<Architecture>::from(Machine::Aarch64),
+            Some(Architecture::Aarch64(Aarch64Architecture::Aarch64))
+        );
+        assert_eq!(Option::<Architecture>::from(Machine::Vax), None);
+    }
+
+    #[test]
+    fn test_read_named() {
+        let path = std::path::Path::new("exit_elf_amd64.o");
+
+        let (_, file) = File::read_named::<()>(path, EXIT_FILE).unwrap();
+        assert_eq!(file.path, Some(path));
+
+        let (_, file) = File::read::<()>(EXIT_FILE).unwrap();
+        assert_eq!(file.path, None);
+    }
+
+    #[test]
+    fn test_read_resolved() {
+        let (_, file) = File::read_resolved::<()>(EXIT_FILE).unwrap();
+
+        // Section names are fetched.
+        assert!(file.section_by_name(".text").is_some());
+
+        // Symbols are named, without having to find the string table.
+        let symbol = file
+            .symbols()
+            .find(|symbol| symbol.name.as_deref() == Some(BStr::new("_start")))
+            .unwrap();
+
+        assert_eq!(symbol.binding, SymbolBinding::Global);
+
+        // With the low-level reader, symbols are not named.
+        let (_, file) = File::read::<()>(EXIT_FILE).unwrap();
+
+        assert!(file.symbols().all(|symbol| symbol.name.is_none()));
+    }
+
+    #[test]
+    fn test_section_names_borrow_the_input() {
+        let (_, mut file) = File::read::<()>(EXIT_FILE).unwrap();
+        file.fetch_section_names();
+
+        let text_section = file.section_by_name(".text").unwrap();
+
+        // The name is not copied: it points inside the input bytes.
+        match &text_section.name {
+            Some(Cow::Borrowed(name)) => {
+                assert_eq!(*name, ".text");
+                assert!(EXIT_FILE.as_ptr_range().contains(&name.as_ptr()));
+            }
+            name => panic!("Expected a borrowed section name, got {name:?}"),
+        }
+    }
+
+    #[test]
+    fn test_extended_section_number_and_index() {
+        let (_, file) = File::read::<()>(EXIT_FILE).unwrap();
+        let sh_offset = u64::from_le_bytes(EXIT_FILE[40..48].try_into().unwrap()) as usize;
+        let SectionIndex::Ok(section_names_index) = file.section_index_for_section_names else {
+            panic!("The section names index must be valid");
+        };
+
+        // Move the number of sections and the index of the section names in
+        // the first section header.
+        let mut input = EXIT_FILE.to_vec();
+        input[60..62].copy_from_slice(&0u16.to_le_bytes());
+        input[62..64].copy_from_slice(&0xffffu16.to_le_bytes());
+        input[sh_offset + 32..sh_offset + 40]
+            .copy_from_slice(&(file.sections.len() as u64).to_le_bytes());
+        input[sh_offset + 40..sh_offset + 44]
+            .copy_from_slice(&(section_names_index as u32).to_le_bytes());
+
+        let (_, extended_file) = File::read::<()>(&input).unwrap();
+
+        assert_eq!(extended_file.sections.len(), file.sections.len());
+        assert_eq!(
+            extended_file.section_index_for_section_names,
+            SectionIndex::Ok(section_names_index)
+        );
+    }
+
+    #[test]
+    fn test_section_headerless() {
+        let (_, executable_file) = File::read::<()>(EXIT_EXECUTABLE_FILE).unwrap();
+        let (_, mut file) = File::read::<()>(EXIT_HEADERLESS_FILE).unwrap();
+        file.fetch_section_names();
+
+        assert!(file.is_section_headerless());
+        assert!(!executable_file.is_section_headerless());
+        assert_eq!(file.section_index_for_section_names, SectionIndex::Undefined);
+        assert_eq!(file.strings_section(), None);
+        assert_eq!(file.entry_point, executable_file.entry_point);
+        assert_eq!(file.programs.len(), executable_file.programs.len());
+
+        for (program, executable_program) in file.programs.iter().zip(&executable_file.programs) {
+            assert_eq!(program.r#type, executable_program.r#type);
+            assert_eq!(program.virtual_address, executable_program.virtual_address);
+        }
+
+        // The number of section headers is meaningless without their offset.
+        let mut input = EXIT_HEADERLESS_FILE.to_vec();
+        input[60..62].copy_from_slice(&3u16.to_le_bytes());
+        input[58..60].copy_from_slice(&(Section::SIZE as u16).to_le_bytes());
+
+        assert!(File::read::<()>(&input).unwrap().1.is_section_headerless());
+    }
+
+    #[test]
+    fn test_allocations_when_parsing() {
+        use weld_counting_allocator::count_allocations;
+
+        // A baseline, to check that the allocations don't grow unnoticed: the
+        // sections and the programs are collected, but their data and names
+        // borrow the input.
+        let (_, allocations) = count_allocations(|| {
+            let (_, mut file) = File::read::<()>(EXIT_FILE).unwrap();
+            file.fetch_section_names();
+
+            file
+        });
+
+        assert!(allocations <= 1, "parsing performs {allocations} allocations");
+
+        let (_, allocations) = count_allocations(|| {
+            let (_, mut file) = File::read::<()>(EXIT_EXECUTABLE_FILE).unwrap();
+            file.fetch_section_names();
+
+            file
+        });
+
+        assert!(allocations <= 2, "parsing performs {allocations} allocations");
+    }
+
+    #[cfg(feature = "arena")]
+    #[test]
+    fn test_fetch_section_names_in() {
+        use crate::arena::Arena;
+
+        let arena = Arena::new();
+        let (_, mut file) = File::read::<()>(EXIT_FILE).unwrap();
+        file.fetch_section_names_in(&arena);
+
+        // The names borrow the input: nothing is allocated in the arena.
+        assert_eq!(file.sections[1].name, Some(Cow::Borrowed(BStr::new(".text"))));
+        assert_eq!(arena.allocated_bytes(), 0);
+
+        // Once the section names are modified, the names are copied into the
+        // arena, and they are still borrowed.
+        let SectionIndex::Ok(index) = file.section_index_for_section_names else {
+            panic!("The file must have a section for section names");
+        };
+        file.sections[index].data.as_mut_slice().unwrap();
+        file.fetch_section_names_in(&arena);
+
+        assert_eq!(file.sections[1].name, Some(Cow::Borrowed(BStr::new(".text"))));
+        assert!(file
+            .sections
+            .iter()
+            .all(|section| matches!(section.name, None | Some(Cow::Borrowed(_)))));
+        assert!(arena.allocated_bytes() > 0);
+    }
+
+    #[test]
+    fn test_truncated_file_header() {
+        let input = &EXIT_FILE[..20];
+
+        assert_eq!(
+            File::read::<nom::error::Error<Input>>(input).map(|_| ()),
+            Err(nom::Err::Failure(nom::error::Error::new(input, ErrorKind::Eof)))
+        );
+    }
+
+    #[test]
+    fn test_not_an_elf_file() {
+        use crate::errors::{FileError, InvalidHeader};
+
+        let input: &[u8] = b"#!/bin/sh\nexit 42\n";
+        let error = File::read::<FileError>(input).map(|_| ()).unwrap_err();
+
+        assert_eq!(
+            error,
+            nom::Err::Failure(FileError {
+                input,
+                code: ErrorKind::Tag,
+                invalid_header: Some(InvalidHeader::NotAnElfFile {
+                    first_bytes: *b"#!/b",
+                    length: 4
+                }),
+            })
+        );
+
+        let nom::Err::Failure(FileError { invalid_header: Some(invalid_header), .. }) = error
+        else {
+            unreachable!();
+        };
+
+        assert_eq!(
+            invalid_header.to_string(),
+            "the file starts with `#!/b` instead of the ELF magic number `\\x7fELF`"
+        );
+
+        // A file shorter than the magic number.
+        assert!(matches!(
+            File::read::<FileError>(b"\x00\x01"),
+            Err(nom::Err::Failure(FileError {
+                invalid_header: Some(InvalidHeader::NotAnElfFile {
+                    first_bytes: [0x00, 0x01, 0x00, 0x00],
+                    length: 2
+                }),
+                ..
+            }))
+        ));
+
+        // A truncated file with the magic number is a truncated file header.
+        assert!(matches!(
+            File::read::<FileError>(&EXIT_FILE[..3]),
+            Err(nom::Err::Failure(FileError { code: ErrorKind::Eof, invalid_header: None, .. }))
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_class() {
+        use crate::errors::{FileError, InvalidHeader};
+
+        // A 32-bit file.
+        let mut input = EXIT_FILE.to_vec();
+        input[4] = 0x01;
+
+        let error = File::read::<FileError>(&input).map(|_| ()).unwrap_err();
+        let nom::Err::Failure(FileError { invalid_header: Some(invalid_header), .. }) = error
+        else {
+            unreachable!();
+        };
+
+        assert_eq!(invalid_header, InvalidHeader::UnsupportedClass { class: 1 });
+        assert_eq!(
+            invalid_header.to_string(),
+            "the file is a 32-bit ELF file, but only 64-bit ELF files are supported"
+        );
+    }
+
+    #[test]
+    fn test_headers_table_out_of_bounds() {
+        // The section headers start past the end of the file.
+        let mut input = EXIT_FILE.to_vec();
+        input[40..48].copy_from_slice(&(EXIT_FILE.len() as u64 + 0x100).to_le_bytes());
+
+        assert_eq!(
+            File::read::<nom::error::Error<Input>>(&input).map(|_| ()),
+            Err(nom::Err::Failure(nom::error::Error::new(&input[input.len()..], ErrorKind::Eof)))
+        );
+
+        // The section headers start inside the file, but end past it.
+        let mut input = EXIT_FILE.to_vec();
+        let sh_offset = EXIT_FILE.len() - Section::SIZE;
+        input[40..48].copy_from_slice(&(sh_offset as u64).to_le_bytes());
+
+        assert_eq!(
+            File::read::<nom::error::Error<Input>>(&input).map(|_| ()),
+            Err(nom::Err::Failure(nom::error::Error::new(&input[sh_offset..], ErrorKind::Eof)))
+        );
+    }
+
+    #[test]
+    fn test_headers_table_overlapping_the_file_header() {
+        // The program headers start inside the file header.
+        let mut input = EXIT_EXECUTABLE_FILE.to_vec();
+        input[32..40].copy_from_slice(&8u64.to_le_bytes());
+
+        assert_eq!(
+            File::read::<nom::error::Error<Input>>(&input).map(|_| ()),
+            Err(nom::Err::Failure(nom::error::Error::new(&input[8..], ErrorKind::Verify)))
+        );
+    }
+
+    #[test]
+    fn test_invalid_alignment() {
+        use crate::errors::{FileError, InvalidHeader};
+
+        // The alignment of the section header #1 is not a power of two.
+        let mut input = EXIT_FILE.to_vec();
+        let sh_offset = u64::from_le_bytes(input[40..48].try_into().unwrap()) as usize;
+        let sh_alignment_offset = sh_offset + Section::SIZE + Section::ALIGNMENT_OFFSET;
+        input[sh_alignment_offset..sh_alignment_offset + 8].copy_from_slice(&3u64.to_le_bytes());
+
+        let error = File::read::<FileError>(&input).map(|_| ()).unwrap_err();
+
+        assert_eq!(
+            error,
+            nom::Err::Failure(FileError {
+                input: &input[sh_offset + Section::SIZE..sh_offset + 2 * Section::SIZE],
+                code: ErrorKind::Verify,
+                invalid_header: Some(InvalidHeader::SectionAlignment { index: 1, alignment: 3 }),
+            })
+        );
+
+        let nom::Err::Failure(FileError { invalid_header: Some(invalid_header), .. }) = error
+        else {
+            unreachable!();
+        };
+
+        assert_eq!(
+            invalid_header.to_string(),
+            "the alignment of the section header #1, `3`, is not a power of two"
+        );
+
+        // The alignment of the program header #0 is not a power of two.
+        let mut input = EXIT_EXECUTABLE_FILE.to_vec();
+        let ph_offset = u64::from_le_bytes(input[32..40].try_into().unwrap()) as usize;
+        let ph_alignment_offset = ph_offset + Program::ALIGNMENT_OFFSET;
+        input[ph_alignment_offset..ph_alignment_offset + 8].copy_from_slice(&6u64.to_le_bytes());
+
+        assert!(matches!(
+            File::read::<FileError>(&input).map(|_| ()),
+            Err(nom::Err::Failure(FileError {
+                code: ErrorKind::Verify,
+                invalid_header: Some(InvalidHeader::ProgramAlignment { index: 0, alignment: 6 }),
+                ..
+            }))
+        ));
+
+        // Other error types are still supported.
+        assert!(File::read::<()>(&input).is_err());
+    }
+
+    #[test]
+    fn test_file_clone_and_eq() {
+        for input in [EXIT_FILE, EXIT_EXECUTABLE_FILE, EXIT_DYNAMIC_FILE, EXIT_SHARED_FILE] {
+            let (_, file) = File::read_resolved::<()>(input).unwrap();
+            let mut cloned_file = file.clone();
+
+            assert_eq!(cloned_file, file);
+            assert!(cloned_file.symbols().eq(file.symbols()));
+
+            for symbol in file.symbols() {
+                assert_eq!(symbol.clone(), symbol);
+            }
+
+            // Modifying the clone leaves the original untouched.
+            if let Some(section) =
+                cloned_file.sections.iter_mut().find(|section| !section.data.is_empty())
+            {
+                section.data_mut().unwrap()[0] ^= 0xff;
+            } else {
+                cloned_file.programs[0].data_mut().unwrap()[0] ^= 0xff;
+            }
+
+            assert_ne!(cloned_file, file);
+            assert_eq!(File::read_resolved::<()>(input).unwrap().1, file);
+        }
+    }
+
+    #[test]
+    fn test_data_reader() {
+        use std::io::{self, BufRead, Read as _};
+
+        let (_, file) = File::read::<()>(EXIT_FILE).unwrap();
+        let section = file.sections.iter().find(|section| !section.data.is_empty()).unwrap();
+        let raw = &EXIT_FILE[section.offset.into()..][..section.data.len()];
+
+        // Read all the bytes.
+        let mut bytes = Vec::new();
+        section.reader().read_to_end(&mut bytes).unwrap();
+
+        assert_eq!(bytes, raw);
+
+        // Read the bytes in small chunks: the reader keeps track of its
+        // position.
+        let mut reader = section.data.reader();
+        let mut chunk = [0; 3];
+        let read = reader.read(&mut chunk).unwrap();
+
+        assert_eq!(&chunk[..read], &raw[..read]);
+        assert_eq!(reader.fill_buf().unwrap(), &raw[read..]);
+
+        reader.consume(1);
+
+        let mut rest = Vec::new();
+        io::copy(&mut reader, &mut rest).unwrap();
+
+        assert_eq!(rest, &raw[read + 1..]);
+        assert_eq!(reader.read(&mut chunk).unwrap(), 0);
+
+        // Program data can be read too.
+        let (_, file) = File::read::<()>(EXIT_EXECUTABLE_FILE).unwrap();
+        let program = file.programs.iter().find(|program| !program.data.is_empty()).unwrap();
+        let mut bytes = Vec::new();
+        program.reader().read_to_end(&mut bytes).unwrap();
+
+        assert_eq!(bytes, program.data.as_ref());
+    }
+
     #[test]
     fn test_me() {
         let (_remaining, mut file) = File::read::<VerboseError<Input>>(EXIT_FILE).unwrap();
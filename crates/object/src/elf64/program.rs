@@ -1,13 +1,13 @@
-use std::{borrow::Cow, io};
+use std::{borrow::Cow, io, iter};
 
 use enumflags2::{bitflags, BitFlags};
 use weld_object_macros::ReadWrite;
 
-use super::{Address, Alignment, Data, DataType};
+use super::{Address, Alignment, Data, DataReader, DataType, Machine};
 use crate::{combinators::*, Input, Number, Read, Result, Write};
 
 /// Program.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Program<'a> {
     /// Identifies the type of the segment.
     pub r#type: ProgramType,
@@ -33,7 +33,13 @@ pub struct Program<'a> {
 }
 
 impl<'a> Program<'a> {
-    pub fn read<N, E>(input: Input<'a>, file: Input<'a>) -> Result<'a, Self, E>
+    /// Size, in bytes, of a program header.
+    pub const SIZE: usize = 56;
+
+    /// Offset, in bytes, of the alignment in a program header.
+    pub(super) const ALIGNMENT_OFFSET: usize = 48;
+
+    pub fn read<N, E>(input: Input<'a>, file: Input<'a>, machine: Machine) -> Result<'a, Self, E>
     where
         N: Number,
         E: ParseError<Input<'a>>,
@@ -71,17 +77,45 @@ impl<'a> Program<'a> {
             alignment,
             segment_flags,
             data: Data::new(
-                Cow::Borrowed(
-                    &file[offset.into()..][..segment_size_in_file_image.try_into().unwrap()],
-                ),
+                Cow::Borrowed(&file[offset.into()..][..segment_size_in_file_image.into()]),
                 DataType::ProgramData,
                 N::endianness(),
+                machine,
                 None,
             ),
         };
 
         Ok((input, program))
     }
+
+    /// Get the data of the program as mutable bytes, see [`Data::as_mut_slice`].
+    pub fn data_mut(&mut self) -> Option<&mut [u8]> {
+        self.data.as_mut_slice()
+    }
+
+    /// Get a reader over the data of the program, see [`Data::reader`].
+    pub fn reader(&self) -> DataReader<'_> {
+        self.data.reader()
+    }
+
+    /// Get the size, in bytes, of the segment once loaded in memory.
+    ///
+    /// It's [`Self::segment_size_in_memory`], or the size of the data if it's
+    /// larger, for a malformed segment.
+    pub fn memory_size(&self) -> u64 {
+        self.segment_size_in_memory.0.max(self.data.len() as u64)
+    }
+
+    /// Get the bytes of the segment once loaded in memory: the data, followed
+    /// by the zeros filling the rest of the segment, e.g. for the `.bss`
+    /// section, see [`Self::memory_size`].
+    pub fn memory_image(&self) -> impl Iterator<Item = u8> + '_ {
+        self.data
+            .iter()
+            .copied()
+            .chain(iter::repeat(0))
+            .take(self.memory_size().try_into().unwrap_or(usize::MAX))
+    }
 }
 
 impl<'a> Write for Program<'a> {
@@ -121,6 +155,15 @@ pub enum ProgramType {
     ProgramHeader = 0x06,
     /// Thread-Local Storage template.
     ThreadLocalStorage = 0x07,
+    /// The `.eh_frame_hdr` section, i.e. the table used to find the unwind
+    /// information of an address.
+    GnuEhFrame = 0x6474_e550,
+    /// Permissions of the stack, through the flags.
+    GnuStack = 0x6474_e551,
+    /// Segment to make read-only after the relocations are applied.
+    GnuRelro = 0x6474_e552,
+    /// The `.note.gnu.property` section.
+    GnuProperty = 0x6474_e553,
 }
 
 /// Program flag.
@@ -136,6 +179,15 @@ pub enum ProgramFlag {
 /// Program flags.
 pub type ProgramFlags = BitFlags<ProgramFlag>;
 
+/// Format program flags the conventional way, e.g. `r-x` for a readable and
+/// executable segment.
+pub fn program_flags_name(flags: ProgramFlags) -> String {
+    [(ProgramFlag::Read, 'r'), (ProgramFlag::Write, 'w'), (ProgramFlag::Execute, 'x')]
+        .into_iter()
+        .map(|(flag, letter)| if flags.contains(flag) { letter } else { '-' })
+        .collect()
+}
+
 impl Read for ProgramFlags {
     fn read<'a, N, E>(input: Input<'a>) -> Result<'a, ProgramFlags, E>
     where
@@ -162,11 +214,17 @@ impl Write for ProgramFlags {
 
 #[cfg(test)]
 mod tests {
-    use std::num::NonZeroU64;
-
     use super::*;
     use crate::{BigEndian, Endianness};
 
+    #[test]
+    fn test_program_flags_name() {
+        assert_eq!(program_flags_name(ProgramFlag::Read | ProgramFlag::Execute), "r-x");
+        assert_eq!(program_flags_name(ProgramFlag::Read | ProgramFlag::Write), "rw-");
+        assert_eq!(program_flags_name(ProgramFlags::all()), "rwx");
+        assert_eq!(program_flags_name(ProgramFlags::empty()), "---");
+    }
+
     #[test]
     fn test_section() {
         #[rustfmt::skip]
@@ -198,9 +256,15 @@ mod tests {
             physical_address: None,
             segment_size_in_file_image: Address(5),
             segment_size_in_memory: Address(0),
-            alignment: Alignment(Some(NonZeroU64::new(512).unwrap())),
+            alignment: Alignment::new(512).unwrap(),
             segment_flags: ProgramFlag::Read | ProgramFlag::Execute,
-            data: Data::new(Cow::Borrowed(&file[..]), DataType::Unspecified, Endianness::Big, None),
+            data: Data::new(
+                Cow::Borrowed(file),
+                DataType::ProgramData,
+                Endianness::Big,
+                Machine::X86_64,
+                None,
+            ),
         };
 
         let mut buffer = Vec::new();
@@ -208,7 +272,40 @@ mod tests {
 
         assert_eq!(buffer, input);
 
-        assert_eq!(Program::read::<BigEndian, ()>(input, file), Ok((&[] as &[u8], program)));
+        assert_eq!(
+            Program::read::<BigEndian, ()>(input, file, Machine::X86_64),
+            Ok((&[] as &[u8], program))
+        );
+    }
+
+    #[test]
+    fn test_memory_image() {
+        let file: &[u8] = &[0x01, 0x02, 0x03, 0x04];
+        let program = Program {
+            r#type: ProgramType::Load,
+            offset: Address(0),
+            virtual_address: Address(0x1000),
+            physical_address: None,
+            segment_size_in_file_image: Address(4),
+            segment_size_in_memory: Address(8),
+            alignment: Alignment::new(8).unwrap(),
+            segment_flags: ProgramFlag::Read | ProgramFlag::Write,
+            data: Data::new(
+                Cow::Borrowed(file),
+                DataType::ProgramData,
+                Endianness::Little,
+                Machine::X86_64,
+                None,
+            ),
+        };
+
+        // The tail of the segment is zero-filled.
+        assert_eq!(program.data.len(), 4);
+        assert_eq!(program.memory_size(), 8);
+        assert_eq!(
+            program.memory_image().collect::<Vec<_>>(),
+            [0x01, 0x02, 0x03, 0x04, 0x00, 0x00, 0x00, 0x00]
+        );
     }
 
     #[test]
@@ -0,0 +1,340 @@
+use std::{borrow::Cow, io, marker::PhantomData, num::NonZeroU64, result::Result as StdResult};
+
+use nom::Offset;
+
+use super::{Address, Machine, SignedAddress};
+use crate::{
+    combinators::*, BigEndian, Endianness, Input, LittleEndian, Number, Read, Result, Write,
+};
+
+/// A relocation entry, with an addend (i.e. a `Rela` entry).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relocation {
+    /// The location at which to apply the relocation. For relocatable files,
+    /// this is an offset from the beginning of the section to which the
+    /// relocation applies. For executable or shared object files, this is a
+    /// virtual address.
+    pub offset: Address,
+    /// The index of the symbol, in the associated symbol table, with respect
+    /// to which the relocation must be made.
+    pub symbol_index: u32,
+    /// The type of the relocation. Relocation types are machine-specific, see
+    /// [`relocation_type_name`] to get a human name.
+    pub r#type: u32,
+    /// A constant addend used to compute the value to be stored into the
+    /// relocatable field.
+    pub addend: i64,
+}
+
+impl Relocation {
+    /// Size, in bytes, of a relocation.
+    pub const SIZE: usize = 24;
+}
+
+impl Read for Relocation {
+    fn read<'r, N, E>(input: Input<'r>) -> Result<'r, Self, E>
+    where
+        N: Number,
+        E: ParseError<Input<'r>>,
+    {
+        let (input, (offset, information, addend)) = tuple((
+            <Address as Read<u64>>::read::<N, _>,
+            N::read_u64,
+            <SignedAddress as Read<u64>>::read::<N, _>,
+        ))(input)?;
+
+        Ok((
+            input,
+            Self {
+                offset,
+                symbol_index: (information >> 32) as u32,
+                r#type: (information & 0xffff_ffff) as u32,
+                addend: addend.0,
+            },
+        ))
+    }
+}
+
+impl Write for Relocation {
+    fn write<N, B>(&self, buffer: &mut B) -> io::Result<()>
+    where
+        N: Number,
+        B: io::Write,
+    {
+        <Address as Write<u64>>::write::<N, _>(&self.offset, buffer)?;
+        buffer.write_all(&N::write_u64(
+            (u64::from(self.symbol_index) << 32) | u64::from(self.r#type),
+        ))?;
+        <SignedAddress as Write<u64>>::write::<N, _>(&SignedAddress(self.addend), buffer)
+    }
+}
+
+/// An iterator producing [`Relocation`]s.
+pub struct RelocationIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    input: Input<'a>,
+    endianness: Endianness,
+    entity_size: Option<NonZeroU64>,
+    _phantom: PhantomData<E>,
+}
+
+impl<'a, E> RelocationIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    pub(super) fn new(
+        input: Input<'a>,
+        endianness: Endianness,
+        entity_size: Option<NonZeroU64>,
+    ) -> Self {
+        Self { input, endianness, entity_size, _phantom: PhantomData }
+    }
+}
+
+impl<'a, E> Iterator for RelocationIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    type Item = StdResult<Relocation, Err<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.input.is_empty() {
+            return None;
+        }
+
+        let read = match self.endianness {
+            Endianness::Big => Relocation::read::<BigEndian, E>(self.input),
+            Endianness::Little => Relocation::read::<LittleEndian, E>(self.input),
+        };
+
+        match read {
+            Ok((next_input, relocation)) => {
+                // Ensure we have read the correct amount of bytes.
+                if let Some(entity_size) = self.entity_size {
+                    let offset = self.input.offset(next_input);
+                    let entity_size: usize = entity_size
+                        .get()
+                        .try_into()
+                        .expect("Failed to cast the entity size from `u64` to `usize`");
+
+                    if offset != entity_size {
+                        return Some(Err(Err::Error(E::from_error_kind(
+                            self.input,
+                            ErrorKind::LengthValue,
+                        ))));
+                    }
+                }
+
+                self.input = next_input;
+
+                Some(Ok(relocation))
+            }
+
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// Get the human name of a relocation type, e.g. `R_X86_64_PC32`.
+///
+/// Relocation types are machine-specific: the same number has a different
+/// meaning depending on the architecture, hence the `machine` argument.
+/// Unrecognized relocation types are named `UNKNOWN(n)`.
+pub fn relocation_type_name(machine: Machine, r#type: u32) -> Cow<'static, str> {
+    let name = match machine {
+        Machine::X86_64 => x86_64_relocation_type_name(r#type),
+        Machine::Aarch64 => aarch64_relocation_type_name(r#type),
+        _ => None,
+    };
+
+    match name {
+        Some(name) => Cow::Borrowed(name),
+        None => Cow::Owned(format!("UNKNOWN({})", r#type)),
+    }
+}
+
+fn x86_64_relocation_type_name(r#type: u32) -> Option<&'static str> {
+    Some(match r#type {
+        0 => "R_X86_64_NONE",
+        1 => "R_X86_64_64",
+        2 => "R_X86_64_PC32",
+        3 => "R_X86_64_GOT32",
+        4 => "R_X86_64_PLT32",
+        5 => "R_X86_64_COPY",
+        6 => "R_X86_64_GLOB_DAT",
+        7 => "R_X86_64_JUMP_SLOT",
+        8 => "R_X86_64_RELATIVE",
+        9 => "R_X86_64_GOTPCREL",
+        10 => "R_X86_64_32",
+        11 => "R_X86_64_32S",
+        12 => "R_X86_64_16",
+        13 => "R_X86_64_PC16",
+        14 => "R_X86_64_8",
+        15 => "R_X86_64_PC8",
+        16 => "R_X86_64_DTPMOD64",
+        17 => "R_X86_64_DTPOFF64",
+        18 => "R_X86_64_TPOFF64",
+        19 => "R_X86_64_TLSGD",
+        20 => "R_X86_64_TLSLD",
+        21 => "R_X86_64_DTPOFF32",
+        22 => "R_X86_64_GOTTPOFF",
+        23 => "R_X86_64_TPOFF32",
+        24 => "R_X86_64_PC64",
+        25 => "R_X86_64_GOTOFF64",
+        26 => "R_X86_64_GOTPC32",
+        27 => "R_X86_64_GOT64",
+        28 => "R_X86_64_GOTPCREL64",
+        29 => "R_X86_64_GOTPC64",
+        30 => "R_X86_64_GOTPLT64",
+        31 => "R_X86_64_PLTOFF64",
+        32 => "R_X86_64_SIZE32",
+        33 => "R_X86_64_SIZE64",
+        34 => "R_X86_64_GOTPC32_TLSDESC",
+        35 => "R_X86_64_TLSDESC_CALL",
+        36 => "R_X86_64_TLSDESC",
+        37 => "R_X86_64_IRELATIVE",
+        38 => "R_X86_64_RELATIVE64",
+        41 => "R_X86_64_GOTPCRELX",
+        42 => "R_X86_64_REX_GOTPCRELX",
+        _ => return None,
+    })
+}
+
+fn aarch64_relocation_type_name(r#type: u32) -> Option<&'static str> {
+    Some(match r#type {
+        0 | 256 => "R_AARCH64_NONE",
+        257 => "R_AARCH64_ABS64",
+        258 => "R_AARCH64_ABS32",
+        259 => "R_AARCH64_ABS16",
+        260 => "R_AARCH64_PREL64",
+        261 => "R_AARCH64_PREL32",
+        262 => "R_AARCH64_PREL16",
+        263 => "R_AARCH64_MOVW_UABS_G0",
+        264 => "R_AARCH64_MOVW_UABS_G0_NC",
+        265 => "R_AARCH64_MOVW_UABS_G1",
+        266 => "R_AARCH64_MOVW_UABS_G1_NC",
+        267 => "R_AARCH64_MOVW_UABS_G2",
+        268 => "R_AARCH64_MOVW_UABS_G2_NC",
+        269 => "R_AARCH64_MOVW_UABS_G3",
+        270 => "R_AARCH64_MOVW_SABS_G0",
+        271 => "R_AARCH64_MOVW_SABS_G1",
+        272 => "R_AARCH64_MOVW_SABS_G2",
+        273 => "R_AARCH64_LD_PREL_LO19",
+        274 => "R_AARCH64_ADR_PREL_LO21",
+        275 => "R_AARCH64_ADR_PREL_PG_HI21",
+        276 => "R_AARCH64_ADR_PREL_PG_HI21_NC",
+        277 => "R_AARCH64_ADD_ABS_LO12_NC",
+        278 => "R_AARCH64_LDST8_ABS_LO12_NC",
+        279 => "R_AARCH64_TSTBR14",
+        280 => "R_AARCH64_CONDBR19",
+        282 => "R_AARCH64_JUMP26",
+        283 => "R_AARCH64_CALL26",
+        284 => "R_AARCH64_LDST16_ABS_LO12_NC",
+        285 => "R_AARCH64_LDST32_ABS_LO12_NC",
+        286 => "R_AARCH64_LDST64_ABS_LO12_NC",
+        287 => "R_AARCH64_MOVW_PREL_G0",
+        288 => "R_AARCH64_MOVW_PREL_G0_NC",
+        289 => "R_AARCH64_MOVW_PREL_G1",
+        290 => "R_AARCH64_MOVW_PREL_G1_NC",
+        291 => "R_AARCH64_MOVW_PREL_G2",
+        292 => "R_AARCH64_MOVW_PREL_G2_NC",
+        293 => "R_AARCH64_MOVW_PREL_G3",
+        299 => "R_AARCH64_LDST128_ABS_LO12_NC",
+        307 => "R_AARCH64_GOTREL64",
+        308 => "R_AARCH64_GOTREL32",
+        309 => "R_AARCH64_GOT_LD_PREL19",
+        311 => "R_AARCH64_ADR_GOT_PAGE",
+        312 => "R_AARCH64_LD64_GOT_LO12_NC",
+        512 => "R_AARCH64_TLSGD_ADR_PREL21",
+        513 => "R_AARCH64_TLSGD_ADR_PAGE21",
+        514 => "R_AARCH64_TLSGD_ADD_LO12_NC",
+        541 => "R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21",
+        542 => "R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC",
+        549 => "R_AARCH64_TLSLE_ADD_TPREL_HI12",
+        550 => "R_AARCH64_TLSLE_ADD_TPREL_LO12",
+        551 => "R_AARCH64_TLSLE_ADD_TPREL_LO12_NC",
+        560 => "R_AARCH64_TLSDESC_ADR_PAGE21",
+        561 => "R_AARCH64_TLSDESC_LD64_LO12",
+        562 => "R_AARCH64_TLSDESC_ADD_LO12",
+        569 => "R_AARCH64_TLSDESC_CALL",
+        1024 => "R_AARCH64_COPY",
+        1025 => "R_AARCH64_GLOB_DAT",
+        1026 => "R_AARCH64_JUMP_SLOT",
+        1027 => "R_AARCH64_RELATIVE",
+        1028 => "R_AARCH64_TLS_DTPMOD",
+        1029 => "R_AARCH64_TLS_DTPREL",
+        1030 => "R_AARCH64_TLS_TPREL",
+        1031 => "R_AARCH64_TLSDESC",
+        1032 => "R_AARCH64_IRELATIVE",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relocation() {
+        #[rustfmt::skip]
+        let input: &[u8] = &[
+            // Offset.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09,
+            // Information (symbol index + type).
+            0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x02,
+            // Addend.
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfc,
+        ];
+
+        let relocation = Relocation { offset: Address(9), symbol_index: 4, r#type: 2, addend: -4 };
+
+        assert_read_write!(
+            Relocation: Read<()> + Write<()> {
+                bytes_value(big_endian) = input,
+                rust_value = relocation,
+            }
+        );
+    }
+
+    #[test]
+    fn test_relocation_iterator() {
+        let mut input = Vec::new();
+
+        for relocation in [
+            Relocation { offset: Address(3), symbol_index: 1, r#type: 2, addend: -4 },
+            Relocation { offset: Address(14), symbol_index: 5, r#type: 4, addend: -4 },
+        ] {
+            relocation.write::<LittleEndian, _>(&mut input).unwrap();
+        }
+
+        let mut iterator =
+            RelocationIterator::<()>::new(&input, Endianness::Little, NonZeroU64::new(24));
+
+        assert_eq!(
+            iterator.next(),
+            Some(Ok(Relocation { offset: Address(3), symbol_index: 1, r#type: 2, addend: -4 }))
+        );
+        assert_eq!(
+            iterator.next(),
+            Some(Ok(Relocation { offset: Address(14), symbol_index: 5, r#type: 4, addend: -4 }))
+        );
+        assert_eq!(iterator.next(), None);
+    }
+
+    #[test]
+    fn test_relocation_type_name() {
+        assert_eq!(relocation_type_name(Machine::X86_64, 2), "R_X86_64_PC32");
+        assert_eq!(relocation_type_name(Machine::X86_64, 4), "R_X86_64_PLT32");
+        assert_eq!(relocation_type_name(Machine::Aarch64, 283), "R_AARCH64_CALL26");
+        assert_eq!(relocation_type_name(Machine::Aarch64, 257), "R_AARCH64_ABS64");
+
+        // Unknown relocation types.
+        assert_eq!(relocation_type_name(Machine::X86_64, 1000), "UNKNOWN(1000)");
+        assert_eq!(relocation_type_name(Machine::Aarch64, 2), "UNKNOWN(2)");
+
+        // Unsupported machines.
+        assert_eq!(relocation_type_name(Machine::Sparc, 2), "UNKNOWN(2)");
+    }
+}
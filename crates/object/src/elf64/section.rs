@@ -1,20 +1,19 @@
 use std::{borrow::Cow, io, num::NonZeroU64};
 
-use bstr::BString;
+use bstr::BStr;
 use enumflags2::{bitflags, BitFlags};
-use weld_object_macros::ReadWrite;
 
-use super::{Address, Alignment, Data};
+use super::{Address, Alignment, Data, DataReader, Machine};
 use crate::{combinators::*, Input, Number, Read, Result, Write};
 
 /// Section header.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Section<'a> {
     /// Name of the section, if any.
-    pub name: Option<BString>,
+    pub name: Option<Cow<'a, BStr>>,
     /// An offset to a string in the `.shstrtab` section that represents the
     /// name of this section.
-    pub(super) name_offset: Address,
+    pub name_offset: Address,
     /// Type of the section header.
     pub r#type: SectionType,
     /// Flags.
@@ -41,7 +40,13 @@ pub struct Section<'a> {
 }
 
 impl<'a> Section<'a> {
-    pub fn read<N, E>(input: Input<'a>, file: Input<'a>) -> Result<'a, Self, E>
+    /// Size, in bytes, of a section header.
+    pub const SIZE: usize = 64;
+
+    /// Offset, in bytes, of the alignment in a section header.
+    pub(super) const ALIGNMENT_OFFSET: usize = 48;
+
+    pub fn read<N, E>(input: Input<'a>, file: Input<'a>, machine: Machine) -> Result<'a, Self, E>
     where
         N: Number,
         E: ParseError<Input<'a>>,
@@ -80,7 +85,15 @@ impl<'a> Section<'a> {
             None
         };
 
-        let section = Self {
+        // `NoBits` sections occupy no space in the file: their size is only
+        // recorded in `segment_size_in_file_image`.
+        let data = if r#type == SectionType::NoBits {
+            &[]
+        } else {
+            &file[offset.into()..][..segment_size_in_file_image.into()]
+        };
+
+        let mut section = Self {
             name: None,
             name_offset,
             r#type,
@@ -93,15 +106,31 @@ impl<'a> Section<'a> {
             alignment,
             entity_size,
             data: Data::new(
-                Cow::Borrowed(&file[offset.into()..][..segment_size_in_file_image.into()]),
+                Cow::Borrowed(data),
                 r#type.into(),
                 N::endianness(),
+                machine,
                 entity_size,
             ),
         };
+        section.data.compressed = flags.contains(SectionFlag::Compressed);
+
+        if r#type == SectionType::NoBits {
+            section.data.no_bits_size = Some(segment_size_in_file_image.0);
+        }
 
         Ok((input, section))
     }
+
+    /// Get the data of the section as mutable bytes, see [`Data::as_mut_slice`].
+    pub fn data_mut(&mut self) -> Option<&mut [u8]> {
+        self.data.as_mut_slice()
+    }
+
+    /// Get a reader over the data of the section, see [`Data::reader`].
+    pub fn reader(&self) -> DataReader<'_> {
+        self.data.reader()
+    }
 }
 
 impl<'a> Write for Section<'a> {
@@ -124,56 +153,171 @@ impl<'a> Write for Section<'a> {
 }
 
 /// Section type.
-#[derive(ReadWrite, Debug, Clone, Copy, PartialEq, Eq)]
-#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SectionType {
     /// Mark an unused section header.
-    Null = 0x00,
+    Null,
     /// The section contains information defined by the program.
-    ProgramData = 0x01,
+    ProgramData,
     /// The section contains a linker symbol table.
-    SymbolTable = 0x02,
+    SymbolTable,
     /// The section contains a string table.
-    StringTable = 0x03,
+    StringTable,
     /// The seciton contains “Rela” type relocation entries, with addends (hence
     /// the “a” in “Rela”, i.e. “RELocations with Addends”).
-    RelocationWithAddends = 0x04,
+    RelocationWithAddends,
     /// The section contains a symbol hash table.
-    SymbolHashTable = 0x05,
+    SymbolHashTable,
     /// The section contains dynamic linking tables.
-    DynamicLinkingTable = 0x06,
+    DynamicLinkingTable,
     /// The section contains note information.
-    Note = 0x07,
+    Note,
     /// The section contains uninitialized space; does not occupy any space in
     /// the file. It represents program space with no data (BSS, Block
     /// Started by Symbol).
-    NoBits = 0x08,
+    NoBits,
     /// The section contains “Rel” type relocation entries, without addends.
-    Relocation = 0x09,
+    Relocation,
     /// Reserved.
-    Shlib = 0x0a,
+    Shlib,
     /// The section contains a dynamic loader symbol table.
-    DynamicLoaderSymbolTable = 0x0b,
+    DynamicLoaderSymbolTable,
     /// Array of constructors.
-    ArrayOfConstructors = 0x0e,
+    ArrayOfConstructors,
     /// Array of destructors.
-    ArrayOfDestructors = 0x0f,
+    ArrayOfDestructors,
     /// Array of pre-constructors.
-    ArrayOfPreConstructors = 0x10,
+    ArrayOfPreConstructors,
     /// Section group.
-    Group = 0x11,
+    Group,
     /// Extended section indices.
-    ExtendedSectionIndices = 0x12,
+    ExtendedSectionIndices,
     /// Number of defined types.
-    NumberOfDefinedTypes = 0x13,
+    NumberOfDefinedTypes,
     /// Low environment-specific use.
-    LowEnvironmentSpecific = 0x6000_0000,
-    /// High environment-specific use.
-    HighEnvironmentSpecific = 0x6fff_ffff,
+    LowEnvironmentSpecific,
+    /// The section contains object attributes, i.e. `.gnu.attributes`.
+    GnuAttributes,
+    /// The section contains a GNU-style symbol hash table, i.e. `.gnu.hash`.
+    GnuHash,
+    /// The section contains the list of the prelinked shared objects, i.e.
+    /// `.gnu.liblist`.
+    GnuLibraryList,
+    /// The section contains version definitions, i.e. `.gnu.version_d`.
+    GnuVersionDefinition,
+    /// The section contains version requirements, i.e. `.gnu.version_r`.
+    GnuVersionNeed,
+    /// The section contains symbol versions, i.e. `.gnu.version`. It is also
+    /// the highest environment-specific value.
+    GnuVersionSymbol,
     /// Low processor-specific use.
-    LowProcessorSpecific = 0x7000_0000,
+    LowProcessorSpecific,
+    /// The section contains unwind information: the exception index table
+    /// on ARM, i.e. `SHT_ARM_EXIDX`, or `.eh_frame` on x86-64 when emitted by
+    /// some compilers, i.e. `SHT_X86_64_UNWIND`.
+    Unwind,
+    /// The section contains the ARM build attributes, i.e. `.ARM.attributes`.
+    ArmAttributes,
     /// High processor-specific use.
-    HighProcessorSpecific = 0x7fff_ffff,
+    HighProcessorSpecific,
+    /// Any other type, not interpreted by `weld`.
+    Unknown(u32),
+}
+
+impl From<u32> for SectionType {
+    fn from(value: u32) -> Self {
+        match value {
+            0x00 => Self::Null,
+            0x01 => Self::ProgramData,
+            0x02 => Self::SymbolTable,
+            0x03 => Self::StringTable,
+            0x04 => Self::RelocationWithAddends,
+            0x05 => Self::SymbolHashTable,
+            0x06 => Self::DynamicLinkingTable,
+            0x07 => Self::Note,
+            0x08 => Self::NoBits,
+            0x09 => Self::Relocation,
+            0x0a => Self::Shlib,
+            0x0b => Self::DynamicLoaderSymbolTable,
+            0x0e => Self::ArrayOfConstructors,
+            0x0f => Self::ArrayOfDestructors,
+            0x10 => Self::ArrayOfPreConstructors,
+            0x11 => Self::Group,
+            0x12 => Self::ExtendedSectionIndices,
+            0x13 => Self::NumberOfDefinedTypes,
+            0x6000_0000 => Self::LowEnvironmentSpecific,
+            0x6fff_fff5 => Self::GnuAttributes,
+            0x6fff_fff6 => Self::GnuHash,
+            0x6fff_fff7 => Self::GnuLibraryList,
+            0x6fff_fffd => Self::GnuVersionDefinition,
+            0x6fff_fffe => Self::GnuVersionNeed,
+            0x6fff_ffff => Self::GnuVersionSymbol,
+            0x7000_0000 => Self::LowProcessorSpecific,
+            0x7000_0001 => Self::Unwind,
+            0x7000_0003 => Self::ArmAttributes,
+            0x7fff_ffff => Self::HighProcessorSpecific,
+            value => Self::Unknown(value),
+        }
+    }
+}
+
+impl From<SectionType> for u32 {
+    fn from(value: SectionType) -> Self {
+        match value {
+            SectionType::Null => 0x00,
+            SectionType::ProgramData => 0x01,
+            SectionType::SymbolTable => 0x02,
+            SectionType::StringTable => 0x03,
+            SectionType::RelocationWithAddends => 0x04,
+            SectionType::SymbolHashTable => 0x05,
+            SectionType::DynamicLinkingTable => 0x06,
+            SectionType::Note => 0x07,
+            SectionType::NoBits => 0x08,
+            SectionType::Relocation => 0x09,
+            SectionType::Shlib => 0x0a,
+            SectionType::DynamicLoaderSymbolTable => 0x0b,
+            SectionType::ArrayOfConstructors => 0x0e,
+            SectionType::ArrayOfDestructors => 0x0f,
+            SectionType::ArrayOfPreConstructors => 0x10,
+            SectionType::Group => 0x11,
+            SectionType::ExtendedSectionIndices => 0x12,
+            SectionType::NumberOfDefinedTypes => 0x13,
+            SectionType::LowEnvironmentSpecific => 0x6000_0000,
+            SectionType::GnuAttributes => 0x6fff_fff5,
+            SectionType::GnuHash => 0x6fff_fff6,
+            SectionType::GnuLibraryList => 0x6fff_fff7,
+            SectionType::GnuVersionDefinition => 0x6fff_fffd,
+            SectionType::GnuVersionNeed => 0x6fff_fffe,
+            SectionType::GnuVersionSymbol => 0x6fff_ffff,
+            SectionType::LowProcessorSpecific => 0x7000_0000,
+            SectionType::Unwind => 0x7000_0001,
+            SectionType::ArmAttributes => 0x7000_0003,
+            SectionType::HighProcessorSpecific => 0x7fff_ffff,
+            SectionType::Unknown(value) => value,
+        }
+    }
+}
+
+impl Read for SectionType {
+    fn read<'a, N, E>(input: Input<'a>) -> Result<'a, Self, E>
+    where
+        N: Number,
+        E: ParseError<Input<'a>>,
+    {
+        let (input, value) = N::read_u32(input)?;
+
+        Ok((input, value.into()))
+    }
+}
+
+impl Write for SectionType {
+    fn write<N, B>(&self, buffer: &mut B) -> io::Result<()>
+    where
+        N: Number,
+        B: io::Write,
+    {
+        buffer.write_all(&N::write_u32((*self).into()))
+    }
 }
 
 /// Section flag.
@@ -201,6 +345,8 @@ pub enum SectionFlag {
     IsPartOfAGroup = 0x200,
     /// Section hold thread-local data.
     HasThreadLocalData = 0x400,
+    /// Section holds compressed data, prefixed by a compression header.
+    Compressed = 0x800,
     // Disabled because those are not powers of two, then it's incompatible with `#[bitflags]`.
     //
     // /// Environment-specific use.
@@ -212,6 +358,27 @@ pub enum SectionFlag {
 /// Section flags.
 pub type SectionFlags = BitFlags<SectionFlag>;
 
+/// Format section flags like `readelf` does, e.g. `WA` for a writable and
+/// allocable section. Unset flags are omitted.
+pub fn section_flags_name(flags: SectionFlags) -> String {
+    flags
+        .iter()
+        .map(|flag| match flag {
+            SectionFlag::Writable => 'W',
+            SectionFlag::Allocable => 'A',
+            SectionFlag::Executable => 'X',
+            SectionFlag::Merge => 'M',
+            SectionFlag::Strings => 'S',
+            SectionFlag::InfoLink => 'I',
+            SectionFlag::LinkOrder => 'L',
+            SectionFlag::OsNonConforming => 'O',
+            SectionFlag::IsPartOfAGroup => 'G',
+            SectionFlag::HasThreadLocalData => 'T',
+            SectionFlag::Compressed => 'C',
+        })
+        .collect()
+}
+
 impl Read for SectionFlags {
     fn read<'a, N, E>(input: Input<'a>) -> Result<'a, Self, E>
     where
@@ -237,7 +404,7 @@ impl Write for SectionFlags {
 }
 
 /// Section index.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SectionIndex {
     /// A valid section index.
     Ok(usize),
@@ -256,9 +423,26 @@ pub enum SectionIndex {
     /// A symbol that has been declared as a common block (Fortran COMMON or C
     /// tentative declaration).
     Common,
+    /// The actual index is too large to fit in the field, and is stored
+    /// elsewhere, e.g. in the first section header for
+    /// [`File::section_index_for_section_names`][super::File::section_index_for_section_names].
+    Extended,
 }
 
 impl SectionIndex {
+    /// Get the section designated by this index in `sections`.
+    ///
+    /// It is `Some` only for a [`SectionIndex::Ok`] index within the bounds
+    /// of `sections`: the special indices, like [`SectionIndex::Undefined`],
+    /// [`SectionIndex::Absolute`] or [`SectionIndex::Common`], designate no
+    /// section.
+    pub fn resolve<'s, 'a>(&self, sections: &'s [Section<'a>]) -> Option<&'s Section<'a>> {
+        match *self {
+            Self::Ok(index) => sections.get(index),
+            _ => None,
+        }
+    }
+
     fn _read<'a, E>(input: Input<'a>, index: u32) -> Result<'a, Self, E>
     where
         E: ParseError<Input<'a>>,
@@ -273,6 +457,7 @@ impl SectionIndex {
                 0xff3f => Self::HighEnvironmentSpecific,
                 0xfff1 => Self::Absolute,
                 0xfff2 => Self::Common,
+                0xffff => Self::Extended,
                 index => Self::Ok(
                     index
                         .try_into()
@@ -317,6 +502,7 @@ macro_rules! section_index_write {
             SectionIndex::HighEnvironmentSpecific => 0xff3f,
             SectionIndex::Absolute => 0xfff1,
             SectionIndex::Common => 0xfff2,
+            SectionIndex::Extended => 0xffff,
             SectionIndex::Ok(index) => {
                 (*index).try_into().expect("Failed to cast the section index from `usize` to `u32`")
             }
@@ -346,9 +532,23 @@ impl Write<u16> for SectionIndex {
 
 #[cfg(test)]
 mod tests {
-    use super::{super::DataType, *};
+    use super::{
+        super::{CompressionHeader, CompressionType, DataType},
+        *,
+    };
     use crate::{BigEndian, Endianness};
 
+    #[test]
+    fn test_section_flags_name() {
+        assert_eq!(section_flags_name(SectionFlag::Allocable | SectionFlag::Executable), "AX");
+        assert_eq!(section_flags_name(SectionFlag::Allocable | SectionFlag::Writable), "WA");
+        assert_eq!(
+            section_flags_name(SectionFlag::Merge | SectionFlag::Strings | SectionFlag::InfoLink),
+            "MSI"
+        );
+        assert_eq!(section_flags_name(SectionFlags::empty()), "");
+    }
+
     #[test]
     fn test_section() {
         #[rustfmt::skip]
@@ -387,9 +587,15 @@ mod tests {
             segment_size_in_file_image: Address(5),
             link: SectionIndex::Ok(3),
             information: 0,
-            alignment: Alignment(Some(NonZeroU64::new(512).unwrap())),
+            alignment: Alignment::new(512).unwrap(),
             entity_size: None,
-            data: Data::new(Cow::Borrowed(&file[..]), DataType::StringTable, Endianness::Big, None),
+            data: Data::new(
+                Cow::Borrowed(file),
+                DataType::StringTable,
+                Endianness::Big,
+                Machine::X86_64,
+                None,
+            ),
         };
 
         let mut buffer = Vec::new();
@@ -397,7 +603,193 @@ mod tests {
 
         assert_eq!(buffer, input);
 
-        assert_eq!(Section::read::<BigEndian, ()>(input, file), Ok((&[] as &[u8], section)));
+        assert_eq!(
+            Section::read::<BigEndian, ()>(input, file, Machine::X86_64),
+            Ok((&[] as &[u8], section))
+        );
+    }
+
+    #[test]
+    fn test_section_type() {
+        for (value, r#type) in [
+            (0x03u32, SectionType::StringTable),
+            (0x6fff_fff6, SectionType::GnuHash),
+            (0x6fff_fffd, SectionType::GnuVersionDefinition),
+            (0x6fff_fffe, SectionType::GnuVersionNeed),
+            (0x6fff_ffff, SectionType::GnuVersionSymbol),
+            (0x7000_0001, SectionType::Unwind),
+            (0x7000_0003, SectionType::ArmAttributes),
+            (0x6fff_fff0, SectionType::Unknown(0x6fff_fff0)),
+            (0x8000_0000, SectionType::Unknown(0x8000_0000)),
+        ] {
+            assert_read_write!(
+                SectionType: Read<()> + Write<()> {
+                    bytes_value(auto_endian) = value,
+                    rust_value = r#type,
+                }
+            );
+            assert_eq!(u32::from(r#type), value);
+        }
+    }
+
+    #[test]
+    fn test_gnu_hash_section() {
+        #[rustfmt::skip]
+        let input: &[u8] = &[
+            // Name offset.
+            0x00, 0x00, 0x00, 0x01,
+            // Type.
+            0x6f, 0xff, 0xff, 0xf6,
+            // Flag.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+            // Virtual address.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // Offset.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // Segment size in file image.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04,
+            // Link.
+            0x00, 0x00, 0x00, 0x02,
+            // Information.
+            0x00, 0x00, 0x00, 0x00,
+            // Alignment.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08,
+            // Entity size.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let file: &[u8] = &[0x01, 0x02, 0x03, 0x04];
+        let (_, section) = Section::read::<BigEndian, ()>(input, file, Machine::X86_64).unwrap();
+
+        assert_eq!(section.r#type, SectionType::GnuHash);
+        assert_eq!(section.data.as_ref(), file);
+
+        let mut buffer = Vec::new();
+        section.write::<BigEndian, _>(&mut buffer).unwrap();
+
+        assert_eq!(buffer, input);
+    }
+
+    #[test]
+    fn test_no_bits_section() {
+        #[rustfmt::skip]
+        let input: &[u8] = &[
+            // Name offset.
+            0x00, 0x00, 0x00, 0x01,
+            // Type.
+            0x00, 0x00, 0x00, 0x08,
+            // Flag.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+            // Virtual address.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x10, 0x00,
+            // Offset.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+            // Segment size in file image.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            // Link.
+            0x00, 0x00, 0x00, 0x00,
+            // Information.
+            0x00, 0x00, 0x00, 0x00,
+            // Alignment.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+            // Entity size.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        // The file is smaller than the section size.
+        let file: &[u8] = &[0x0, 0x61, 0x62, 0x63, 0x0];
+
+        let (_, section) = Section::read::<BigEndian, ()>(input, file, Machine::X86_64).unwrap();
+
+        assert_eq!(section.r#type, SectionType::NoBits);
+        assert_eq!(section.segment_size_in_file_image, Address(0x100));
+        assert!(section.data.is_empty());
+    }
+
+    #[test]
+    fn test_compressed_section() {
+        #[rustfmt::skip]
+        let input: &[u8] = &[
+            // Name offset.
+            0x00, 0x00, 0x00, 0x01,
+            // Type.
+            0x00, 0x00, 0x00, 0x01,
+            // Flag.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00,
+            // Virtual address.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // Offset.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // Segment size in file image.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1a,
+            // Link.
+            0x00, 0x00, 0x00, 0x00,
+            // Information.
+            0x00, 0x00, 0x00, 0x00,
+            // Alignment.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08,
+            // Entity size.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        #[rustfmt::skip]
+        let file: &[u8] = &[
+            // Compression type.
+            0x00, 0x00, 0x00, 0x01,
+            // Reserved.
+            0x00, 0x00, 0x00, 0x00,
+            // Uncompressed size.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00,
+            // Uncompressed alignment.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+            // Compressed data, left as is.
+            0x78, 0x9c,
+        ];
+
+        let (_, section) = Section::read::<BigEndian, ()>(input, file, Machine::X86_64).unwrap();
+
+        assert_eq!(section_flags_name(section.flags), "C");
+        assert_eq!(
+            section.data.compression_header(),
+            Some(CompressionHeader {
+                r#type: CompressionType::Zlib,
+                size: 0x400,
+                alignment: Alignment::new(1).unwrap(),
+            })
+        );
+        assert_eq!(section.data.len(), 0x1a);
+    }
+
+    #[test]
+    fn test_section_data_mut() {
+        let file: &[u8] = &[0x0, 0x61, 0x62, 0x63, 0x0];
+        let mut section = Section {
+            name: None,
+            name_offset: Address(0),
+            r#type: SectionType::ProgramData,
+            flags: SectionFlags::EMPTY,
+            virtual_address: Address(0),
+            offset: Address(0),
+            segment_size_in_file_image: Address(5),
+            link: SectionIndex::Undefined,
+            information: 0,
+            alignment: Alignment::new(0).unwrap(),
+            entity_size: None,
+            data: Data::new(
+                Cow::Borrowed(file),
+                DataType::ProgramData,
+                Endianness::Big,
+                Machine::X86_64,
+                None,
+            ),
+        };
+
+        section.data_mut().unwrap()[1] = 0x7a;
+
+        assert_eq!(section.data.as_ref(), &[0x0, 0x7a, 0x62, 0x63, 0x0]);
+        assert!(matches!(*section.data, Cow::Owned(_)));
+        // The original bytes are left untouched.
+        assert_eq!(file, &[0x0, 0x61, 0x62, 0x63, 0x0]);
     }
 
     #[test]
@@ -425,6 +817,7 @@ mod tests {
             0x100 => SectionFlag::OsNonConforming,
             0x200 => SectionFlag::IsPartOfAGroup,
             0x400 => SectionFlag::HasThreadLocalData,
+            0x800 => SectionFlag::Compressed,
         );
     }
 
@@ -457,8 +850,47 @@ mod tests {
             0xff3f => SectionIndex::HighEnvironmentSpecific,
             0xfff1 => SectionIndex::Absolute,
             0xfff2 => SectionIndex::Common,
+            0xffff => SectionIndex::Extended,
             0x0001 => SectionIndex::Ok(1),
             0x002a => SectionIndex::Ok(42),
         );
     }
+
+    #[test]
+    fn test_section_index_resolve() {
+        let section = |r#type| Section {
+            name: None,
+            name_offset: Address(0),
+            r#type,
+            flags: SectionFlags::EMPTY,
+            virtual_address: Address(0),
+            offset: Address(0),
+            segment_size_in_file_image: Address(0),
+            link: SectionIndex::Undefined,
+            information: 0,
+            alignment: Alignment::new(0).unwrap(),
+            entity_size: None,
+            data: Data::new(
+                Cow::Borrowed(&[]),
+                DataType::Unspecified,
+                Endianness::Big,
+                Machine::X86_64,
+                None,
+            ),
+        };
+        let sections = [section(SectionType::Null), section(SectionType::ProgramData)];
+
+        assert_eq!(SectionIndex::Ok(1).resolve(&sections), Some(&sections[1]));
+        assert_eq!(SectionIndex::Ok(2).resolve(&sections), None);
+        assert_eq!(SectionIndex::Ok(99).resolve(&sections), None);
+
+        for special in [
+            SectionIndex::Undefined,
+            SectionIndex::Absolute,
+            SectionIndex::Common,
+            SectionIndex::Extended,
+        ] {
+            assert_eq!(special.resolve(&sections), None);
+        }
+    }
 }
@@ -3,13 +3,13 @@ use std::{borrow::Cow, marker::PhantomData, num::NonZeroU64, result::Result as S
 use bstr::BStr;
 use nom::Offset;
 
-use super::{Address, Section, SectionIndex};
+use super::{Address, Data, SectionIndex};
 use crate::{
     combinators::*, BigEndian, Endianness, Input, LittleEndian, Number, Read, Result, Write,
 };
 
 /// A symbol.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Symbol<'a> {
     // Name of the symbol, if any.
     pub name: Option<Cow<'a, BStr>>,
@@ -39,6 +39,50 @@ pub struct Symbol<'a> {
     pub size: u64,
 }
 
+impl<'a> Symbol<'a> {
+    /// Size, in bytes, of a symbol.
+    pub const SIZE: usize = 24;
+
+    /// Check whether the symbol is defined, i.e. it is not undefined, see
+    /// [`Self::is_undefined`]. An absolute symbol is defined.
+    pub fn is_defined(&self) -> bool {
+        !self.is_undefined()
+    }
+
+    /// Check whether the symbol is undefined, i.e. it is defined by another
+    /// object file.
+    pub fn is_undefined(&self) -> bool {
+        self.section_index_where_symbol_is_defined == SectionIndex::Undefined
+    }
+
+    /// Check whether the symbol is a function.
+    pub fn is_function(&self) -> bool {
+        self.r#type == SymbolType::Function
+    }
+
+    /// Check whether the symbol is a data object.
+    pub fn is_object(&self) -> bool {
+        self.r#type == SymbolType::Object
+    }
+
+    /// Check whether the symbol is local, i.e. not visible outside its
+    /// object file.
+    pub fn is_local(&self) -> bool {
+        self.binding == SymbolBinding::Local
+    }
+
+    /// Check whether the symbol is global. A weak symbol is not global, see
+    /// [`Self::is_weak`].
+    pub fn is_global(&self) -> bool {
+        self.binding == SymbolBinding::Global
+    }
+
+    /// Check whether the symbol is weak.
+    pub fn is_weak(&self) -> bool {
+        self.binding == SymbolBinding::Weak
+    }
+}
+
 impl<'a> Read for Symbol<'a> {
     fn read<'r, N, E>(input: Input<'r>) -> Result<'r, Self, E>
     where
@@ -105,6 +149,8 @@ impl<'a> Write for Symbol<'a> {
             SymbolType::Function => 0x02,
             SymbolType::Section => 0x03,
             SymbolType::File => 0x04,
+            SymbolType::Common => 0x05,
+            SymbolType::ThreadLocalStorage => 0x06,
             SymbolType::LowEnvironmentSpecific => 0x0a,
             SymbolType::HighEnvironmentSpecific => 0x0c,
             SymbolType::LowProcessorSpecific => 0x0d,
@@ -158,8 +204,8 @@ impl Read for SymbolBinding {
                 0x01 => Self::Global,
                 0x02 => Self::Weak,
                 0x0a => Self::LowEnvironmentSpecific,
-                0x0c => Self::HighProcessorSpecific,
-                0x0d => Self::LowEnvironmentSpecific,
+                0x0c => Self::HighEnvironmentSpecific,
+                0x0d => Self::LowProcessorSpecific,
                 0x0f => Self::HighProcessorSpecific,
                 _ => return Err(Err::Error(E::from_error_kind(input, ErrorKind::Alt))),
             },
@@ -180,6 +226,12 @@ pub enum SymbolType {
     Section = 0x03,
     /// Source file associated with the object file.
     File = 0x04,
+    /// Uninitialized common block.
+    Common = 0x05,
+    /// Thread-local storage entity: the value of the symbol is an offset in
+    /// the thread-local storage template, see
+    /// [`ProgramType::ThreadLocalStorage`][super::ProgramType::ThreadLocalStorage].
+    ThreadLocalStorage = 0x06,
     /// Low environment-specific use.
     LowEnvironmentSpecific = 0x0a,
     /// High environment-specific use.
@@ -206,9 +258,11 @@ impl Read for SymbolType {
                 0x02 => Self::Function,
                 0x03 => Self::Section,
                 0x04 => Self::File,
+                0x05 => Self::Common,
+                0x06 => Self::ThreadLocalStorage,
                 0x0a => Self::LowEnvironmentSpecific,
-                0x0c => Self::HighProcessorSpecific,
-                0x0d => Self::LowEnvironmentSpecific,
+                0x0c => Self::HighEnvironmentSpecific,
+                0x0d => Self::LowProcessorSpecific,
                 0x0f => Self::HighProcessorSpecific,
                 _ => return Err(Err::Error(E::from_error_kind(input, ErrorKind::Alt))),
             },
@@ -217,15 +271,20 @@ impl Read for SymbolType {
 }
 
 /// An iterator producing [`Symbol`]s.
-pub struct SymbolIterator<'a, E>
+///
+/// The endianness of the symbols is only known at runtime: it is matched once,
+/// in [`SymbolIterator::new`], to pick an iterator which is specialised for
+/// it. Reading the symbols doesn't branch on the endianness.
+///
+/// The iterator stops after the first symbol that can't be read.
+pub enum SymbolIterator<'a, E>
 where
     E: ParseError<Input<'a>>,
 {
-    input: Input<'a>,
-    endianness: Endianness,
-    entity_size: Option<NonZeroU64>,
-    strings_section: Option<&'a Section<'a>>,
-    _phantom: PhantomData<E>,
+    /// The symbols are big-endian.
+    Big(EndianSymbolIterator<'a, BigEndian, E>),
+    /// The symbols are little-endian.
+    Little(EndianSymbolIterator<'a, LittleEndian, E>),
 }
 
 impl<'a, E> SymbolIterator<'a, E>
@@ -236,9 +295,14 @@ where
         input: Input<'a>,
         endianness: Endianness,
         entity_size: Option<NonZeroU64>,
-        strings_section: Option<&'a Section<'a>>,
+        strings: Option<&'a Data<'a>>,
     ) -> Self {
-        Self { input, endianness, entity_size, strings_section, _phantom: PhantomData }
+        match endianness {
+            Endianness::Big => Self::Big(EndianSymbolIterator::new(input, entity_size, strings)),
+            Endianness::Little => {
+                Self::Little(EndianSymbolIterator::new(input, entity_size, strings))
+            }
+        }
     }
 }
 
@@ -248,51 +312,108 @@ where
 {
     type Item = StdResult<Symbol<'a>, Err<E>>;
 
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Big(iterator) => iterator.next(),
+            Self::Little(iterator) => iterator.next(),
+        }
+    }
+
+    fn fold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        // Match the endianness once for all the symbols.
+        match self {
+            Self::Big(iterator) => iterator.fold(init, f),
+            Self::Little(iterator) => iterator.fold(init, f),
+        }
+    }
+}
+
+/// An iterator producing [`Symbol`]s of a known endianness, `N`.
+pub struct EndianSymbolIterator<'a, N, E>
+where
+    N: Number,
+    E: ParseError<Input<'a>>,
+{
+    input: Input<'a>,
+    entity_size: Option<usize>,
+    strings: Option<&'a Data<'a>>,
+    _phantom: PhantomData<(N, E)>,
+}
+
+impl<'a, N, E> EndianSymbolIterator<'a, N, E>
+where
+    N: Number,
+    E: ParseError<Input<'a>>,
+{
+    pub(super) fn new(
+        input: Input<'a>,
+        entity_size: Option<NonZeroU64>,
+        strings: Option<&'a Data<'a>>,
+    ) -> Self {
+        let entity_size = entity_size.map(|entity_size| {
+            entity_size
+                .get()
+                .try_into()
+                .expect("Failed to cast the entity size from `u64` to `usize`")
+        });
+
+        Self { input, entity_size, strings, _phantom: PhantomData }
+    }
+}
+
+impl<'a, N, E> Iterator for EndianSymbolIterator<'a, N, E>
+where
+    N: Number,
+    E: ParseError<Input<'a>>,
+{
+    type Item = StdResult<Symbol<'a>, Err<E>>;
+
     fn next(&mut self) -> Option<Self::Item> {
         if self.input.is_empty() {
             return None;
         }
 
-        let read = match self.endianness {
-            Endianness::Big => Symbol::read::<BigEndian, E>(self.input),
-            Endianness::Little => Symbol::read::<LittleEndian, E>(self.input),
-        };
-
-        match read {
+        match Symbol::read::<N, E>(self.input) {
             Ok((next_input, mut symbol)) => {
                 // Ensure we have read the correct amount of bytes.
                 if let Some(entity_size) = self.entity_size {
-                    let offset = self.input.offset(next_input);
-                    let entity_size: usize = entity_size
-                        .get()
-                        .try_into()
-                        .expect("Failed to cast the entity size from `u64` to `usize`");
-
-                    if offset != entity_size {
-                        return Some(Err(Err::Error(E::from_error_kind(
-                            self.input,
-                            ErrorKind::LengthValue,
-                        ))));
+                    if self.input.offset(next_input) != entity_size {
+                        let error = E::from_error_kind(self.input, ErrorKind::LengthValue);
+
+                        // The next symbols can't be located: stop here.
+                        self.input = &[];
+
+                        return Some(Err(Err::Error(error)));
                     }
                 }
 
                 self.input = next_input;
 
-                if let Some(strings_section) = &self.strings_section {
-                    symbol.name = strings_section.data.string_at_offset(symbol.name_offset.into());
+                if let Some(strings) = self.strings {
+                    symbol.name = strings.name_at_offset(symbol.name_offset.into());
                 }
 
                 Some(Ok(symbol))
             }
 
-            Err(err) => Some(Err(err)),
+            Err(err) => {
+                self.input = &[];
+
+                Some(Err(err))
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use super::{
+        super::{DataType, Machine},
+        *,
+    };
 
     #[test]
     fn test_symbol() {
@@ -330,6 +451,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_symbol_predicates() {
+        let symbol = Symbol {
+            name: None,
+            name_offset: Address(1),
+            binding: SymbolBinding::Global,
+            r#type: SymbolType::Function,
+            section_index_where_symbol_is_defined: SectionIndex::Ok(2),
+            value: Address(7),
+            size: 1,
+        };
+
+        assert!(symbol.is_defined());
+        assert!(!symbol.is_undefined());
+        assert!(symbol.is_function());
+        assert!(!symbol.is_object());
+        assert!(!symbol.is_local());
+        assert!(symbol.is_global());
+        assert!(!symbol.is_weak());
+
+        let symbol = Symbol {
+            binding: SymbolBinding::Weak,
+            r#type: SymbolType::Object,
+            section_index_where_symbol_is_defined: SectionIndex::Undefined,
+            ..symbol
+        };
+
+        assert!(!symbol.is_defined());
+        assert!(symbol.is_undefined());
+        assert!(!symbol.is_function());
+        assert!(symbol.is_object());
+        assert!(!symbol.is_global());
+        assert!(symbol.is_weak());
+
+        let symbol = Symbol {
+            binding: SymbolBinding::Local,
+            section_index_where_symbol_is_defined: SectionIndex::Absolute,
+            ..symbol
+        };
+
+        assert!(symbol.is_defined());
+        assert!(symbol.is_local());
+    }
+
     #[test]
     fn test_symbol_binding() {
         macro_rules! test {
@@ -350,8 +515,8 @@ mod tests {
             0x01 => SymbolBinding::Global,
             0x02 => SymbolBinding::Weak,
             0x0a => SymbolBinding::LowEnvironmentSpecific,
-            0x0c => SymbolBinding::HighProcessorSpecific,
-            0x0d => SymbolBinding::LowEnvironmentSpecific,
+            0x0c => SymbolBinding::HighEnvironmentSpecific,
+            0x0d => SymbolBinding::LowProcessorSpecific,
             0x0f => SymbolBinding::HighProcessorSpecific,
         );
     }
@@ -376,13 +541,67 @@ mod tests {
             0x02 => SymbolType::Function,
             0x03 => SymbolType::Section,
             0x04 => SymbolType::File,
+            0x05 => SymbolType::Common,
+            0x06 => SymbolType::ThreadLocalStorage,
             0x0a => SymbolType::LowEnvironmentSpecific,
-            0x0c => SymbolType::HighProcessorSpecific,
-            0x0d => SymbolType::LowEnvironmentSpecific,
+            0x0c => SymbolType::HighEnvironmentSpecific,
+            0x0d => SymbolType::LowProcessorSpecific,
             0x0f => SymbolType::HighProcessorSpecific,
         );
     }
 
+    #[test]
+    fn test_symbol_binding_and_type_round_trip() {
+        let bindings = [
+            SymbolBinding::Local,
+            SymbolBinding::Global,
+            SymbolBinding::Weak,
+            SymbolBinding::LowEnvironmentSpecific,
+            SymbolBinding::HighEnvironmentSpecific,
+            SymbolBinding::LowProcessorSpecific,
+            SymbolBinding::HighProcessorSpecific,
+        ];
+        let types = [
+            SymbolType::NoType,
+            SymbolType::Object,
+            SymbolType::Function,
+            SymbolType::Section,
+            SymbolType::File,
+            SymbolType::Common,
+            SymbolType::ThreadLocalStorage,
+            SymbolType::LowEnvironmentSpecific,
+            SymbolType::HighEnvironmentSpecific,
+            SymbolType::LowProcessorSpecific,
+            SymbolType::HighProcessorSpecific,
+        ];
+
+        for binding in bindings {
+            for r#type in types {
+                let symbol = Symbol {
+                    name: None,
+                    name_offset: Address(1),
+                    r#type,
+                    binding,
+                    section_index_where_symbol_is_defined: SectionIndex::Ok(2),
+                    value: Address(7),
+                    size: 1,
+                };
+
+                let mut buffer = Vec::new();
+                symbol.write::<crate::LittleEndian, _>(&mut buffer).unwrap();
+
+                // Binding and type are packed in the same byte.
+                assert_eq!(buffer[4], ((binding as u8) << 4) | r#type as u8);
+
+                let (remaining, read_symbol) =
+                    Symbol::read::<crate::LittleEndian, ()>(&buffer).unwrap();
+
+                assert!(remaining.is_empty());
+                assert_eq!(read_symbol, symbol, "{binding:?} + {type:?}", type = r#type);
+            }
+        }
+    }
+
     #[test]
     fn test_symbol_iterator() {
         #[rustfmt::skip]
@@ -458,4 +677,116 @@ mod tests {
             assert_eq!(iterator.next(), None);
         }
     }
+
+    #[test]
+    fn test_symbol_iterator_stops_at_error() {
+        let input: &[u8] = &[0x00; 24];
+
+        // The entity size doesn't match the size of a symbol.
+        let mut iterator =
+            SymbolIterator::<()>::new(input, Endianness::Big, NonZeroU64::new(23), None);
+
+        assert_eq!(iterator.next(), Some(Err(Err::Error(()))));
+        assert_eq!(iterator.next(), None);
+
+        // The last symbol is truncated.
+        let mut iterator = SymbolIterator::<()>::new(&input[..20], Endianness::Big, None, None);
+
+        assert_eq!(iterator.next(), Some(Err(Err::Error(()))));
+        assert_eq!(iterator.next(), None);
+    }
+
+    #[test]
+    fn test_symbol_iterator_with_no_name() {
+        #[rustfmt::skip]
+        let input: &[u8] = &[
+            // Symbol 1, unnamed.
+
+            // Name offset.
+            0x00, 0x00, 0x00, 0x00,
+            // Binding + type.
+            0x03,
+            // (other).
+            0x00,
+            // Section index.
+            0x00, 0x01,
+            // Value.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // Size.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+
+            // Symbol 2, named.
+
+            // Name offset.
+            0x00, 0x00, 0x00, 0x01,
+            // Binding + type.
+            0x12,
+            // (other).
+            0x00,
+            // Section index.
+            0x00, 0x01,
+            // Value.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // Size.
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let strings = Data::new(
+            Cow::Borrowed(b"\0foo\0"),
+            DataType::StringTable,
+            Endianness::Big,
+            Machine::X86_64,
+            None,
+        );
+
+        let names = SymbolIterator::<()>::new(input, Endianness::Big, None, Some(&strings))
+            .map(|symbol| symbol.unwrap().name)
+            .collect::<Vec<_>>();
+
+        // The offset 0 means there is no name, not the empty string.
+        assert_eq!(names, [None, Some(Cow::Borrowed(BStr::new("foo")))]);
+    }
+
+    #[test]
+    fn test_symbol_iterator_over_a_large_symbol_table() {
+        const SYMBOL_COUNT: usize = 100_000;
+
+        let symbols = (0..SYMBOL_COUNT)
+            .map(|index| Symbol {
+                name: None,
+                name_offset: Address(index as u64),
+                binding: if index % 2 == 0 { SymbolBinding::Global } else { SymbolBinding::Local },
+                r#type: SymbolType::Function,
+                section_index_where_symbol_is_defined: SectionIndex::Ok(index % 7 + 1),
+                value: Address(index as u64 * 16),
+                size: 16,
+            })
+            .collect::<Vec<_>>();
+
+        for endianness in [Endianness::Big, Endianness::Little] {
+            let mut input = Vec::with_capacity(SYMBOL_COUNT * Symbol::SIZE);
+
+            for symbol in &symbols {
+                match endianness {
+                    Endianness::Big => symbol.write::<BigEndian, _>(&mut input).unwrap(),
+                    Endianness::Little => symbol.write::<LittleEndian, _>(&mut input).unwrap(),
+                }
+            }
+
+            // The endianness is matched once, to pick the iterator specialised
+            // for it.
+            let iterator = SymbolIterator::<()>::new(&input, endianness, NonZeroU64::new(24), None);
+
+            match (endianness, &iterator) {
+                (Endianness::Big, SymbolIterator::Big(_))
+                | (Endianness::Little, SymbolIterator::Little(_)) => {}
+                _ => panic!("The iterator doesn't match the `{endianness:?}` endianness"),
+            }
+
+            assert_eq!(
+                iterator.collect::<StdResult<Vec<_>, _>>().unwrap(),
+                symbols,
+                "{endianness:?}"
+            );
+        }
+    }
 }
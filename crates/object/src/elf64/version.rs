@@ -0,0 +1,411 @@
+use std::{borrow::Cow, marker::PhantomData, result::Result as StdResult};
+
+use bstr::BStr;
+
+use super::{Address, Section};
+use crate::{combinators::*, BigEndian, Endianness, Input, LittleEndian, Number, Read, Result};
+
+/// A symbol version, i.e. an entry of the `.gnu.version` section.
+///
+/// The `.gnu.version` section is parallel to the dynamic symbol table: the
+/// n-th entry holds the version of the n-th symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionSymbol {
+    /// The version index. `0` means the symbol is local, `1` means the symbol
+    /// is global and unversioned, other values refer to a version definition
+    /// or to a version requirement (see [`VersionNeedAuxiliary::version`]).
+    pub index: u16,
+    /// Whether the symbol is hidden, i.e. it cannot be referenced by another
+    /// object without naming its version explicitly.
+    pub hidden: bool,
+}
+
+impl VersionSymbol {
+    /// Size, in bytes, of a symbol version.
+    pub const SIZE: usize = 2;
+
+    /// Bit of the raw value marking the symbol as hidden.
+    const HIDDEN: u16 = 0x8000;
+}
+
+impl Read for VersionSymbol {
+    fn read<'r, N, E>(input: Input<'r>) -> Result<'r, Self, E>
+    where
+        N: Number,
+        E: ParseError<Input<'r>>,
+    {
+        let (input, value) = N::read_u16(input)?;
+
+        Ok((input, Self { index: value & !Self::HIDDEN, hidden: value & Self::HIDDEN != 0 }))
+    }
+}
+
+/// An iterator producing [`VersionSymbol`]s.
+pub struct VersionSymbolIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    input: Input<'a>,
+    endianness: Endianness,
+    _phantom: PhantomData<E>,
+}
+
+impl<'a, E> VersionSymbolIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    pub(super) fn new(input: Input<'a>, endianness: Endianness) -> Self {
+        Self { input, endianness, _phantom: PhantomData }
+    }
+}
+
+impl<'a, E> Iterator for VersionSymbolIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    type Item = StdResult<VersionSymbol, Err<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.input.is_empty() {
+            return None;
+        }
+
+        let read = match self.endianness {
+            Endianness::Big => VersionSymbol::read::<BigEndian, E>(self.input),
+            Endianness::Little => VersionSymbol::read::<LittleEndian, E>(self.input),
+        };
+
+        match read {
+            Ok((next_input, version_symbol)) => {
+                self.input = next_input;
+
+                Some(Ok(version_symbol))
+            }
+
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// A version requirement, i.e. an entry of the `.gnu.version_r` section.
+///
+/// It describes the versions needed from one shared object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionNeed<'a> {
+    /// The version of the structure itself. It is always `1`.
+    pub version: u16,
+    /// Name of the shared object, if any.
+    pub file: Option<Cow<'a, BStr>>,
+    /// An offset, in bytes, to the name of the shared object, relative to the
+    /// start of the associated string table (usually `.dynstr`).
+    pub file_offset: Address,
+    /// The versions needed from the shared object.
+    pub auxiliaries: Vec<VersionNeedAuxiliary<'a>>,
+}
+
+/// A version needed from a shared object, see [`VersionNeed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionNeedAuxiliary<'a> {
+    /// Hash of the version name.
+    pub hash: u32,
+    /// Version flags, e.g. `0x2` for a weak version.
+    pub flags: u16,
+    /// The version index, as used by [`VersionSymbol::index`].
+    pub version: u16,
+    /// Name of the version, e.g. `GLIBC_2.2.5`, if any.
+    pub name: Option<Cow<'a, BStr>>,
+    /// An offset, in bytes, to the name of the version, relative to the start
+    /// of the associated string table (usually `.dynstr`).
+    pub name_offset: Address,
+}
+
+/// Read a version requirement header, and return the offsets to its first
+/// auxiliary entry and to the next version requirement, along with the number
+/// of auxiliary entries.
+fn read_version_need<'r, N, E>(
+    input: Input<'r>,
+) -> Result<'r, (VersionNeed<'r>, u16, usize, usize), E>
+where
+    N: Number,
+    E: ParseError<Input<'r>>,
+{
+    let (input, (version, count, file_offset, auxiliary_offset, next_offset)) = tuple((
+        N::read_u16,
+        N::read_u16,
+        <Address as Read<u32>>::read::<N, _>,
+        N::read_u32,
+        N::read_u32,
+    ))(input)?;
+
+    Ok((
+        input,
+        (
+            VersionNeed { version, file: None, file_offset, auxiliaries: Vec::new() },
+            count,
+            auxiliary_offset as usize,
+            next_offset as usize,
+        ),
+    ))
+}
+
+/// Read a version requirement auxiliary entry, and return the offset to the
+/// next auxiliary entry.
+fn read_version_need_auxiliary<'r, N, E>(
+    input: Input<'r>,
+) -> Result<'r, (VersionNeedAuxiliary<'r>, usize), E>
+where
+    N: Number,
+    E: ParseError<Input<'r>>,
+{
+    let (input, (hash, flags, version, name_offset, next_offset)) = tuple((
+        N::read_u32,
+        N::read_u16,
+        N::read_u16,
+        <Address as Read<u32>>::read::<N, _>,
+        N::read_u32,
+    ))(input)?;
+
+    Ok((
+        input,
+        (
+            VersionNeedAuxiliary { hash, flags, version, name: None, name_offset },
+            next_offset as usize,
+        ),
+    ))
+}
+
+/// An iterator producing [`VersionNeed`]s.
+///
+/// Entries are linked by offsets: the iterator follows them until an entry
+/// has no successor.
+pub struct VersionNeedIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    input: Input<'a>,
+    /// Offset of the next entry, if any.
+    offset: Option<usize>,
+    endianness: Endianness,
+    strings_section: Option<&'a Section<'a>>,
+    _phantom: PhantomData<E>,
+}
+
+impl<'a, E> VersionNeedIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    pub(super) fn new(
+        input: Input<'a>,
+        endianness: Endianness,
+        strings_section: Option<&'a Section<'a>>,
+    ) -> Self {
+        Self {
+            input,
+            offset: (!input.is_empty()).then_some(0),
+            endianness,
+            strings_section,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Read the version requirement at `offset`, along with its auxiliary
+    /// entries, and return the offset of the next version requirement.
+    fn read_at<N>(&self, offset: usize) -> StdResult<(VersionNeed<'a>, Option<usize>), Err<E>>
+    where
+        N: Number,
+    {
+        let input = self.input.get(offset..).unwrap_or_default();
+        let (_, (mut version_need, count, auxiliary_offset, next_offset)) =
+            read_version_need::<N, E>(input)?;
+
+        if let Some(strings_section) = &self.strings_section {
+            version_need.file =
+                strings_section.data.string_at_offset(version_need.file_offset.into());
+        }
+
+        let mut auxiliary_offset = offset + auxiliary_offset;
+
+        for _ in 0..count {
+            let input = self.input.get(auxiliary_offset..).unwrap_or_default();
+            let (_, (mut auxiliary, next_auxiliary_offset)) =
+                read_version_need_auxiliary::<N, E>(input)?;
+
+            if let Some(strings_section) = &self.strings_section {
+                auxiliary.name =
+                    strings_section.data.string_at_offset(auxiliary.name_offset.into());
+            }
+
+            version_need.auxiliaries.push(auxiliary);
+            auxiliary_offset += next_auxiliary_offset;
+        }
+
+        Ok((version_need, (next_offset != 0).then_some(offset + next_offset)))
+    }
+}
+
+impl<'a, E> Iterator for VersionNeedIterator<'a, E>
+where
+    E: ParseError<Input<'a>>,
+{
+    type Item = StdResult<VersionNeed<'a>, Err<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset.take()?;
+
+        let read = match self.endianness {
+            Endianness::Big => self.read_at::<BigEndian>(offset),
+            Endianness::Little => self.read_at::<LittleEndian>(offset),
+        };
+
+        match read {
+            Ok((version_need, next_offset)) => {
+                self.offset = next_offset;
+
+                Some(Ok(version_need))
+            }
+
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elf64::{
+        Alignment, Data, DataType, Machine, SectionFlags, SectionIndex, SectionType,
+    };
+
+    #[test]
+    fn test_version_symbols() {
+        #[rustfmt::skip]
+        let data = Data::new(
+            Cow::Borrowed(&[
+                // Symbol 0: local.
+                0x00, 0x00,
+                // Symbol 1: global.
+                0x01, 0x00,
+                // Symbol 2: version 2.
+                0x02, 0x00,
+                // Symbol 3: version 3, hidden.
+                0x03, 0x80,
+            ]),
+            DataType::VersionSymbolTable,
+            Endianness::Little,
+            Machine::X86_64,
+            None,
+        );
+
+        assert_eq!(
+            data.version_symbols::<()>()
+                .unwrap()
+                .map(|version_symbol| version_symbol.unwrap())
+                .collect::<Vec<_>>(),
+            &[
+                VersionSymbol { index: 0, hidden: false },
+                VersionSymbol { index: 1, hidden: false },
+                VersionSymbol { index: 2, hidden: false },
+                VersionSymbol { index: 3, hidden: true },
+            ],
+        );
+    }
+
+    #[test]
+    fn test_version_needs() {
+        #[rustfmt::skip]
+        let data = Data::new(
+            Cow::Borrowed(&[
+                // Version need 0.
+                // Version.
+                0x01, 0x00,
+                // Count.
+                0x02, 0x00,
+                // File offset.
+                0x01, 0x00, 0x00, 0x00,
+                // Auxiliary offset.
+                0x10, 0x00, 0x00, 0x00,
+                // Next offset.
+                0x00, 0x00, 0x00, 0x00,
+
+                // Auxiliary 0.
+                // Hash.
+                0x75, 0x1a, 0x69, 0x09,
+                // Flags.
+                0x00, 0x00,
+                // Version index.
+                0x02, 0x00,
+                // Name offset.
+                0x0b, 0x00, 0x00, 0x00,
+                // Next offset.
+                0x10, 0x00, 0x00, 0x00,
+
+                // Auxiliary 1.
+                // Hash.
+                0x14, 0x69, 0x69, 0x0d,
+                // Flags.
+                0x02, 0x00,
+                // Version index.
+                0x03, 0x00,
+                // Name offset.
+                0x17, 0x00, 0x00, 0x00,
+                // Next offset.
+                0x00, 0x00, 0x00, 0x00,
+            ]),
+            DataType::VersionNeedTable,
+            Endianness::Little,
+            Machine::X86_64,
+            None,
+        );
+        let strings_section = Section {
+            name: None,
+            name_offset: Address(0),
+            r#type: SectionType::StringTable,
+            flags: SectionFlags::empty(),
+            virtual_address: Address(0),
+            offset: Address(0),
+            segment_size_in_file_image: Address(0),
+            link: SectionIndex::Undefined,
+            information: 0,
+            alignment: Alignment::new(0).unwrap(),
+            entity_size: None,
+            data: Data::new(
+                Cow::Borrowed(b"\0libc.so.6\0GLIBC_2.2.5\0GLIBC_2.34\0"),
+                DataType::StringTable,
+                Endianness::Little,
+                Machine::X86_64,
+                None,
+            ),
+        };
+
+        let version_needs = data
+            .version_needs::<()>(Some(&strings_section))
+            .unwrap()
+            .collect::<StdResult<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            version_needs,
+            &[VersionNeed {
+                version: 1,
+                file: Some(Cow::Borrowed(BStr::new("libc.so.6"))),
+                file_offset: Address(1),
+                auxiliaries: vec![
+                    VersionNeedAuxiliary {
+                        hash: 0x0969_1a75,
+                        flags: 0,
+                        version: 2,
+                        name: Some(Cow::Borrowed(BStr::new("GLIBC_2.2.5"))),
+                        name_offset: Address(0x0b),
+                    },
+                    VersionNeedAuxiliary {
+                        hash: 0x0d69_6914,
+                        flags: 2,
+                        version: 3,
+                        name: Some(Cow::Borrowed(BStr::new("GLIBC_2.34"))),
+                        name_offset: Address(0x17),
+                    },
+                ],
+            }],
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use nom::number::complete::{be_u16, be_u32, be_u64, be_u8, le_u16, le_u32, le_u64, le_u8};
+use nom::number::complete::{
+    be_u128, be_u16, be_u32, be_u64, be_u8, le_u128, le_u16, le_u32, le_u64, le_u8,
+};
 
 use crate::{combinators::*, Input, Result};
 
@@ -36,6 +38,11 @@ pub trait Number {
     where
         E: ParseError<Input<'a>>;
 
+    /// Read a `u128`.
+    fn read_u128<'a, E>(input: Input<'a>) -> Result<'a, u128, E>
+    where
+        E: ParseError<Input<'a>>;
+
     /// Write a `u8`.
     fn write_u8(n: u8) -> [u8; 1];
 
@@ -47,10 +54,14 @@ pub trait Number {
 
     /// Write a `u64`.
     fn write_u64(n: u64) -> [u8; 8];
+
+    /// Write a `u128`.
+    fn write_u128(n: u128) -> [u8; 16];
 }
 
 /// Type that implements [`Number`], which manipulates various little-endian
 /// numbers.
+#[derive(Debug)]
 pub struct LittleEndian;
 
 impl Number for LittleEndian {
@@ -86,6 +97,13 @@ impl Number for LittleEndian {
         le_u64(input)
     }
 
+    fn read_u128<'a, E>(input: Input<'a>) -> Result<'a, u128, E>
+    where
+        E: ParseError<Input<'a>>,
+    {
+        le_u128(input)
+    }
+
     fn write_u8(n: u8) -> [u8; 1] {
         n.to_le_bytes()
     }
@@ -101,10 +119,15 @@ impl Number for LittleEndian {
     fn write_u64(n: u64) -> [u8; 8] {
         n.to_le_bytes()
     }
+
+    fn write_u128(n: u128) -> [u8; 16] {
+        n.to_le_bytes()
+    }
 }
 
 /// Type that implements [`Number`], which manipulates various big-endian
 /// numbers.
+#[derive(Debug)]
 pub struct BigEndian;
 
 impl Number for BigEndian {
@@ -140,6 +163,13 @@ impl Number for BigEndian {
         be_u64(input)
     }
 
+    fn read_u128<'a, E>(input: Input<'a>) -> Result<'a, u128, E>
+    where
+        E: ParseError<Input<'a>>,
+    {
+        be_u128(input)
+    }
+
     fn write_u8(n: u8) -> [u8; 1] {
         n.to_be_bytes()
     }
@@ -155,6 +185,10 @@ impl Number for BigEndian {
     fn write_u64(n: u64) -> [u8; 8] {
         n.to_be_bytes()
     }
+
+    fn write_u128(n: u128) -> [u8; 16] {
+        n.to_be_bytes()
+    }
 }
 
 #[cfg(test)]
@@ -168,6 +202,7 @@ mod tests {
         assert_eq!(LittleEndian::read_u16::<()>(&42u16.to_le_bytes()), Ok((&[] as &[u8], 42)));
         assert_eq!(LittleEndian::read_u32::<()>(&42u32.to_le_bytes()), Ok((&[] as &[u8], 42)));
         assert_eq!(LittleEndian::read_u64::<()>(&42u64.to_le_bytes()), Ok((&[] as &[u8], 42)));
+        assert_eq!(LittleEndian::read_u128::<()>(&42u128.to_le_bytes()), Ok((&[] as &[u8], 42)));
     }
 
     #[test]
@@ -177,5 +212,25 @@ mod tests {
         assert_eq!(BigEndian::read_u16::<()>(&42u16.to_be_bytes()), Ok((&[] as &[u8], 42)));
         assert_eq!(BigEndian::read_u32::<()>(&42u32.to_be_bytes()), Ok((&[] as &[u8], 42)));
         assert_eq!(BigEndian::read_u64::<()>(&42u64.to_be_bytes()), Ok((&[] as &[u8], 42)));
+        assert_eq!(BigEndian::read_u128::<()>(&42u128.to_be_bytes()), Ok((&[] as &[u8], 42)));
+    }
+
+    #[test]
+    fn test_u128_round_trip() {
+        let n = 0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10u128;
+
+        assert_eq!(LittleEndian::write_u128(n), n.to_le_bytes());
+        assert_eq!(BigEndian::write_u128(n), n.to_be_bytes());
+        assert_eq!(
+            LittleEndian::read_u128::<()>(&LittleEndian::write_u128(n)),
+            Ok((&[] as &[u8], n))
+        );
+        assert_eq!(BigEndian::read_u128::<()>(&BigEndian::write_u128(n)), Ok((&[] as &[u8], n)));
+
+        // Both byte orders are the reverse of each other.
+        let mut bytes = LittleEndian::write_u128(n);
+        bytes.reverse();
+
+        assert_eq!(bytes, BigEndian::write_u128(n));
     }
 }
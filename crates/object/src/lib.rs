@@ -8,6 +8,9 @@
 #[macro_use]
 mod test;
 
+pub mod archive;
+#[cfg(feature = "arena")]
+pub mod arena;
 mod combinators;
 #[cfg(feature = "elf64")]
 pub mod elf64;
@@ -23,6 +26,26 @@ pub type Input<'a> = &'a [u8];
 /// Represent the result returned by the parsers.
 pub type Result<'a, O, E> = nom::IResult<Input<'a>, O, E>;
 
+/// The most common imports, to read and to write object files.
+///
+/// ```rust
+/// use weld_object::prelude::*;
+///
+/// # fn main() {
+/// let input: &[u8] = include_bytes!("../tests/fixtures/exit_elf_amd64.o");
+/// let (_, file) = File::read_resolved::<()>(input).unwrap();
+///
+/// let text: &Section = file.section_by_name(".text").unwrap();
+/// assert!(!text.data.is_empty());
+/// assert!(file.symbols().any(|symbol: Symbol| symbol.name.is_some()));
+/// # }
+/// ```
+pub mod prelude {
+    #[cfg(feature = "elf64")]
+    pub use crate::elf64::{File, Program, Section, Symbol};
+    pub use crate::{BigEndian, LittleEndian, Number, Read, Write};
+}
+
 /// Errors used by the crate.
 pub mod errors {
     pub use nom::Err as Error;
@@ -31,5 +54,102 @@ pub mod errors {
     /// multiple errors, but stores just one.
     pub type SingleError<'a> = nom::error::Error<super::Input<'a>>;
 
+    use std::fmt;
+
     pub use nom::error::ErrorKind;
+    use nom::error::{FromExternalError, ParseError};
+
+    use super::Input;
+
+    /// A malformed header: the identification of the file header, or a
+    /// program header or a section header, identified by its index.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum InvalidHeader {
+        /// The file doesn't start with the ELF magic number: it isn't an ELF
+        /// file at all.
+        NotAnElfFile {
+            /// The first bytes of the file.
+            first_bytes: [u8; 4],
+            /// The number of first bytes, less than 4 if the file is shorter.
+            length: usize,
+        },
+        /// The class of the file isn't 64-bit.
+        UnsupportedClass {
+            /// The class of the file, e.g. `1` for 32-bit.
+            class: u8,
+        },
+        /// The alignment of a program header isn't a power of two.
+        ProgramAlignment {
+            /// Index of the program header.
+            index: usize,
+            /// The invalid alignment.
+            alignment: u64,
+        },
+        /// The alignment of a section header isn't a power of two.
+        SectionAlignment {
+            /// Index of the section header.
+            index: usize,
+            /// The invalid alignment.
+            alignment: u64,
+        },
+    }
+
+    impl fmt::Display for InvalidHeader {
+        fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::NotAnElfFile { length: 0, .. } => {
+                    write!(formatter, "the file is empty, it doesn't start with the ELF magic number")
+                }
+                Self::NotAnElfFile { first_bytes, length } => write!(
+                    formatter,
+                    "the file starts with `{}` instead of the ELF magic number `\\x7fELF`",
+                    first_bytes[..*length].escape_ascii()
+                ),
+                Self::UnsupportedClass { class: 1 } => write!(
+                    formatter,
+                    "the file is a 32-bit ELF file, but only 64-bit ELF files are supported"
+                ),
+                Self::UnsupportedClass { class } => write!(
+                    formatter,
+                    "the class of the file, `{class}`, is unknown, but only 64-bit ELF files are supported"
+                ),
+                Self::ProgramAlignment { index, alignment } => write!(
+                    formatter,
+                    "the alignment of the program header #{index}, `{alignment}`, is not a power of two"
+                ),
+                Self::SectionAlignment { index, alignment } => write!(
+                    formatter,
+                    "the alignment of the section header #{index}, `{alignment}`, is not a power of two"
+                ),
+            }
+        }
+    }
+
+    /// Represent an error that can be used by parser, like [`SingleError`],
+    /// which also remembers the malformed header, if any.
+    #[derive(Debug, PartialEq)]
+    pub struct FileError<'a> {
+        /// Position of the error in the input.
+        pub input: Input<'a>,
+        /// Kind of the error.
+        pub code: ErrorKind,
+        /// The malformed header, if the error is about one.
+        pub invalid_header: Option<InvalidHeader>,
+    }
+
+    impl<'a> ParseError<Input<'a>> for FileError<'a> {
+        fn from_error_kind(input: Input<'a>, code: ErrorKind) -> Self {
+            Self { input, code, invalid_header: None }
+        }
+
+        fn append(_input: Input<'a>, _code: ErrorKind, other: Self) -> Self {
+            other
+        }
+    }
+
+    impl<'a> FromExternalError<Input<'a>, InvalidHeader> for FileError<'a> {
+        fn from_external_error(input: Input<'a>, code: ErrorKind, error: InvalidHeader) -> Self {
+            Self { input, code, invalid_header: Some(error) }
+        }
+    }
 }
@@ -1,3 +1,8 @@
+use weld_counting_allocator::CountingAllocator;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
 macro_rules! assert_read_write {
     (
         $subject:ty : Read< $reader_read_from:ty > + Write< $writer_read_from:ty > {
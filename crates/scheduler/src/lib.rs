@@ -18,6 +18,15 @@
 //! `Future`s because at some points there were all pending, and suddently there
 //! is a lot more jobs to do. In practise, this case happens rarely.
 //!
+//! Jobs have a [`Priority`]. There is one channel per priority, and idle
+//! `Worker`s receive from the channel of [`Priority::High`] first, see
+//! [`ThreadPool::execute_with_priority`]. A job already received by a `Worker`
+//! is never preempted though.
+//!
+//! The executor of each `Worker` is [`SmolExecutor`] by default. Another one
+//! can be used with [`ThreadPool::with_executor`], by implementing the
+//! [`Executor`] trait, e.g. to reuse the runtime of an application.
+//!
 //! This `ThreadPool` design does not aim to be general and performant in all
 //! case. It's tailored for the needs of this project only. The major constraint
 //! was having something simple.
@@ -60,9 +69,9 @@ use std::{
     thread::{self, JoinHandle},
 };
 
-use async_channel::{unbounded, Receiver, SendError, Sender};
-use async_executor::Executor;
-use futures_lite::future::block_on;
+use async_channel::{unbounded, Receiver, RecvError, SendError, Sender};
+use async_executor::Executor as AsyncExecutor;
+use futures_lite::future::{block_on, or};
 
 /// A thread pool allows to execute `Future`s on multiple threads automatically.
 ///
@@ -125,8 +134,20 @@ use futures_lite::future::block_on;
 /// ```
 pub struct ThreadPool<'e, T> {
     _workers: Vec<Worker>,
-    executor: Executor<'e>,
-    sender: Sender<Job<T>>,
+    executor: AsyncExecutor<'e>,
+    high_sender: Sender<Job<T>>,
+    normal_sender: Sender<Job<T>>,
+}
+
+/// The priority of a job, see [`ThreadPool::execute_with_priority`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Priority {
+    /// The job is received by a `Worker` in the order it has been sent.
+    #[default]
+    Normal,
+    /// The job is received by an idle `Worker` before any job of
+    /// [`Priority::Normal`].
+    High,
 }
 
 impl<'e, T> ThreadPool<'e, T>
@@ -153,25 +174,100 @@ where
     /// Thus, `desired_pool_size` is clamped between 1 and
     /// [`std::thread::available_parallelism`].
     pub fn new(desired_pool_size: NonZeroUsize) -> Result<Self, io::Error> {
+        Self::with_executor(desired_pool_size, SmolExecutor::default)
+    }
+
+    /// Create a new pool of threads, like [`Self::new`], where each `Worker`
+    /// uses the executor created by `new_executor`.
+    pub fn with_executor<E, F>(
+        desired_pool_size: NonZeroUsize,
+        new_executor: F,
+    ) -> Result<Self, io::Error>
+    where
+        E: Executor,
+        F: Fn() -> E,
+    {
         let pool_size = cmp::min(desired_pool_size, thread::available_parallelism()?).get();
 
         let mut workers = Vec::with_capacity(pool_size);
 
-        let (sender, receiver) = unbounded::<Job<T>>();
+        let (high_sender, high_receiver) = unbounded::<Job<T>>();
+        let (normal_sender, normal_receiver) = unbounded::<Job<T>>();
 
         for nth in 0..pool_size {
-            workers.push(Worker::new(nth, receiver.clone())?);
+            workers.push(Worker::new(
+                nth,
+                high_receiver.clone(),
+                normal_receiver.clone(),
+                new_executor(),
+            )?);
         }
 
-        Ok(Self { _workers: workers, executor: Executor::new(), sender })
+        Ok(Self { _workers: workers, executor: AsyncExecutor::new(), high_sender, normal_sender })
     }
 
-    /// Execute a `Future` onto a thread that can accept it.
+    /// Execute a `Future` onto a thread that can accept it, with
+    /// [`Priority::Normal`].
     pub fn execute<F>(&self, work: F) -> Result<(), SendError<Job<T>>>
     where
         F: Future<Output = T> + Send + 'static,
     {
-        block_on(self.executor.run(self.sender.send(Box::pin(work))))
+        self.execute_with_priority(work, Priority::Normal)
+    }
+
+    /// Execute a `Future` onto a thread that can accept it, with the given
+    /// `priority`.
+    ///
+    /// Jobs of [`Priority::High`] that are waiting to be received are
+    /// preferred by idle `Worker`s over jobs of [`Priority::Normal`].
+    pub fn execute_with_priority<F>(
+        &self,
+        work: F,
+        priority: Priority,
+    ) -> Result<(), SendError<Job<T>>>
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let sender = match priority {
+            Priority::High => &self.high_sender,
+            Priority::Normal => &self.normal_sender,
+        };
+
+        block_on(self.executor.run(sender.send(Box::pin(work))))
+    }
+}
+
+/// An asynchronous executor, used by a `Worker` to run its jobs.
+pub trait Executor: Send + 'static {
+    /// Spawn a `Future` in the background. Its output is discarded.
+    fn spawn_detached(&self, future: Pin<Box<dyn Future<Output = ()> + Send + 'static>>);
+
+    /// Block the current thread until `future` completes. The spawned
+    /// `Future`s must make progress in the meantime.
+    fn block_on<F>(&self, future: F)
+    where
+        F: Future<Output = ()>,
+    {
+        block_on(future)
+    }
+}
+
+/// The default executor, based on `smol`'s `async-executor`.
+#[derive(Default)]
+pub struct SmolExecutor {
+    executor: AsyncExecutor<'static>,
+}
+
+impl Executor for SmolExecutor {
+    fn spawn_detached(&self, future: Pin<Box<dyn Future<Output = ()> + Send + 'static>>) {
+        self.executor.spawn(future).detach();
+    }
+
+    fn block_on<F>(&self, future: F)
+    where
+        F: Future<Output = ()>,
+    {
+        block_on(self.executor.run(future))
     }
 }
 
@@ -184,29 +280,60 @@ struct Worker {
 type Job<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
 
 impl Worker {
-    fn new<T>(worker_id: usize, receiver: Receiver<Job<T>>) -> Result<Self, io::Error>
+    fn new<T, E>(
+        worker_id: usize,
+        high_receiver: Receiver<Job<T>>,
+        normal_receiver: Receiver<Job<T>>,
+        executor: E,
+    ) -> Result<Self, io::Error>
     where
         T: Send + 'static,
+        E: Executor,
     {
         let thread_handle = thread::Builder::new()
             .name(format!("{prefix}-worker-{worker_id}", prefix = env!("CARGO_PKG_NAME")))
             .spawn(move || {
-                let executor = Executor::new();
-
-                block_on(executor.run(async {
-                    while let Ok(received_future) = receiver.recv().await {
-                        executor.spawn(received_future).detach();
+                executor.block_on(async {
+                    while let Ok(received_future) =
+                        Self::recv(&high_receiver, &normal_receiver).await
+                    {
+                        executor.spawn_detached(Box::pin(async move {
+                            received_future.await;
+                        }));
                     }
-                }))
+                })
             })?;
 
         Ok(Self { _thread_handle: thread_handle })
     }
+
+    /// Receive the next job, from `high_receiver` first.
+    async fn recv<T>(
+        high_receiver: &Receiver<Job<T>>,
+        normal_receiver: &Receiver<Job<T>>,
+    ) -> Result<Job<T>, RecvError> {
+        match or(high_receiver.recv(), normal_receiver.recv()).await {
+            Ok(job) => Ok(job),
+
+            // A channel is closed and empty, i.e. the `ThreadPool` is dropped:
+            // the jobs left in the other channel are still received.
+            Err(_) => match high_receiver.try_recv() {
+                Ok(job) => Ok(job),
+                Err(_) => normal_receiver.recv().await,
+            },
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
 
     use async_io::Timer;
 
@@ -251,4 +378,119 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn priorities() -> Result<(), io::Error> {
+        let desired_pool_size = NonZeroUsize::new(1).unwrap();
+        let thread_pool = ThreadPool::new(desired_pool_size)?;
+
+        // Keep the single `Worker` busy, so that the next jobs wait to be
+        // received.
+        let (started_sender, started_receiver) = std::sync::mpsc::channel::<()>();
+        let (resume_sender, resume_receiver) = std::sync::mpsc::channel::<()>();
+
+        thread_pool
+            .execute(async move {
+                started_sender.send(()).unwrap();
+                resume_receiver.recv().unwrap();
+            })
+            .unwrap();
+
+        started_receiver.recv().unwrap();
+
+        let (sender, receiver) = unbounded::<&'static str>();
+
+        {
+            let sender = sender.clone();
+
+            thread_pool
+                .execute_with_priority(
+                    async move {
+                        sender.send("normal").await.unwrap();
+                        Timer::after(Duration::from_millis(10)).await;
+                    },
+                    Priority::Normal,
+                )
+                .unwrap();
+        }
+
+        {
+            let sender = sender.clone();
+
+            thread_pool
+                .execute_with_priority(
+                    async move {
+                        sender.send("high").await.unwrap();
+                    },
+                    Priority::High,
+                )
+                .unwrap();
+        }
+
+        drop(sender);
+        resume_sender.send(()).unwrap();
+
+        block_on(async {
+            let mut order = Vec::new();
+
+            while let Ok(received) = receiver.recv().await {
+                order.push(received);
+            }
+
+            // The high priority job runs first, even if it has been sent last.
+            assert_eq!(order, ["high", "normal"]);
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn custom_executor() -> Result<(), io::Error> {
+        /// A trivial executor running the futures immediately, and counting
+        /// them.
+        struct CountingExecutor {
+            spawned: Arc<AtomicUsize>,
+        }
+
+        impl Executor for CountingExecutor {
+            fn spawn_detached(&self, future: Pin<Box<dyn Future<Output = ()> + Send + 'static>>) {
+                self.spawned.fetch_add(1, Ordering::SeqCst);
+                block_on(future);
+            }
+        }
+
+        let spawned = Arc::new(AtomicUsize::new(0));
+        let desired_pool_size = NonZeroUsize::new(2).unwrap();
+        let thread_pool = ThreadPool::with_executor(desired_pool_size, || CountingExecutor {
+            spawned: spawned.clone(),
+        })?;
+
+        let (sender, receiver) = unbounded::<u32>();
+
+        for nth in 0..10 {
+            let sender = sender.clone();
+
+            thread_pool
+                .execute(async move {
+                    sender.send(nth).await.unwrap();
+                })
+                .unwrap();
+        }
+
+        drop(sender);
+
+        block_on(async {
+            let mut total = 0;
+
+            while let Ok(received) = receiver.recv().await {
+                total += received;
+            }
+
+            assert_eq!(total, (0..10).sum());
+        });
+
+        assert_eq!(spawned.load(Ordering::SeqCst), 10);
+
+        Ok(())
+    }
 }